@@ -4,6 +4,7 @@ pub mod args;
 
 #[derive(Debug)]
 pub struct Cli {
+    #[allow(dead_code)]
     stdout: std::io::Stdout
 }
 
@@ -0,0 +1,62 @@
+use std::collections::{HashMap, HashSet};
+
+use url::{Position, Url};
+
+use crate::crawler::{crawl_result::CrawlResult, Crawler};
+
+/// How well a crawl covered the pages listed in a sitemap
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageReport {
+    pub coverage_pct: f64,                   // The percentage of sitemap pages crawled successfully, 0 if the sitemap is empty
+    pub missing_from_crawl: Vec<Url>,        // Sitemap pages the crawl never reached
+    pub client_errors: Vec<Url>,             // Sitemap pages which responded with a 4xx status code
+    pub extra_in_crawl: Vec<Url>,            // Crawled pages not listed in the sitemap
+    pub status_breakdown: HashMap<u16, usize> // The number of crawled sitemap pages by status code
+}
+
+impl Crawler {
+    /// Compare the crawled pages with those listed in a sitemap. URLs differing only in their fragment are the same page.
+    pub fn coverage_report(results: &[CrawlResult], sitemap_urls: Vec<Url>) -> CoverageReport {
+        let crawled_pages: HashMap<&str, &CrawlResult> = results
+            .iter()
+            .map(|result| (&result.url[..Position::AfterQuery], result))
+            .collect();
+
+        let mut sitemap_pages: HashSet<String> = HashSet::new();
+        let mut missing_from_crawl = Vec::new();
+        let mut client_errors = Vec::new();
+        let mut status_breakdown: HashMap<u16, usize> = HashMap::new();
+        let mut successful_pages = 0;
+
+        for url in sitemap_urls {
+            if !sitemap_pages.insert(url[..Position::AfterQuery].to_owned()) { continue; }
+
+            match crawled_pages.get(&url[..Position::AfterQuery]) {
+                Some(result) => {
+                    *status_breakdown.entry(result.status_code).or_insert(0) += 1;
+
+                    if result.status_code == 200 {
+                        successful_pages += 1;
+                    } else if (400..500).contains(&result.status_code) {
+                        client_errors.push(url);
+                    }
+                }
+                None => missing_from_crawl.push(url)
+            }
+        }
+
+        let extra_in_crawl = results
+            .iter()
+            .filter(|result| !sitemap_pages.contains(&result.url[..Position::AfterQuery]))
+            .map(|result| result.url.clone())
+            .collect();
+
+        let coverage_pct = if sitemap_pages.is_empty() {
+            0.0
+        } else {
+            successful_pages as f64 / sitemap_pages.len() as f64 * 100.0
+        };
+
+        CoverageReport { coverage_pct, missing_from_crawl, client_errors, extra_in_crawl, status_breakdown }
+    }
+}
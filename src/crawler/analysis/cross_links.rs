@@ -0,0 +1,64 @@
+use std::collections::{HashMap, HashSet};
+
+use url::Url;
+
+use crate::{
+    crawler::{crawl_result::CrawlResult, Crawler},
+    web::host::Host,
+};
+
+/// How the crawl targets of a multi-target crawl link to each other
+#[derive(Debug, Clone, Default)]
+pub struct CrossLinkReport {
+    pub links_between_targets: HashMap<(Host, Host), Vec<(Url, Url)>>, // (source page, linked page) pairs for each (source target, linked target)
+    pub self_linking_targets: Vec<Host>, // Targets that only link to themselves
+    pub hub_pages: Vec<(Url, usize)> // Pages linking to other targets, the most cross-target links first
+}
+
+impl Crawler {
+    /// Analyse which of the crawled targets link to each other.
+    pub fn cross_link_analysis(results: &[CrawlResult]) -> CrossLinkReport {
+        let targets: HashSet<&Host> = results.iter().map(|result| &result.target).collect();
+
+        let mut report = CrossLinkReport::default();
+        let mut internally_linking_targets: HashSet<&Host> = HashSet::new();
+        let mut externally_linking_targets: HashSet<&Host> = HashSet::new();
+
+        for result in results {
+            let mut cross_target_links = 0;
+
+            for link in &result.links {
+                let Some(link_host) = link.host() else { continue; };
+                let Ok(link_host) = Host::try_from(link_host) else { continue; };
+
+                // Only links to other crawled targets are of interest
+                if !targets.contains(&link_host) { continue; }
+
+                if link_host == result.target {
+                    internally_linking_targets.insert(&result.target);
+                    continue;
+                }
+
+                cross_target_links += 1;
+                externally_linking_targets.insert(&result.target);
+                report.links_between_targets
+                    .entry((result.target.clone(), link_host))
+                    .or_default()
+                    .push((result.url.clone(), link.clone()));
+            }
+
+            if cross_target_links > 0 {
+                report.hub_pages.push((result.url.clone(), cross_target_links));
+            }
+        }
+
+        report.self_linking_targets = internally_linking_targets
+            .difference(&externally_linking_targets)
+            .map(|&target| target.clone())
+            .collect();
+
+        report.hub_pages.sort_by(|(_, links1), (_, links2)| links2.cmp(links1));
+
+        report
+    }
+}
@@ -0,0 +1,33 @@
+use url::Url;
+
+use crate::crawler::{crawl_result::CrawlResult, Crawler};
+
+impl Crawler {
+    /// Returns the crawled pages without a meta description, or with an empty one.
+    pub fn find_pages_missing_description(results: &[CrawlResult]) -> Vec<Url> {
+        Self::find_pages_by_description(results, |description| description.is_empty())
+    }
+
+    /// Returns the crawled pages whose meta description has fewer than `min_length` characters.
+    /// Pages without a description are reported by `find_pages_missing_description` instead.
+    pub fn find_pages_with_short_description(results: &[CrawlResult], min_length: usize) -> Vec<Url> {
+        Self::find_pages_by_description(results, |description| {
+            !description.is_empty() && description.chars().count() < min_length
+        })
+    }
+
+    /// Returns the crawled pages whose meta description has more than `max_length` characters,
+    /// and would be truncated in search results.
+    pub fn find_pages_with_long_description(results: &[CrawlResult], max_length: usize) -> Vec<Url> {
+        Self::find_pages_by_description(results, |description| description.chars().count() > max_length)
+    }
+
+    /// Returns the crawled pages whose description matches the predicate, a missing description being empty
+    fn find_pages_by_description(results: &[CrawlResult], predicate: impl Fn(&str) -> bool) -> Vec<Url> {
+        results
+            .iter()
+            .filter(|result| predicate(result.description.as_deref().unwrap_or_default()))
+            .map(|result| result.url.clone())
+            .collect()
+    }
+}
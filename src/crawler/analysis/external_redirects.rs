@@ -0,0 +1,33 @@
+use url::Url;
+
+use crate::crawler::{crawl_result::CrawlResult, redirect, Crawler};
+
+/// A crawled page which redirects to an unrelated domain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalRedirect {
+    pub source: Url,               // The crawled page
+    pub external_destination: Url, // The first URL on an unrelated domain
+    pub intermediate_hops: Vec<Url>
+}
+
+impl Crawler {
+    /// Returns the crawled pages whose redirect chain leads to an unrelated domain,
+    /// whether or not the redirect was followed.
+    pub fn find_redirect_to_external(results: &[CrawlResult]) -> Vec<ExternalRedirect> {
+        results
+            .iter()
+            .filter_map(|result| {
+                let external_hop = result
+                    .redirect_chain
+                    .iter()
+                    .position(|hop| redirect::is_cross_domain(&result.url, hop))?;
+
+                Some(ExternalRedirect {
+                    source: result.url.clone(),
+                    external_destination: result.redirect_chain[external_hop].clone(),
+                    intermediate_hops: result.redirect_chain[..external_hop].to_vec()
+                })
+            })
+            .collect()
+    }
+}
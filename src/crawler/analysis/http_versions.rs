@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+
+use url::Url;
+
+use crate::{
+    crawler::{crawl_result::CrawlResult, Crawler},
+    web::http::HttpVersion,
+};
+
+impl Crawler {
+    /// Count the crawled pages by the HTTP version they were served over.
+    pub fn http_version_distribution(results: &[CrawlResult]) -> HashMap<HttpVersion, usize> {
+        let mut distribution = HashMap::new();
+
+        for result in results {
+            *distribution.entry(result.http_version).or_insert(0) += 1;
+        }
+
+        distribution
+    }
+
+    /// Returns the crawled pages served over a version of HTTP older than HTTP/2.
+    pub fn pages_needing_http_upgrade(results: &[CrawlResult]) -> Vec<Url> {
+        results
+            .iter()
+            .filter(|result| result.http_version.needs_upgrade())
+            .map(|result| result.url.clone())
+            .collect()
+    }
+}
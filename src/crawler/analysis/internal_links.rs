@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use url::Url;
+
+use crate::{
+    crawler::{crawl_result::CrawlResult, Crawler},
+    web::host::Host,
+};
+
+/// The number of pages listed in the most and least linked pages of an audit
+const LINKED_PAGES_COUNT: usize = 10;
+
+/// A report on the internal linking of the crawled pages
+#[derive(Debug, Clone, Default)]
+pub struct InternalLinkAudit {
+    pub pages_with_no_outbound_internal_links: Vec<Url>, // Dead-end pages
+    pub pages_with_no_inbound_internal_links: Vec<Url>,  // Orphan pages
+    pub most_linked_pages: Vec<(Url, usize)>,            // The pages with the most inbound internal links
+    pub least_linked_pages: Vec<(Url, usize)>,           // The pages with the fewest inbound internal links
+    pub average_internal_links_per_page: f64
+}
+
+impl Crawler {
+    /// Audit the internal links between the crawled pages of each target.
+    pub fn audit_internal_links(results: &[CrawlResult]) -> InternalLinkAudit {
+        let mut audit = InternalLinkAudit::default();
+        if results.is_empty() { return audit; }
+
+        let mut inbound_links: HashMap<&str, usize> = results.iter().map(|result| (page_key(&result.url), 0)).collect();
+        let mut total_internal_links = 0;
+
+        for result in results {
+            let mut outbound_internal_links = 0;
+
+            for link in &result.links {
+                let Some(link_host) = link.host() else { continue; };
+                let Ok(link_host) = Host::try_from(link_host) else { continue; };
+                if link_host != result.target { continue; }
+
+                outbound_internal_links += 1;
+
+                // Links from a page to itself do not make it reachable
+                let link_key = page_key(link);
+                if link_key == page_key(&result.url) { continue; }
+
+                if let Some(inbound) = inbound_links.get_mut(link_key) {
+                    *inbound += 1;
+                }
+            }
+
+            if outbound_internal_links == 0 {
+                audit.pages_with_no_outbound_internal_links.push(result.url.clone());
+            }
+
+            total_internal_links += outbound_internal_links;
+        }
+
+        let mut linked_pages: Vec<(Url, usize)> = results
+            .iter()
+            .map(|result| (result.url.clone(), inbound_links[page_key(&result.url)]))
+            .collect();
+        linked_pages.sort_by(|(url1, links1), (url2, links2)| links2.cmp(links1).then_with(|| url1.cmp(url2)));
+
+        audit.pages_with_no_inbound_internal_links = linked_pages
+            .iter()
+            .filter(|(_, links)| *links == 0)
+            .map(|(url, _)| url.clone())
+            .collect();
+        audit.most_linked_pages = linked_pages.iter().take(LINKED_PAGES_COUNT).cloned().collect();
+        audit.least_linked_pages = linked_pages.iter().rev().take(LINKED_PAGES_COUNT).cloned().collect();
+        audit.average_internal_links_per_page = total_internal_links as f64 / results.len() as f64;
+
+        audit
+    }
+}
+
+/// Returns the URL without its fragment, which does not identify a separate page
+fn page_key(url: &Url) -> &str {
+    match url.as_str().split_once('#') {
+        Some((page, _)) => page,
+        None => url.as_str()
+    }
+}
@@ -0,0 +1,51 @@
+use url::Url;
+
+use crate::crawler::{crawl_result::CrawlResult, Crawler};
+
+/// Path segments of URLs which are likely login pages
+const LOGIN_PATHS: [&str; 3] = ["/login", "/signin", "/auth"];
+
+/// Phrases in the titles of pages which likely require logging in
+const LOGIN_TITLE_PHRASES: [&str; 3] = ["sign in", "log in", "authentication required"];
+
+/// Why a crawled page seems to require logging in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoginWallIndicator {
+    HttpAuth,              // The page responded with 401 Unauthorized or 403 Forbidden
+    RedirectToLogin(Url),  // The page redirected to a login page
+    ContentIndicator,      // The page's title asks to log in
+}
+
+impl Crawler {
+    /// Returns the crawled pages which seem to require logging in, and why.
+    /// Many such pages on a target suggest that the crawl needs to be authenticated.
+    pub fn detect_login_walls(results: &[CrawlResult]) -> Vec<(Url, LoginWallIndicator)> {
+        results
+            .iter()
+            .filter_map(|result| {
+                if result.status_code == 401 || result.status_code == 403 {
+                    return Some((result.url.clone(), LoginWallIndicator::HttpAuth));
+                }
+
+                let login_redirect = result.redirect_chain.iter().find(|hop| Self::is_login_url(hop));
+
+                if let Some(login_redirect) = login_redirect {
+                    return Some((result.url.clone(), LoginWallIndicator::RedirectToLogin(login_redirect.clone())));
+                }
+
+                let title = result.title.as_deref().unwrap_or_default().to_lowercase();
+                if LOGIN_TITLE_PHRASES.iter().any(|phrase| title.contains(phrase)) {
+                    return Some((result.url.clone(), LoginWallIndicator::ContentIndicator));
+                }
+
+                None
+            })
+            .collect()
+    }
+
+    /// Returns whether the URL is likely that of a login page
+    pub(crate) fn is_login_url(url: &Url) -> bool {
+        let path = url.path().to_lowercase();
+        LOGIN_PATHS.iter().any(|login_path| path.contains(login_path))
+    }
+}
@@ -0,0 +1,11 @@
+pub mod cross_links;
+pub mod internal_links;
+pub mod http_versions;
+pub mod titles;
+pub mod descriptions;
+pub mod external_redirects;
+pub mod login_walls;
+pub mod url_categories;
+pub mod coverage;
+pub mod vary_headers;
+pub mod schema_types;
@@ -0,0 +1,18 @@
+use std::collections::HashMap;
+
+use crate::crawler::{crawl_result::CrawlResult, Crawler};
+
+impl Crawler {
+    /// Count the crawled pages by the Schema.org types of their JSON-LD blocks, each page counting once per type.
+    pub fn schema_type_distribution(results: &[CrawlResult]) -> HashMap<String, usize> {
+        let mut distribution = HashMap::new();
+
+        for result in results {
+            for schema_type in &result.schema_org_types {
+                *distribution.entry(schema_type.clone()).or_insert(0) += 1;
+            }
+        }
+
+        distribution
+    }
+}
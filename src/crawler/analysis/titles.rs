@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+use url::Url;
+
+use crate::crawler::{crawl_result::CrawlResult, Crawler};
+
+impl Crawler {
+    /// Returns the crawled pages without a title, or with an empty one.
+    pub fn find_pages_missing_title(results: &[CrawlResult]) -> Vec<Url> {
+        results
+            .iter()
+            .filter(|result| result.title.as_deref().unwrap_or_default().is_empty())
+            .map(|result| result.url.clone())
+            .collect()
+    }
+
+    /// Group the crawled pages by title, returning the titles shared by more than one page.
+    pub fn find_pages_with_duplicate_titles(results: &[CrawlResult]) -> Vec<(String, Vec<Url>)> {
+        let mut pages_by_title: HashMap<&str, Vec<Url>> = HashMap::new();
+
+        for result in results {
+            let Some(title) = result.title.as_deref() else { continue; };
+            if title.is_empty() { continue; }
+
+            pages_by_title.entry(title).or_default().push(result.url.clone());
+        }
+
+        pages_by_title
+            .into_iter()
+            .filter(|(_, pages)| pages.len() > 1)
+            .map(|(title, pages)| (title.to_owned(), pages))
+            .collect()
+    }
+}
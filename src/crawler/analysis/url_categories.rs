@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use url::Url;
+
+use crate::crawler::{crawl_result::CrawlResult, Crawler};
+
+/// The category of the URLs which match no category
+pub const UNCATEGORIZED: &str = "uncategorized";
+
+/// A category of URLs, e.g. blog posts, defined by the patterns its URLs match
+#[derive(Debug, Clone)]
+pub struct UrlCategory {
+    pub name: String,
+    pub patterns: Vec<Regex> // Matched against the whole URL
+}
+
+impl UrlCategory {
+    pub fn new(name: &str, patterns: Vec<Regex>) -> UrlCategory {
+        UrlCategory { name: name.to_owned(), patterns }
+    }
+
+    /// Returns whether the URL matches any of the category's patterns
+    pub fn matches(&self, url: &Url) -> bool {
+        self.patterns.iter().any(|pattern| pattern.is_match(url.as_str()))
+    }
+}
+
+impl Crawler {
+    /// Group the crawled pages by the first category of the taxonomy they match,
+    /// the pages matching none being under `UNCATEGORIZED`.
+    pub fn categorize_urls(results: &[CrawlResult], taxonomy: Vec<UrlCategory>) -> HashMap<String, Vec<Url>> {
+        let mut categories: HashMap<String, Vec<Url>> = HashMap::new();
+
+        for result in results {
+            let category = taxonomy
+                .iter()
+                .find(|category| category.matches(&result.url))
+                .map_or(UNCATEGORIZED, |category| category.name.as_str());
+
+            categories.entry(category.to_owned()).or_default().push(result.url.clone());
+        }
+
+        categories
+    }
+}
@@ -0,0 +1,42 @@
+use crate::crawler::{crawl_result::CrawlResult, Crawler};
+
+/// How the crawled pages vary by request headers, as percentages of all crawled pages, 0 if there are none
+#[derive(Debug, Clone, PartialEq)]
+pub struct VaryReport {
+    pub cookie_pct: f64,          // Pages varying by Cookie, which CDNs can't cache
+    pub user_agent_pct: f64,      // Pages varying by User-Agent, which CDNs can't cache efficiently
+    pub accept_encoding_pct: f64, // Pages varying by Accept-Encoding
+    pub cacheable_pct: f64        // Pages without a Vary header, or varying only by Accept-Encoding
+}
+
+impl Crawler {
+    /// Summarise the Vary headers of the crawled pages, for finding pages which aren't cacheable by CDNs
+    pub fn vary_header_analysis(results: &[CrawlResult]) -> VaryReport {
+        let (mut cookie, mut user_agent, mut accept_encoding, mut cacheable) = (0, 0, 0, 0);
+
+        for result in results {
+            let varied_headers: Vec<String> = result.vary_header
+                .iter()
+                .flat_map(|vary_header| vary_header.split(','))
+                .map(|header| header.trim().to_ascii_lowercase())
+                .filter(|header| !header.is_empty())
+                .collect();
+
+            let varies_by = |name: &str| varied_headers.iter().any(|header| header == name);
+
+            if varies_by("cookie") { cookie += 1; }
+            if varies_by("user-agent") { user_agent += 1; }
+            if varies_by("accept-encoding") { accept_encoding += 1; }
+            if varied_headers.iter().all(|header| header == "accept-encoding") { cacheable += 1; }
+        }
+
+        let percentage = |count: usize| if results.is_empty() { 0.0 } else { count as f64 * 100.0 / results.len() as f64 };
+
+        VaryReport {
+            cookie_pct: percentage(cookie),
+            user_agent_pct: percentage(user_agent),
+            accept_encoding_pct: percentage(accept_encoding),
+            cacheable_pct: percentage(cacheable)
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::fmt;
+
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+
+use super::{analysis::login_walls::LoginWallIndicator, crawl_target::CrawlTarget};
+
+/// The credentials sent with every request to a target, redacted when debug printed.
+/// Written as `{"basic": {"username": "...", "password": "..."}}` or `{"bearer": "..."}` in configuration files.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum TargetAuth {
+    Basic { username: String, password: Option<String> }, // HTTP Basic authentication
+    Bearer(String),                                       // A bearer token, e.g. an API token
+}
+
+impl TargetAuth {
+    /// Add the credentials to a request
+    pub fn authenticate(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Self::Basic { username, password } => request.basic_auth(username, password.as_ref()),
+            Self::Bearer(token) => request.bearer_auth(token)
+        }
+    }
+}
+
+impl fmt::Debug for TargetAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic { username, password } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &password.as_ref().map(|_| "[REDACTED]"))
+                .finish(),
+            Self::Bearer(_) => f.debug_tuple("Bearer").field(&"[REDACTED]").finish()
+        }
+    }
+}
+
+/// A target whose main page requires logging in, found by probing it before crawling
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthRequiredTarget {
+    pub target: CrawlTarget,
+    pub indicator: LoginWallIndicator // HttpAuth or RedirectToLogin
+}
@@ -0,0 +1,88 @@
+use std::{collections::HashSet, path::Path};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::web::host::Host;
+
+use super::{crawl_target::CrawlTarget, crawler_config::CrawlerConfig, Crawler, CrawlerError};
+
+/// The progress of a crawl, saved as JSON
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    targets: Vec<CrawlTarget>,
+    crawled_urls: Vec<(Host, Vec<String>)>,     // The deduplication keys of the URLs queued for crawling, by target host
+    frontier: Vec<(Host, Vec<(String, usize)>)> // The URLs queued but not yet fetched with their depths, by target host
+}
+
+/// The progress of a target restored from a checkpoint
+#[derive(Debug, Default)]
+pub(crate) struct ResumedTarget {
+    pub(crate) crawled_urls: HashSet<String>, // The deduplication keys of the URLs queued before the checkpoint
+    pub(crate) frontier: Vec<(Url, usize)>    // The URLs queued but not yet fetched, with their depths
+}
+
+impl Crawler {
+    /// Save the targets and the URLs queued and fetched so far to a file, so that the crawl can be resumed after a restart
+    pub async fn save_checkpoint(&self, path: &Path) -> Result<(), CrawlerError> {
+        let lock_error = |error: String| CrawlerError::Other(format!("Failed to obtain mutex lock: {}", error));
+
+        let targets = self.crawl_targets.lock().map_err(|error| lock_error(error.to_string()))?.iter().cloned().collect();
+
+        let crawled_urls = self.crawl_state.discovered_urls
+            .lock()
+            .map_err(|error| lock_error(error.to_string()))?
+            .iter()
+            .map(|(host, urls)| (host.clone(), urls.iter().cloned().collect()))
+            .collect();
+
+        let frontier = self.crawl_state.frontier
+            .lock()
+            .map_err(|error| lock_error(error.to_string()))?
+            .iter()
+            .map(|(host, urls)| (host.clone(), urls.values().map(|(url, depth)| (url.to_string(), *depth)).collect()))
+            .collect();
+
+        let checkpoint = serde_json::to_vec(&Checkpoint { targets, crawled_urls, frontier })
+            .map_err(|error| CrawlerError::wrap("Failed to serialize checkpoint", error))?;
+
+        tokio::fs::write(path, checkpoint)
+            .await
+            .map_err(|error| CrawlerError::wrap(&format!("Failed to write checkpoint to {}", path.display()), error))
+    }
+
+    /// Create a crawler which continues the crawl saved to a checkpoint. Its targets are crawled from the URLs they had
+    /// queued but not fetched, without crawling the URLs queued before again. The configuration isn't part of the checkpoint.
+    pub async fn resume_from_checkpoint(path: &Path, config: CrawlerConfig) -> Result<Crawler, CrawlerError> {
+        let checkpoint = tokio::fs::read(path)
+            .await
+            .map_err(|error| CrawlerError::wrap(&format!("Failed to read checkpoint from {}", path.display()), error))?;
+        let checkpoint: Checkpoint = serde_json::from_slice(&checkpoint)
+            .map_err(|error| CrawlerError::wrap("Failed to parse checkpoint", error))?;
+
+        let crawler = Crawler::new(config)?;
+
+        match crawler.crawl_targets.lock() {
+            Ok(mut crawl_targets) => crawl_targets.extend(checkpoint.targets.iter().cloned()),
+            Err(error) => return Err(CrawlerError::Other(format!("Failed to obtain mutex lock: {}", error)))
+        }
+
+        match crawler.crawl_state.resumed_targets.lock() {
+            // Targets which hadn't queued any URLs are crawled from the beginning
+            Ok(mut resumed_targets) => {
+                for (host, crawled_urls) in checkpoint.crawled_urls {
+                    resumed_targets.entry(host).or_default().crawled_urls.extend(crawled_urls);
+                }
+
+                for (host, frontier) in checkpoint.frontier {
+                    resumed_targets.entry(host).or_default().frontier.extend(
+                        frontier.into_iter().filter_map(|(url, depth)| Some((Url::parse(&url).ok()?, depth)))
+                    );
+                }
+            }
+            Err(error) => return Err(CrawlerError::Other(format!("Failed to obtain mutex lock: {}", error)))
+        }
+
+        Ok(crawler)
+    }
+}
@@ -0,0 +1,40 @@
+use std::{collections::HashMap, fmt::Debug};
+
+use url::Url;
+
+/// The action used for HTML pages when no configured MIME type prefix matches
+static PARSE_LINKS: ContentTypeAction = ContentTypeAction::ParseLinks;
+
+/// The action used for other content when no configured MIME type prefix matches
+static SKIP: ContentTypeAction = ContentTypeAction::Skip;
+
+/// Handles the response bodies of a content type the crawler can't parse itself
+pub trait ContentParser: Debug + Send + Sync {
+    /// Parse the body of the page at the URL, returning the links found in it
+    fn parse(&self, url: &Url, body: &[u8]) -> Vec<Url>;
+}
+
+/// What to do with a page of a given content type
+#[derive(Debug)]
+pub enum ContentTypeAction {
+    ParseLinks,                    // Parse the page as HTML and follow its links
+    RecordOnly,                    // Record the page and the hash of its body, without looking for links
+    Skip,                          // Don't record the page
+    Custom(Box<dyn ContentParser>) // Record the page and follow the links found by the parser
+}
+
+/// Returns the action for the content type, using the longest matching MIME type prefix in the map.
+/// Content types without a matching prefix are parsed if they are HTML and skipped otherwise.
+pub fn content_type_action<'a>(content_type_map: &'a HashMap<String, ContentTypeAction>, content_type: &str) -> &'a ContentTypeAction {
+    let configured_action = content_type_map
+        .iter()
+        .filter(|(prefix, _)| content_type.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, action)| action);
+
+    match configured_action {
+        Some(action) => action,
+        None if content_type.starts_with("text/html") => &PARSE_LINKS,
+        None => &SKIP
+    }
+}
@@ -0,0 +1,314 @@
+use std::{sync::Mutex, time::{Duration, SystemTime}};
+
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use tracing::error;
+use url::Url;
+
+use super::CrawlerError;
+
+/// The multi-label public suffixes most commonly seen, for which, like for top-level domains, no cookies are accepted.
+/// The full public suffix list isn't bundled, so cookies may still be set for the less common ones.
+const MULTI_LABEL_PUBLIC_SUFFIXES: [&str; 24] = [
+    "co.uk", "org.uk", "ac.uk", "gov.uk", "me.uk", "com.au", "net.au", "org.au", "co.nz", "co.jp", "ne.jp", "or.jp",
+    "com.br", "com.cn", "com.mx", "com.tr", "co.in", "co.za", "co.kr", "com.sg", "github.io", "herokuapp.com", "blogspot.com", "appspot.com"
+];
+
+/// A cookie kept by the jar
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cookie {
+    domain: String,               // The lowercase domain name the cookie was set for, without a leading dot
+    host_only: bool,              // Whether the cookie is only sent to the domain itself, rather than also to its subdomains
+    path: String,                 // The cookie is only sent to the URLs under this path, e.g. "/account"
+    secure: bool,                 // Whether the cookie is only sent over HTTPS
+    expires: Option<SystemTime>,  // When the cookie stops being sent, if it isn't a session cookie
+    name: String,
+    value: String
+}
+
+/// The cookies sent with the requests of a crawl, e.g. the session cookies of a logged in user.
+/// Cookies are only sent to the URLs their Domain, Path and Secure attributes match, until they expire.
+#[derive(Debug, Default)]
+pub struct CookieJar(Mutex<Vec<Cookie>>);
+
+impl CookieJar {
+    /// Add a cookie sent to the domain and its subdomains, replacing any cookie of the same name set for it
+    pub fn add(&self, domain: &str, name: &str, value: &str) -> Result<(), CrawlerError> {
+        let domain = domain.trim_start_matches('.').to_ascii_lowercase();
+
+        if domain.is_empty() {
+            return Err(CrawlerError::Other(format!("Invalid cookie {}: it has no domain", name)));
+        }
+        if name.is_empty() || name.contains(|character: char| character == '=' || character == ';' || character.is_whitespace()) {
+            return Err(CrawlerError::Other(format!("Invalid cookie name: {}", name)));
+        }
+        if value.contains(';') || HeaderValue::from_str(value).is_err() {
+            return Err(CrawlerError::Other(format!("Invalid value of cookie {}: {}", name, value)));
+        }
+
+        self.set(Cookie {
+            domain,
+            host_only: false,
+            path: String::from("/"),
+            secure: false,
+            expires: None,
+            name: name.to_owned(),
+            value: value.to_owned()
+        });
+        Ok(())
+    }
+
+    /// Returns the names and values of the cookies sent with requests for the URL
+    pub fn cookies_for(&self, url: &Url) -> Vec<(String, String)> {
+        let Some(host) = url.host_str().map(str::to_ascii_lowercase) else { return Vec::new(); };
+        let now = SystemTime::now();
+
+        match self.0.lock() {
+            Ok(mut cookies) => {
+                // Expired cookies are dropped once they're next looked up
+                cookies.retain(|cookie| !cookie.has_expired(now));
+
+                cookies
+                    .iter()
+                    .filter(|cookie| cookie.matches(&host) && cookie.matches_path(url.path()) && (!cookie.secure || url.scheme() == "https"))
+                    .map(|cookie| (cookie.name.clone(), cookie.value.clone()))
+                    .collect()
+            }
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); Vec::new() }
+        }
+    }
+
+    /// Add the Cookie header of the cookies for the URL to the headers, returning the cookies sent
+    pub fn add_cookie_header(&self, url: &Url, headers: &mut HeaderMap) -> Vec<(String, String)> {
+        let cookies = self.cookies_for(url);
+        if cookies.is_empty() { return cookies; }
+
+        let cookie_header = cookies.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<String>>().join("; ");
+        if let Ok(cookie_header) = HeaderValue::from_str(&cookie_header) {
+            headers.insert(header::COOKIE, cookie_header);
+        }
+
+        cookies
+    }
+
+    /// Store the cookies the Set-Cookie headers of a response for the URL set, returning their names and values.
+    /// Cookies for other domains than the URL's or for public suffixes are ignored, and those which have already expired removed.
+    pub fn store_response_cookies(&self, url: &Url, headers: &HeaderMap) -> Vec<(String, String)> {
+        let mut received = Vec::new();
+
+        for (cookie, expired) in parse_set_cookies(url, headers) {
+            received.push((cookie.name.clone(), cookie.value.clone()));
+
+            if expired {
+                self.remove(&cookie);
+            } else {
+                self.set(cookie);
+            }
+        }
+
+        received
+    }
+
+    fn set(&self, cookie: Cookie) {
+        match self.0.lock() {
+            Ok(mut cookies) => {
+                cookies.retain(|stored| !stored.same_cookie(&cookie));
+                cookies.push(cookie);
+            }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    fn remove(&self, cookie: &Cookie) {
+        match self.0.lock() {
+            Ok(mut cookies) => cookies.retain(|stored| !stored.same_cookie(cookie)),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+}
+
+/// Returns the names and values of the cookies the Set-Cookie headers of a response for the URL set, without storing them
+pub fn response_cookies(url: &Url, headers: &HeaderMap) -> Vec<(String, String)> {
+    parse_set_cookies(url, headers).into_iter().map(|(cookie, _)| (cookie.name, cookie.value)).collect()
+}
+
+/// Parse the cookies the Set-Cookie headers of a response for the URL set for its domains, along with whether each has expired.
+/// Cookies for public suffixes are rejected, as are Secure cookies set over HTTP.
+fn parse_set_cookies(url: &Url, headers: &HeaderMap) -> Vec<(Cookie, bool)> {
+    let Some(host) = url.host_str().map(str::to_ascii_lowercase) else { return Vec::new(); };
+    let now = SystemTime::now();
+    let mut cookies = Vec::new();
+
+    for set_cookie in headers.get_all(header::SET_COOKIE).iter().filter_map(|value| value.to_str().ok()) {
+        let mut attributes = set_cookie.split(';');
+        let Some((name, value)) = attributes.next().and_then(|pair| pair.split_once('=')) else { continue; };
+        let (name, value) = (name.trim(), value.trim());
+        if name.is_empty() { continue; }
+
+        let mut cookie = Cookie {
+            domain: host.clone(),
+            host_only: true,
+            path: default_path(url.path()).to_owned(),
+            secure: false,
+            expires: None,
+            name: name.to_owned(),
+            value: value.to_owned()
+        };
+        let mut max_age = None;
+
+        for attribute in attributes {
+            let (key, attribute_value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let attribute_value = attribute_value.trim();
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !attribute_value.is_empty() => {
+                    cookie.domain = attribute_value.trim_start_matches('.').to_ascii_lowercase();
+                    cookie.host_only = false;
+                }
+                "path" if attribute_value.starts_with('/') => cookie.path = attribute_value.to_owned(),
+                "secure" => cookie.secure = true,
+                "max-age" => max_age = attribute_value.parse::<i64>().ok(),
+                // Cookie dates are often written with dashes, e.g. "Wed, 21-Oct-2015 07:28:00 GMT"
+                "expires" => {
+                    if let Ok(expires) = httpdate::parse_http_date(&attribute_value.replace('-', " ")) {
+                        cookie.expires = Some(expires);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Max-Age takes precedence over Expires
+        if let Some(max_age) = max_age {
+            cookie.expires = Some(if max_age <= 0 { SystemTime::UNIX_EPOCH } else { now + Duration::from_secs(max_age.unsigned_abs()) });
+        }
+        let expired = cookie.has_expired(now);
+
+        // A host may only set cookies for itself and the domains it belongs to, other than public suffixes,
+        // and Secure cookies only over HTTPS
+        if cookie.secure && url.scheme() != "https" { continue; }
+        if !cookie.host_only && is_public_suffix(&cookie.domain) { continue; }
+        if cookie.matches(&host) { cookies.push((cookie, expired)); }
+    }
+
+    cookies
+}
+
+/// Returns the path a cookie set without a Path attribute applies to, the directory of the path of the URL setting it
+fn default_path(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(0) | None => "/",
+        Some(end) => &path[..end]
+    }
+}
+
+/// Returns whether the lowercase domain is a public suffix, such as a top-level domain, which cookies can't be set for
+fn is_public_suffix(domain: &str) -> bool {
+    !domain.contains('.') || MULTI_LABEL_PUBLIC_SUFFIXES.contains(&domain)
+}
+
+impl Cookie {
+    /// Returns whether the cookie is sent to the lowercase host
+    fn matches(&self, host: &str) -> bool {
+        host == self.domain
+            || (!self.host_only && host.strip_suffix(self.domain.as_str()).is_some_and(|subdomain| subdomain.ends_with('.')))
+    }
+
+    /// Returns whether the cookie is sent to the URLs of the path, those under its own path
+    fn matches_path(&self, path: &str) -> bool {
+        path == self.path
+            || (path.starts_with(self.path.as_str()) && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/')))
+    }
+
+    fn has_expired(&self, now: SystemTime) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// Returns whether the cookies have the same name, domain and path, so that setting one replaces the other
+    fn same_cookie(&self, other: &Cookie) -> bool {
+        self.name == other.name && self.domain == other.domain && self.host_only == other.host_only && self.path == other.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::{self, HeaderMap, HeaderValue};
+    use url::Url;
+
+    use super::CookieJar;
+
+    fn set_cookies(jar: &CookieJar, url: &str, set_cookies: &[&str]) -> Vec<(String, String)> {
+        let mut headers = HeaderMap::new();
+        for set_cookie in set_cookies {
+            headers.append(header::SET_COOKIE, HeaderValue::from_str(set_cookie).unwrap());
+        }
+
+        jar.store_response_cookies(&Url::parse(url).unwrap(), &headers)
+    }
+
+    fn cookie_names(jar: &CookieJar, url: &str) -> Vec<String> {
+        jar.cookies_for(&Url::parse(url).unwrap()).into_iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn domain_cookies_are_sent_to_subdomains_and_host_cookies_only_to_the_host() {
+        let jar = CookieJar::default();
+        set_cookies(&jar, "https://www.example.com/", &["domain=1; Domain=example.com", "host=1"]);
+
+        assert_eq!(cookie_names(&jar, "https://www.example.com/"), vec!["domain", "host"]);
+        assert_eq!(cookie_names(&jar, "https://blog.example.com/"), vec!["domain"]);
+        assert!(cookie_names(&jar, "https://example.org/").is_empty());
+    }
+
+    #[test]
+    fn cookies_for_public_suffixes_and_other_domains_are_rejected() {
+        let jar = CookieJar::default();
+        let received = set_cookies(&jar, "https://www.example.co.uk/", &[
+            "tld=1; Domain=uk",
+            "suffix=1; Domain=.co.uk",
+            "other=1; Domain=example.org",
+            "sibling=1; Domain=blog.example.co.uk",
+            "own=1; Domain=example.co.uk"
+        ]);
+
+        assert_eq!(received, vec![(String::from("own"), String::from("1"))]);
+        assert!(cookie_names(&jar, "https://other.co.uk/").is_empty());
+        assert_eq!(cookie_names(&jar, "https://www.example.co.uk/"), vec!["own"]);
+    }
+
+    #[test]
+    fn cookies_are_only_sent_under_their_path() {
+        let jar = CookieJar::default();
+        set_cookies(&jar, "https://example.com/account/settings", &["explicit=1; Path=/shop", "default=1"]);
+
+        assert_eq!(cookie_names(&jar, "https://example.com/shop"), vec!["explicit"]);
+        assert_eq!(cookie_names(&jar, "https://example.com/shop/cart"), vec!["explicit"]);
+        assert!(cookie_names(&jar, "https://example.com/shopping").is_empty());
+        assert_eq!(cookie_names(&jar, "https://example.com/account/profile"), vec!["default"]);
+        assert!(cookie_names(&jar, "https://example.com/").is_empty());
+    }
+
+    #[test]
+    fn secure_cookies_are_only_set_and_sent_over_https() {
+        let jar = CookieJar::default();
+        assert!(set_cookies(&jar, "http://example.com/", &["insecure=1; Secure"]).is_empty());
+
+        set_cookies(&jar, "https://example.com/", &["secure=1; Secure"]);
+        assert_eq!(cookie_names(&jar, "https://example.com/"), vec!["secure"]);
+        assert!(cookie_names(&jar, "http://example.com/").is_empty());
+    }
+
+    #[test]
+    fn expired_cookies_are_not_sent() {
+        let jar = CookieJar::default();
+        set_cookies(&jar, "https://example.com/", &[
+            "past=1; Expires=Wed, 21-Oct-2015 07:28:00 GMT",
+            "future=1; Expires=Fri, 01 Jan 2100 00:00:00 GMT",
+            "overridden=1; Max-Age=3600; Expires=Wed, 21 Oct 2015 07:28:00 GMT",
+            "session=1"
+        ]);
+        assert_eq!(cookie_names(&jar, "https://example.com/"), vec!["future", "overridden", "session"]);
+
+        set_cookies(&jar, "https://example.com/", &["session=; Max-Age=0"]);
+        assert_eq!(cookie_names(&jar, "https://example.com/"), vec!["future", "overridden"]);
+    }
+}
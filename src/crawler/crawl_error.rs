@@ -0,0 +1,58 @@
+use std::fmt;
+
+use url::Url;
+
+use crate::web::host::is_private_address_error;
+
+use super::CrawlerError;
+
+/// What went wrong crawling a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrawlErrorKind {
+    HttpError,        // The request failed, or the server responded with an error status code
+    ParseError,       // The response could not be read or processed
+    Timeout,          // The request took too long to complete
+    TooManyRedirects, // The page redirected more times than followed
+    NonHtmlContent,   // The page isn't of a content type which is recorded
+    BodyTooLarge,     // The response body is larger than allowed, so at most its beginning was read
+    BlockedByPolicy,  // The page's host, or one it redirected to, has a private address, which isn't requested unless allowed
+    Other             // Anything else, e.g. middleware rejecting the page
+}
+
+/// The error of a page which couldn't be crawled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrawlError {
+    pub url: Url,
+    pub kind: CrawlErrorKind,
+    pub message: String
+}
+
+impl CrawlError {
+    pub fn new(url: Url, kind: CrawlErrorKind, message: String) -> CrawlError {
+        CrawlError { url, kind, message }
+    }
+
+    /// Classify the error which stopped a page from being crawled
+    pub fn from_crawler_error(url: &Url, error: &CrawlerError) -> CrawlError {
+        let kind = match error {
+            CrawlerError::Request { error, .. } if is_private_address_error(error) => CrawlErrorKind::BlockedByPolicy,
+            CrawlerError::Request { error, .. } if error.is_timeout() => CrawlErrorKind::Timeout,
+            CrawlerError::Request { error, .. } if error.is_redirect() => CrawlErrorKind::TooManyRedirects,
+            CrawlerError::Request { error, .. } if error.is_decode() || error.is_body() => CrawlErrorKind::ParseError,
+            CrawlerError::Request { .. } | CrawlerError::HttpStatus { .. } | CrawlerError::TlsVersionTooOld { .. } | CrawlerError::ResponseTooLarge { .. } => {
+                CrawlErrorKind::HttpError
+            }
+            CrawlerError::Other(_) | CrawlerError::InvalidConfig(_) | CrawlerError::InvalidSelector { .. } | CrawlerError::Wrapped { .. } | CrawlerError::ConfigParse { .. } => {
+                CrawlErrorKind::Other
+            }
+        };
+
+        CrawlError::new(error.url().unwrap_or(url).clone(), kind, error.to_string())
+    }
+}
+
+impl fmt::Display for CrawlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} error crawling {}: {}", self.kind, self.url, self.message)
+    }
+}
@@ -0,0 +1,15 @@
+use url::Url;
+
+use crate::web::host::Host;
+
+/// Something that happened during a crawl, published to the crawler's subscribers as it happens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrawlEvent {
+    UrlDiscovered(Url),                      // A URL was queued for crawling
+    UrlCrawled(Url),                         // A page was fetched, processed and saved
+    TargetStarted(Host),                     // Crawling a target began
+    TargetFinished(Host),                    // All pages of a target have been crawled, or its crawl was cancelled
+    TargetLimitReached(Host),                // A target had as many pages as it allows, so no more of them are crawled
+    CrawlError { url: Url, error: String },  // A page was given up on
+    CrossHostRedirect { from: Url, to: Url } // A page was redirected to another host, whose page is dispatched like a link
+}
@@ -0,0 +1,16 @@
+use clap::ValueEnum;
+
+/// Which pages of a target are crawled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum CrawlMode {
+    #[default]
+    FullCrawl,   // The target's main page or seed URLs, and every page linked to from the crawled pages
+    SitemapOnly  // Only the pages listed in the target's sitemaps, or its seed URLs, without extracting their links
+}
+
+impl CrawlMode {
+    /// Returns whether the links of the crawled pages are extracted and followed
+    pub fn follows_links(&self) -> bool {
+        *self == Self::FullCrawl
+    }
+}
@@ -0,0 +1,43 @@
+use std::{collections::{HashMap, HashSet}, time::Duration};
+
+use url::Url;
+
+use crate::web::host::Host;
+
+use super::{crawl_error::CrawlError, crawl_stats::CrawlStats, link::Link};
+
+/// The data transferred while crawling a target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TargetSummary {
+    pub bytes_downloaded: u64, // The response headers and bodies of the crawled pages
+    pub bytes_uploaded: u64    // The request bodies sent, which page requests don't have
+}
+
+/// How long a crawled page took, from sending its request to extracting its links
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlTiming {
+    pub url: Url,
+    pub elapsed: Duration,
+    pub status: u16,       // The status code of the page's response
+    pub link_count: usize  // The number of links found on the page
+}
+
+/// What a crawl found and which pages it failed on
+#[derive(Debug, Default)]
+pub struct CrawlReport {
+    pub discovered_urls: HashMap<Host, HashSet<String>>, // The normalised URLs queued for crawling, by target host
+    pub errors: Vec<CrawlError>,                         // The errors of the pages given up on, in order
+    pub targets: HashMap<Host, TargetSummary>,           // The data transferred for each target host
+    pub resources: HashSet<String>,                      // The scripts, stylesheets, images and other resources of the crawled pages
+    pub link_graph: HashMap<Url, Vec<Link>>,             // The outbound links of each crawled page, with their anchor text
+    pub cookies: HashMap<Url, Vec<(String, String)>>,    // The names and values of the cookies sent and received for each page, if recorded
+    pub redirects: HashMap<Url, Url>,                    // The URL each redirected page was requested at, and the one it finally redirected to
+    pub redirect_chains: HashMap<Url, Vec<Url>>,         // The URLs each redirected page redirected through after the requested one, in order
+    pub status_codes: HashMap<Url, u16>,                 // The status codes of the pages crawled in sitemap-only mode, e.g. for finding dead links
+    pub timings: Vec<UrlTiming>,                         // How long each crawled page took, in the order they finished, if recorded
+    pub bytes_downloaded: u64,                           // The data downloaded across all targets
+    pub bytes_uploaded: u64,                             // The data uploaded across all targets
+    pub crawl_duration: Duration,
+    pub stats: CrawlStats,                               // The request counts, sizes and latencies
+    pub cancelled: bool                                  // Whether the crawl was stopped before every page was crawled
+}
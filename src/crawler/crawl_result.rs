@@ -0,0 +1,243 @@
+use std::{collections::{HashMap, HashSet}, time::SystemTime};
+
+use rusqlite::{named_params, Connection};
+use sha2::{Digest, Sha256};
+use static_assertions::assert_impl_all;
+use url::Url;
+
+use crate::web::{host::Host, http::{HttpVersion, TlsVersion}};
+
+/// A resource hint declared by a page, e.g. <link rel="preload">
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceHint {
+    pub url: Url,
+    pub relation: String,            // preload, prefetch, preconnect or dns-prefetch
+    pub as_attribute: Option<String> // The type of the hinted resource, e.g. script
+}
+
+/// The result of crawling a single page
+#[derive(Debug, Clone)]
+pub struct CrawlResult {
+    pub url: Url,                          // The URL of the crawled page
+    pub target: Host,                      // The host of the crawl target the page belongs to
+    pub status_code: u16,                  // The HTTP status code of the response
+    pub fetched_at: SystemTime,            // When the response was received
+    pub http_version: HttpVersion,         // The HTTP version of the response
+    pub tls_version: Option<TlsVersion>,   // The minimum TLS version the connection is known to use
+    pub response_headers: Vec<(String, String)>, // The names and values of the response headers, in order
+    pub vary_header: Option<String>,       // The raw value of the response's Vary header
+    pub response_body: String,             // The body decoded as text, if it's an HTML page
+    pub body_bytes: Vec<u8>,               // The body as received, if it was read
+    pub body_sha256: Option<String>,       // The hex-encoded SHA-256 hash of the response body, if it was read
+    pub page_size_bytes: u64,              // The size of the response body as downloaded, if it was read
+    pub body_truncated: bool,              // Whether only the beginning of a too large body was read, which isn't saved
+    pub title: Option<String>,             // The text of the page's <title> element
+    pub description: Option<String>,       // The content of the page's meta description
+    pub links: Vec<Url>,                   // The outbound links found on the page
+    pub resource_hints: Vec<ResourceHint>, // The resource hints declared by the page, if extracted
+    pub resources: Vec<Url>,               // The scripts, stylesheets and images the page refers to, which aren't saved
+    pub schema_org_types: Vec<String>,     // The Schema.org @type values of the page's JSON-LD blocks, e.g. Product
+    pub canonical_url: Option<Url>,        // The canonical URL declared by the page
+    pub cross_domain_redirect: Option<Url>, // The destination of a blocked redirect to another domain
+    pub redirect_chain: Vec<Url>,            // The URLs redirected through after the requested one, in order
+    pub sitemap_priority: Option<f64>,       // The priority of the page in the sitemap it was crawled from
+    pub sitemap_changefreq: Option<String>,  // The change frequency of the page in the sitemap it was crawled from
+    pub from_cache: bool,                   // Whether the page is unchanged since a previous crawl
+    pub from_http_cache: bool               // Whether the page's response came from the HTTP cache, fresh or confirmed unchanged by the server
+}
+
+assert_impl_all!(CrawlResult: Send, Sync);
+
+impl CrawlResult {
+    /// Create the result of a page with an empty body and no links, received now
+    pub fn new(url: Url, target: Host, status_code: u16, http_version: HttpVersion) -> CrawlResult {
+        CrawlResult {
+            url,
+            target,
+            status_code,
+            fetched_at: SystemTime::now(),
+            http_version,
+            tls_version: None,
+            response_headers: Vec::new(),
+            vary_header: None,
+            response_body: String::new(),
+            body_bytes: Vec::new(),
+            body_sha256: None,
+            page_size_bytes: 0,
+            body_truncated: false,
+            title: None,
+            description: None,
+            links: Vec::new(),
+            resource_hints: Vec::new(),
+            resources: Vec::new(),
+            schema_org_types: Vec::new(),
+            canonical_url: None,
+            cross_domain_redirect: None,
+            redirect_chain: Vec::new(),
+            sitemap_priority: None,
+            sitemap_changefreq: None,
+            from_cache: false,
+            from_http_cache: false
+        }
+    }
+
+    /// Set the hash of the response body
+    pub fn set_body_hash(&mut self, body: &[u8]) {
+        self.body_sha256 = Some(Sha256::digest(body).iter().map(|byte| format!("{:02x}", byte)).collect());
+    }
+
+    /// Returns whether the page has the same body, links and metadata as in a previous result
+    pub fn is_unchanged_from(&self, previous: &CrawlResult) -> bool {
+        self.status_code == previous.status_code
+            && self.http_version == previous.http_version
+            && self.tls_version == previous.tls_version
+            && self.canonical_url == previous.canonical_url
+            && self.cross_domain_redirect == previous.cross_domain_redirect
+            && self.redirect_chain == previous.redirect_chain
+            && self.title == previous.title
+            && self.description == previous.description
+            && self.response_body == previous.response_body
+            && self.links.iter().collect::<HashSet<_>>() == previous.links.iter().collect::<HashSet<_>>()
+            && self.resource_hints == previous.resource_hints
+    }
+
+    /// Save the sitemap metadata of an already saved result
+    pub fn save_sitemap_metadata(&self, db: &Connection) -> Result<(), rusqlite::Error> {
+        db.execute(
+            "UPDATE urls SET sitemap_priority = :sitemap_priority, sitemap_changefreq = :sitemap_changefreq WHERE url = :url",
+            named_params! {
+                ":url": self.url,
+                ":sitemap_priority": self.sitemap_priority,
+                ":sitemap_changefreq": self.sitemap_changefreq
+            }
+        )?;
+
+        Ok(())
+    }
+
+    /// Save the result, its outbound links and its resource hints to the database
+    pub fn save(&self, db: &Connection) -> Result<(), rusqlite::Error> {
+        db.execute(
+            "INSERT INTO urls (url, target, response_code, http_version, tls_version, response_headers, vary_header, response_body, body_sha256, page_size_bytes, title, description, schema_org_types, canonical_url, cross_domain_redirect, redirect_chain)
+            VALUES (:url, :target, :response_code, :http_version, :tls_version, :response_headers, :vary_header, :response_body, :body_sha256, :page_size_bytes, :title, :description, :schema_org_types, :canonical_url, :cross_domain_redirect, :redirect_chain)",
+            named_params! {
+                ":url": self.url,
+                ":target": self.target.to_string(),
+                ":response_code": self.status_code,
+                ":http_version": self.http_version.to_string(),
+                ":tls_version": self.tls_version.map(|tls_version| tls_version.to_string()),
+                ":response_headers": self.response_headers.iter().map(|(name, value)| format!("{}: {}", name, value)).collect::<Vec<String>>().join("\n"),
+                ":vary_header": self.vary_header,
+                ":response_body": self.response_body,
+                ":body_sha256": self.body_sha256,
+                ":page_size_bytes": self.page_size_bytes,
+                ":title": self.title,
+                ":description": self.description,
+                ":schema_org_types": self.schema_org_types.join(" "),
+                ":canonical_url": self.canonical_url,
+                ":cross_domain_redirect": self.cross_domain_redirect,
+                ":redirect_chain": self.redirect_chain.iter().map(Url::as_str).collect::<Vec<&str>>().join(" ")
+            }
+        )?;
+
+        for link in &self.links {
+            db.execute(
+                "INSERT INTO links (source_url, target_url) VALUES (:source_url, :target_url)",
+                named_params! { ":source_url": self.url, ":target_url": link }
+            )?;
+        }
+
+        for resource_hint in &self.resource_hints {
+            db.execute(
+                "INSERT INTO resource_hints (page_url, url, relation, as_attribute) VALUES (:page_url, :url, :relation, :as_attribute)",
+                named_params! {
+                    ":page_url": self.url,
+                    ":url": resource_hint.url,
+                    ":relation": resource_hint.relation,
+                    ":as_attribute": resource_hint.as_attribute
+                }
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Load all crawl results stored in the database
+    pub fn load_all(db: &Connection) -> Result<Vec<CrawlResult>, rusqlite::Error> {
+        let mut links: HashMap<Url, Vec<Url>> = HashMap::new();
+
+        let mut statement = db.prepare("SELECT source_url, target_url FROM links")?;
+        let rows = statement.query_map([], |row| Ok((row.get::<_, Url>("source_url")?, row.get::<_, Url>("target_url")?)))?;
+        for row in rows {
+            let (source_url, target_url) = row?;
+            links.entry(source_url).or_default().push(target_url);
+        }
+
+        let mut resource_hints: HashMap<Url, Vec<ResourceHint>> = HashMap::new();
+
+        let mut statement = db.prepare("SELECT page_url, url, relation, as_attribute FROM resource_hints")?;
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            resource_hints.entry(row.get("page_url")?).or_default().push(ResourceHint {
+                url: row.get("url")?,
+                relation: row.get("relation")?,
+                as_attribute: row.get("as_attribute")?
+            });
+        }
+
+        let mut results = Vec::new();
+
+        let mut statement = db.prepare("SELECT * FROM urls")?;
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let url: Url = row.get("url")?;
+            let Some(host) = url.host() else { continue; };
+            let Ok(target) = Host::try_from(host) else { continue; };
+
+            let http_version = row
+                .get::<_, Option<String>>("http_version")?
+                .and_then(|http_version| http_version.parse().ok())
+                .unwrap_or_default();
+
+            let mut result = CrawlResult::new(url.clone(), target, row.get::<_, Option<u16>>("response_code")?.unwrap_or_default(), http_version);
+            result.tls_version = row
+                .get::<_, Option<String>>("tls_version")?
+                .and_then(|tls_version| tls_version.parse().ok());
+            result.response_headers = row
+                .get::<_, Option<String>>("response_headers")?
+                .unwrap_or_default()
+                .lines()
+                .filter_map(|header| header.split_once(": "))
+                .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                .collect();
+            result.vary_header = row.get("vary_header")?;
+            result.response_body = row.get::<_, Option<String>>("response_body")?.unwrap_or_default();
+            result.body_sha256 = row.get("body_sha256")?;
+            result.page_size_bytes = row.get::<_, Option<u64>>("page_size_bytes")?.unwrap_or_default();
+            result.title = row.get("title")?;
+            result.description = row.get("description")?;
+            result.schema_org_types = row
+                .get::<_, Option<String>>("schema_org_types")?
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(str::to_owned)
+                .collect();
+            result.links = links.remove(&url).unwrap_or_default();
+            result.resource_hints = resource_hints.remove(&url).unwrap_or_default();
+            result.canonical_url = row.get("canonical_url")?;
+            result.cross_domain_redirect = row.get("cross_domain_redirect")?;
+            result.redirect_chain = row
+                .get::<_, Option<String>>("redirect_chain")?
+                .unwrap_or_default()
+                .split_whitespace()
+                .filter_map(|hop| Url::parse(hop).ok())
+                .collect();
+            result.sitemap_priority = row.get("sitemap_priority")?;
+            result.sitemap_changefreq = row.get("sitemap_changefreq")?;
+
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+}
@@ -0,0 +1,74 @@
+use std::{sync::{atomic::{AtomicU64, Ordering}, Mutex}, time::Duration};
+
+use tracing::error;
+
+/// Statistics about the requests of a finished crawl
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CrawlStats {
+    pub total_requests: u64,      // The page requests sent, including retries
+    pub response_body_bytes: u64, // The bodies of the crawled pages, by their Content-Length if given
+    pub errors: u64,              // The pages given up on
+    pub unique_hosts: u64,        // The target hosts with a crawled page
+    pub mean_latency: Duration,   // The mean time between sending a page request and receiving its response headers
+    pub p95_latency: Duration,    // The time 95% of the page requests received their response headers within
+    pub elapsed: Duration         // The time the whole crawl took
+}
+
+/// Counts the requests of a crawl as they're made by its concurrent tasks
+#[derive(Debug, Default)]
+pub(crate) struct CrawlStatsCounters {
+    total_requests: AtomicU64,
+    response_body_bytes: AtomicU64,
+    errors: AtomicU64,
+    latencies: Mutex<Vec<Duration>> // Kept individually for the percentiles
+}
+
+impl CrawlStatsCounters {
+    /// Record a page request which was answered, or failed, after the latency
+    pub(crate) fn request_sent(&self, latency: Duration) {
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+
+        match self.latencies.lock() {
+            Ok(mut latencies) => latencies.push(latency),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Record the body size of a crawled page
+    pub(crate) fn body_received(&self, bytes: u64) {
+        self.response_body_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Record a page given up on
+    pub(crate) fn page_failed(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Freeze the counters into the statistics of a crawl which took the elapsed time and crawled pages of the unique hosts
+    pub(crate) fn snapshot(&self, unique_hosts: usize, elapsed: Duration) -> CrawlStats {
+        let mut latencies = match self.latencies.lock() {
+            Ok(latencies) => latencies.clone(),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); Vec::new() }
+        };
+        latencies.sort_unstable();
+
+        let mean_latency = match latencies.len() {
+            0 => Duration::ZERO,
+            count => latencies.iter().sum::<Duration>() / count as u32
+        };
+        let p95_latency = match latencies.len() {
+            0 => Duration::ZERO,
+            count => latencies[(count * 95).div_ceil(100) - 1]
+        };
+
+        CrawlStats {
+            total_requests: self.total_requests.load(Ordering::Relaxed),
+            response_body_bytes: self.response_body_bytes.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            unique_hosts: unique_hosts as u64,
+            mean_latency,
+            p95_latency,
+            elapsed
+        }
+    }
+}
@@ -1,43 +1,337 @@
-use std::hash::Hash;
+use std::{collections::HashSet, fmt, fs, hash::{Hash, Hasher}, path::Path, str::FromStr};
 
-use crate::web::host::Host;
+use reqwest::{header::HeaderMap, Client};
+use serde::{Deserialize, Serialize};
+use static_assertions::assert_impl_all;
+use tracing::warn;
+use url::Url;
 
-/// A crawl target
-#[derive(Debug, Eq, Clone, Hash)]
+use crate::{crawler::{auth::TargetAuth, CrawlerError}, web::{host::Host, http}};
+
+/// The scheme a target's pages are requested over
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default, Serialize, Deserialize)]
+pub enum PreferredScheme {
+    Https,
+    Http,
+    #[default]
+    Auto // HTTPS, falling back to HTTP if the target's main page can't be connected to over HTTPS
+}
+
+impl PreferredScheme {
+    /// Returns the URL scheme, HTTPS for Auto
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Https | Self::Auto => "https",
+            Self::Http => "http"
+        }
+    }
+}
+
+/// A crawl target, identified by its host and scheme. A target also covers the subdomains of its registered domain,
+/// so those aren't crawled as targets of their own when found during a crawl.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrawlTarget {
-    host: Host // The target host
+    host: Host, // The target host
+    scheme: PreferredScheme,
+    path_prefix: Option<String>, // Only the pages whose paths start with this are crawled, e.g. "/blog/"
+    max_depth: Option<usize>,    // Pages more links than this away from the target's seed URLs are skipped
+    max_pages: Option<usize>,    // No more than this many of the target's pages are crawled
+    #[serde(default)]
+    priority: i32,               // Targets with a higher priority are crawled sooner, 0 by default
+    #[serde(skip)]
+    headers: Option<HeaderMap>, // Sent with the target's requests, overriding the crawler's default headers of the same name
+    #[serde(skip)]
+    auth: Option<TargetAuth> // Sent with the requests to the target's host only, never saved
+}
+
+impl PartialEq for CrawlTarget {
+    fn eq(&self, other: &Self) -> bool {
+        self.host == other.host && self.scheme == other.scheme
+    }
+}
+
+impl Eq for CrawlTarget {}
+
+impl Hash for CrawlTarget {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.host.hash(state);
+        self.scheme.hash(state);
+    }
+}
+
+assert_impl_all!(CrawlTarget: Send, Sync);
+
+/// Returns the registered domain of a host, its last two labels such as example.com for blog.example.com.
+/// IP addresses are their own registered domain.
+pub fn registered_domain(host: &Host) -> String {
+    match host {
+        Host::Domain(domain_name) => format!("{}.{}", domain_name.domain(), domain_name.tld()),
+        Host::Ipv4(ip) => ip.to_string(),
+        Host::Ipv6(ip) => ip.to_string()
+    }
 }
 
 impl CrawlTarget {
     pub fn new(host: Host) -> CrawlTarget {
+        CrawlTarget {
+            host,
+            scheme: PreferredScheme::Auto,
+            path_prefix: None,
+            max_depth: None,
+            max_pages: None,
+            priority: 0,
+            headers: None,
+            auth: None
+        }
+    }
 
-        match host
-        {
-            Host::Domain(host) => {
-                CrawlTarget {
-                    host: Host::Domain(host), 
-                }
-            },
+    /// Create a crawl target whose pages are requested over the given scheme
+    pub fn with_scheme(host: Host, scheme: PreferredScheme) -> CrawlTarget {
+        CrawlTarget { scheme, ..CrawlTarget::new(host) }
+    }
 
-            Host::Ipv4(ip) => CrawlTarget {
-                host: Host::Ipv4(ip), 
-            },
+    /// Create a crawl target limited to the pages under a path, e.g. "/docs/", which is crawled from instead of the main page
+    pub fn with_prefix(host: Host, prefix: &str) -> CrawlTarget {
+        let path_prefix = if prefix.starts_with('/') { prefix.to_owned() } else { format!("/{}", prefix) };
 
-            Host::Ipv6(ip) => CrawlTarget {
-                host: Host::Ipv6(ip), 
-            }
-        }
+        CrawlTarget { path_prefix: Some(path_prefix), ..CrawlTarget::new(host) }
+    }
 
+    /// Create a crawl target whose pages are only crawled up to a number of links away from its seed URLs,
+    /// unless the crawler's maximum depth is lower
+    pub fn with_max_depth(host: Host, max_depth: usize) -> CrawlTarget {
+        CrawlTarget { max_depth: Some(max_depth), ..CrawlTarget::new(host) }
+    }
+
+    /// Create a crawl target of which at most a number of pages are crawled
+    pub fn with_max_pages(host: Host, max_pages: usize) -> CrawlTarget {
+        CrawlTarget { max_pages: Some(max_pages), ..CrawlTarget::new(host) }
+    }
+
+    /// Create a crawl target which is crawled before the targets with a lower priority, which is 0 by default
+    pub fn with_priority(host: Host, priority: i32) -> CrawlTarget {
+        CrawlTarget { priority, ..CrawlTarget::new(host) }
+    }
+
+    /// Create a crawl target whose requests are sent with HTTP Basic authentication
+    pub fn with_basic_auth(host: Host, username: &str, password: Option<&str>) -> CrawlTarget {
+        let auth = TargetAuth::Basic { username: username.to_owned(), password: password.map(str::to_owned) };
+
+        CrawlTarget { auth: Some(auth), ..CrawlTarget::new(host) }
+    }
+
+    /// Create a crawl target whose requests are sent with a bearer token, e.g. an API token
+    pub fn with_bearer_token(host: Host, token: &str) -> CrawlTarget {
+        CrawlTarget { auth: Some(TargetAuth::Bearer(token.to_owned())), ..CrawlTarget::new(host) }
+    }
+
+    /// Returns whether the other target is covered by this one, being on the same registered domain over the same scheme,
+    /// e.g. blog.example.com by example.com
+    pub fn covers(&self, other: &CrawlTarget) -> bool {
+        registered_domain(&self.host) == registered_domain(&other.host) && self.scheme == other.scheme
     }
 
     /// Returns the host of the crawl target
     pub fn host(&self) -> &Host {
         &self.host
     }
+
+    /// Returns the scheme the target's pages are requested over
+    pub fn scheme(&self) -> PreferredScheme {
+        self.scheme
+    }
+
+    /// Returns the path the target's pages are limited to, if any
+    pub fn path_prefix(&self) -> Option<&str> {
+        self.path_prefix.as_deref()
+    }
+
+    /// Returns the number of links from the target's seed URLs its pages are crawled up to, if limited
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Returns the number of the target's pages crawled at most, if limited
+    pub fn max_pages(&self) -> Option<usize> {
+        self.max_pages
+    }
+
+    /// Returns the priority of the target, those with a higher one being crawled sooner
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Crawl the target before the targets with a lower priority
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    /// Returns whether the URL's path is within the target's path prefix, if it has one
+    pub fn in_scope(&self, url: &Url) -> bool {
+        self.path_prefix.as_ref().is_none_or(|path_prefix| url.path().starts_with(path_prefix.as_str()))
+    }
+
+    /// Returns the credentials sent with the target's requests, if any
+    pub fn auth(&self) -> Option<&TargetAuth> {
+        self.auth.as_ref()
+    }
+
+    /// Returns the headers sent with the target's requests instead of the crawler's default ones, if any
+    pub fn headers(&self) -> Option<&HeaderMap> {
+        self.headers.as_ref()
+    }
+
+    /// Send the headers with the target's requests, taking precedence over the crawler's default headers of the same name
+    pub fn set_headers(&mut self, headers: HeaderMap) {
+        self.headers = Some(headers);
+    }
+
+    /// Create a crawl target for the host of a URL
+    pub fn from_url(url: &Url) -> Result<CrawlTarget, CrawlerError> {
+        url.host()
+            .and_then(|host| Host::try_from(host).ok())
+            .map(CrawlTarget::new)
+            .ok_or_else(|| CrawlerError::Other(format!("{} has no host to crawl", url)))
+    }
+
+    /// Create crawl targets from domain names or URLs, returning the targets and the inputs which could not be parsed
+    pub fn from_domain_list(domains: &[&str]) -> (Vec<CrawlTarget>, Vec<(String, CrawlerError)>) {
+        let mut targets = Vec::new();
+        let mut failures = Vec::new();
+
+        for domain in domains {
+            let domain = domain.trim();
+            if domain.is_empty() { continue; }
+
+            match CrawlTarget::from_str(domain) {
+                Ok(target) => targets.push(target),
+                Err(error) => failures.push((domain.to_owned(), error))
+            }
+        }
+
+        (targets, failures)
+    }
+
+    /// Create crawl targets from a file with a domain name or URL on each line, reporting the lines which could not be parsed
+    pub fn from_domain_file(path: &Path) -> Result<Vec<CrawlTarget>, CrawlerError> {
+        let domains = fs::read_to_string(path)
+            .map_err(|error| CrawlerError::wrap(&format!("Failed to read targets from {}", path.display()), error))?;
+
+        let (targets, failures) = CrawlTarget::from_domain_list(&domains.lines().collect::<Vec<&str>>());
+        for (_, error) in failures {
+            warn!("{}", error);
+        }
+
+        Ok(targets)
+    }
+
+    /// Fetch a robots.txt file and create a crawl target for each unique host referenced by its `Sitemap:` directives
+    pub async fn targets_from_robots_txt(robots_url: Url, client: &Client) -> Result<Vec<CrawlTarget>, CrawlerError> {
+        let response = http::get_url(client, robots_url.clone(), None)
+            .await
+            .map_err(CrawlerError::from_reqwest)?;
+
+        if !response.status().is_success() {
+            return Err(CrawlerError::HttpStatus { url: robots_url, status_code: response.status().as_u16() });
+        }
+
+        let robots_txt = response
+            .text()
+            .await
+            .map_err(CrawlerError::from_reqwest)?;
+
+        let mut hosts: HashSet<Host> = HashSet::new();
+        let mut targets = Vec::new();
+
+        for line in robots_txt.lines() {
+            // Directive names are case-insensitive and comments start with a '#'
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((directive, value)) = line.split_once(':') else { continue; };
+            if !directive.trim().eq_ignore_ascii_case("sitemap") { continue; }
+
+            // Sitemap URLs may be relative to the robots.txt file
+            let Ok(sitemap_url) = robots_url.join(value.trim()) else { continue; };
+            let Some(host) = sitemap_url.host() else { continue; };
+            let Ok(host) = Host::try_from(host) else { continue; };
+
+            if hosts.insert(host.clone()) {
+                targets.push(CrawlTarget::new(host));
+            }
+        }
+
+        Ok(targets)
+    }
 }
 
-impl PartialEq for CrawlTarget {
-    fn eq(&self, other: &Self) -> bool {
-        self.host == other.host
+/// A crawl target as written in a targets configuration file, with the host given as a domain name, IP address or URL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TargetConfig {
+    pub host: String,
+    #[serde(default)]
+    pub scheme: PreferredScheme,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<TargetAuth>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_pages: Option<usize>,
+    #[serde(default)]
+    pub priority: i32
+}
+
+impl TryFrom<TargetConfig> for CrawlTarget {
+    type Error = CrawlerError;
+
+    fn try_from(target_config: TargetConfig) -> Result<Self, Self::Error> {
+        let target = CrawlTarget::from_str(&target_config.host)?;
+        let path_prefix = target_config.path_prefix
+            .map(|prefix| if prefix.starts_with('/') { prefix } else { format!("/{}", prefix) });
+
+        Ok(CrawlTarget {
+            scheme: target_config.scheme,
+            path_prefix,
+            max_depth: target_config.max_depth,
+            max_pages: target_config.max_pages,
+            priority: target_config.priority,
+            auth: target_config.auth,
+            ..target
+        })
+    }
+}
+
+impl From<&CrawlTarget> for TargetConfig {
+    /// The configuration of the target, without the headers which can't be written in configuration files
+    fn from(target: &CrawlTarget) -> Self {
+        TargetConfig {
+            host: target.host.to_string(),
+            scheme: target.scheme,
+            path_prefix: target.path_prefix.clone(),
+            auth: target.auth.clone(),
+            max_depth: target.max_depth,
+            max_pages: target.max_pages,
+            priority: target.priority
+        }
+    }
+}
+
+impl FromStr for CrawlTarget {
+    type Err = CrawlerError;
+
+    /// Parse a domain name, IP address or URL, those without a scheme being treated as HTTPS hosts
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let url = if s.contains("://") { Url::parse(s) } else { Url::parse(&format!("https://{}", s)) };
+
+        url.ok()
+            .and_then(|url| CrawlTarget::from_url(&url).ok())
+            .ok_or_else(|| CrawlerError::Other(format!("Invalid target domain: {}", s)))
+    }
+}
+
+impl fmt::Display for CrawlTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.host)
     }
 }
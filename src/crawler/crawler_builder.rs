@@ -0,0 +1,251 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use reqwest::{header::HeaderMap, Certificate};
+use static_assertions::assert_impl_all;
+use url::Url;
+
+use super::{
+    crawl_mode::CrawlMode,
+    crawl_target::CrawlTarget,
+    crawler_config::CrawlerConfig,
+    http_cache::HttpCacheConfig,
+    link::LinkSelectors,
+    observer::CrawlObserver,
+    proxy::ProxyConfig,
+    rate_limit::DomainRateLimiter,
+    redirect::RedirectLog,
+    scope::ScopePolicy,
+    Crawler,
+    CrawlerError
+};
+
+/// Builds a crawler, starting from the default configuration unless given another.
+/// The configuration is validated, the link selectors compiled and the web client set up once, by `build`.
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use cherna_vdovitsa::crawler::{crawl_target::CrawlTarget, crawler_builder::CrawlerBuilder};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let crawler = CrawlerBuilder::new()
+///     .add_target("example.com".parse::<CrawlTarget>()?)
+///     .max_concurrent_tasks(16)
+///     .request_timeout(Duration::from_secs(10))
+///     .user_agent("my-crawler/1.0")
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct CrawlerBuilder {
+    config: CrawlerConfig
+}
+
+/// The crawler builder by the name the crawler is known by
+pub type VdovitsaBuilder = CrawlerBuilder;
+
+assert_impl_all!(CrawlerBuilder: Send, Sync);
+
+impl CrawlerBuilder {
+    /// Create a builder with the default configuration
+    pub fn new() -> CrawlerBuilder {
+        CrawlerBuilder::default()
+    }
+
+    /// Create a builder with the configuration, which the builder's other methods change
+    pub fn from_config(config: CrawlerConfig) -> CrawlerBuilder {
+        CrawlerBuilder { config }
+    }
+
+    /// Returns the configuration built so far
+    pub fn config(&self) -> &CrawlerConfig {
+        &self.config
+    }
+
+    /// Change any option of the configuration, including those without a builder method
+    pub fn configure(mut self, configure: impl FnOnce(&mut CrawlerConfig)) -> CrawlerBuilder {
+        configure(&mut self.config);
+        self
+    }
+
+    /// Crawl the target, along with those already added
+    pub fn add_target(mut self, target: CrawlTarget) -> CrawlerBuilder {
+        self.config.initial_targets.insert(target);
+        self
+    }
+
+    /// Crawl the targets, along with those already added
+    pub fn targets(mut self, targets: impl IntoIterator<Item = CrawlTarget>) -> CrawlerBuilder {
+        self.config.initial_targets.extend(targets);
+        self
+    }
+
+    /// Crawl the URL instead of the main page of its target, which is added if needed
+    pub fn seed_url(mut self, url: Url) -> CrawlerBuilder {
+        if !self.config.seed_urls.contains(&url) { self.config.seed_urls.push(url); }
+        self
+    }
+
+    pub fn scope_policy(mut self, scope_policy: ScopePolicy) -> CrawlerBuilder {
+        self.config.scope_policy = scope_policy;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> CrawlerBuilder {
+        self.config.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn default_headers(mut self, default_headers: HeaderMap) -> CrawlerBuilder {
+        self.config.default_headers = default_headers;
+        self
+    }
+
+    pub fn request_timeout(mut self, request_timeout: Duration) -> CrawlerBuilder {
+        self.config.request_timeout = Some(request_timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> CrawlerBuilder {
+        self.config.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    pub fn follow_redirects(mut self, follow_redirects: bool) -> CrawlerBuilder {
+        self.config.follow_redirects = follow_redirects;
+        self
+    }
+
+    pub fn max_redirects(mut self, max_redirects: usize) -> CrawlerBuilder {
+        self.config.max_redirects = max_redirects;
+        self
+    }
+
+    pub fn max_concurrent_tasks(mut self, max_concurrent_tasks: usize) -> CrawlerBuilder {
+        self.config.max_concurrent_tasks = max_concurrent_tasks;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: usize) -> CrawlerBuilder {
+        self.config.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn politeness_delay(mut self, politeness_delay: Duration) -> CrawlerBuilder {
+        self.config.politeness_delay = Some(politeness_delay);
+        self
+    }
+
+    pub fn respect_robots_txt(mut self, respect_robots_txt: bool) -> CrawlerBuilder {
+        self.config.respect_robots_txt = respect_robots_txt;
+        self
+    }
+
+    pub fn allow_private_hosts(mut self, allow_private_hosts: bool) -> CrawlerBuilder {
+        self.config.allow_private_hosts = allow_private_hosts;
+        self
+    }
+
+    /// Send the requests through the proxy, except those to its exempted hosts
+    pub fn proxy(mut self, proxy: ProxyConfig) -> CrawlerBuilder {
+        self.config.proxy = Some(proxy);
+        self
+    }
+
+    /// Request the pages through the HTTP cache, reusing the responses their caching headers allow
+    pub fn http_cache(mut self, http_cache: HttpCacheConfig) -> CrawlerBuilder {
+        self.config.http_cache = Some(http_cache);
+        self
+    }
+
+    pub fn tls_verify(mut self, tls_verify: bool) -> CrawlerBuilder {
+        self.config.tls_verify = tls_verify;
+        self
+    }
+
+    /// Trust the certificate alongside the system's root certificates, e.g. that of an internal certificate authority
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> CrawlerBuilder {
+        self.config.root_certificates.push(certificate);
+        self
+    }
+
+    pub fn cookie_store(mut self, cookie_store: bool) -> CrawlerBuilder {
+        self.config.cookie_store = cookie_store;
+        self
+    }
+
+    pub fn record_cookies(mut self, record_cookies: bool) -> CrawlerBuilder {
+        self.config.record_cookies = record_cookies;
+        self
+    }
+
+    /// Follow the elements matching the CSS selectors as links, by the attribute paired with each, e.g. ("div[data-href]", "data-href")
+    pub fn link_selectors(mut self, link_selectors: Vec<(String, String)>) -> CrawlerBuilder {
+        self.config.link_selectors = link_selectors;
+        self
+    }
+
+    /// Record how long each crawled page took in the crawl report's timings
+    pub fn timing_enabled(mut self, timing_enabled: bool) -> CrawlerBuilder {
+        self.config.timing_enabled = timing_enabled;
+        self
+    }
+
+    /// Let the targets take turns requesting pages, so that they all make progress even if one has many pages queued
+    pub fn rotate_targets(mut self, rotate_targets: bool) -> CrawlerBuilder {
+        self.config.rotate_targets = rotate_targets;
+        self
+    }
+
+    pub fn parse_csp_headers(mut self, parse_csp_headers: bool) -> CrawlerBuilder {
+        self.config.parse_csp_headers = parse_csp_headers;
+        self
+    }
+
+    pub fn crawl_mode(mut self, crawl_mode: CrawlMode) -> CrawlerBuilder {
+        self.config.crawl_mode = crawl_mode;
+        self
+    }
+
+    /// Notify the observer before and after every page request, and of the pages and targets skipped
+    pub fn observer(mut self, observer: impl CrawlObserver + 'static) -> CrawlerBuilder {
+        self.config.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Save the crawled pages to the SQLite database at the path
+    pub fn db_path(mut self, db_path: impl Into<PathBuf>) -> CrawlerBuilder {
+        self.config.db_path = db_path.into();
+        self
+    }
+
+    /// Build the crawler, failing with the first problem found if the configuration is invalid,
+    /// e.g. `CrawlerError::InvalidSelector` for a link selector which isn't valid CSS
+    pub fn build(self) -> Result<Crawler, CrawlerError> {
+        let (config, link_selectors) = self.validated()?;
+
+        let redirect_log = Arc::new(RedirectLog::default());
+        let client = Crawler::build_client(&config, Arc::clone(&redirect_log))?;
+        let initial_targets = config.initial_targets.clone();
+
+        Ok(Crawler::with_shared_state(Arc::new(config), client, redirect_log, Arc::new(DomainRateLimiter::new()), link_selectors, initial_targets))
+    }
+
+    /// Returns the configuration and its compiled link selectors, or the first problem found with the configuration
+    pub(crate) fn validated(self) -> Result<(CrawlerConfig, LinkSelectors), CrawlerError> {
+        if let Err(errors) = self.config.validate() {
+            if let Some(error) = errors.into_iter().next() {
+                return Err(error);
+            }
+        }
+
+        let link_selectors = LinkSelectors::compile(&self.config.link_selectors)?;
+        Ok((self.config, link_selectors))
+    }
+}
+
+impl From<CrawlerConfig> for CrawlerBuilder {
+    fn from(config: CrawlerConfig) -> Self {
+        CrawlerBuilder::from_config(config)
+    }
+}
@@ -1,11 +1,445 @@
-use std::{collections::HashSet, path::PathBuf};
+use std::{collections::{HashMap, HashSet}, fs::File, io::BufReader, path::{Path, PathBuf}, sync::Arc, time::{Duration, Instant}};
 
-use crate::CrawlTarget;
+use reqwest::{header::{HeaderMap, HeaderName, HeaderValue}, Certificate};
+use scraper::Selector;
+use static_assertions::assert_impl_all;
+use tracing::warn;
 
+use url::Url;
+
+use crate::{util::csv::CsvReader, web::{http::{RetryPolicy, TlsVersion}, user_agent::BrowserUA}};
+
+use super::{
+    CrawlerError,
+    content_type::ContentTypeAction,
+    crawl_mode::CrawlMode,
+    crawl_target::CrawlTarget,
+    error_handler::ErrorHandler,
+    filter::UrlFilter,
+    http_cache::HttpCacheConfig,
+    middleware::CrawlMiddleware,
+    observer::CrawlObserver,
+    proxy::ProxyConfig,
+    redirect::CrossDomainRedirectPolicy,
+    scope::ScopePolicy,
+    target_hook::TargetHook,
+    target_priority::PriorityFn,
+    url_transform::{QueryDeduplication, UrlTransform},
+};
+
+/// The default maximum size of a page's response headers
+pub const DEFAULT_MAX_RESPONSE_HEADER_SIZE: usize = 32 * 1024;
+
+/// The default maximum number of links sent from a crawled page to its target at once
+pub const DEFAULT_URL_BATCH_SIZE: usize = 100;
+
+/// The default time robots.txt files are cached for before being fetched again
+pub const DEFAULT_ROBOTS_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The default time the addresses of domain names are cached for before being resolved again
+pub const DEFAULT_DNS_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The default time requests may take to complete before failing
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The default time connecting to a host may take before the request fails
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The default time idle connections are kept open for reuse, as in `reqwest::ClientBuilder::pool_idle_timeout`
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// The default maximum number of redirects followed for a single request
+pub const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+/// The maximum depth of the pages crawled by `Crawler::crawl_with_seed_discovery` by default
+pub const SEED_DISCOVERY_MAX_DEPTH: usize = 3;
+
+/// The maximum number of pages of each target discovered by `Crawler::crawl_with_seed_discovery`
+pub const SEED_DISCOVERY_MAX_PAGES: usize = 100;
+
+/// The default user agent the crawler identifies itself with
+pub const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// The default maximum number of pages requested, and of targets crawled, at once
+pub const DEFAULT_MAX_CONCURRENT_TASKS: usize = 64;
+
+/// The least number of batches of links found on pages buffered for their target before the pages wait to send more,
+/// unless configured otherwise. More are buffered if more pages are requested at once.
+pub const DEFAULT_LINK_CHANNEL_BUFFER: usize = 64;
+
+/// The least number of new targets found buffered before the targets finding them wait to send more, unless configured
+/// otherwise. More are buffered if more targets are crawled at once.
+pub const DEFAULT_TARGET_CHANNEL_BUFFER: usize = 64;
+
+/// The default number of crawl events kept for subscribers which haven't received them yet
+pub const DEFAULT_EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// The default CSS selector of the elements whose attributes are followed as links
+pub const DEFAULT_LINK_SELECTOR: &str = "a";
+
+/// The default attribute holding the URLs of the elements followed as links
+pub const DEFAULT_LINK_ATTRIBUTE: &str = "href";
+
+/// The query parameters added by UTM campaign tracking
+pub const UTM_PARAMS: [&str; 5] = ["utm_source", "utm_medium", "utm_campaign", "utm_term", "utm_content"];
+
+/// Common ad click, newsletter and referral tracking query parameters, besides the UTM ones
+pub const TRACKING_PARAMS: [&str; 12] = [
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "twclid", "mc_eid", "mc_cid", "ref", "affiliate_id"
+];
 
 #[derive(Debug)]
 pub struct CrawlerConfig {
-    pub initial_targets: HashSet<CrawlTarget>,
-    pub crawl_subdomains: bool,
-    pub db_path: PathBuf
-}
\ No newline at end of file
+    pub initial_targets: HashSet<CrawlTarget>, // None by default
+    pub seed_urls: Vec<Url>, // Crawled instead of the main pages of the targets they belong to, which are added if needed; none by default
+    pub scope_policy: ScopePolicy, // Which other hosts linked to are crawled as new targets, only the targets themselves by default
+    pub user_agent: String, // The user agent sent with every request, `cherna_vdovitsa/<version>` by default
+    pub default_headers: HeaderMap, // Headers sent with every request, unless a target overrides them; none by default
+    pub request_timeout: Option<Duration>, // Requests which take longer than this to complete fail, 30 seconds by default
+    pub connect_timeout: Option<Duration>, // Requests which take longer than this to connect to their host fail, 10 seconds by default
+    pub crawl_deadline: Option<Instant>, // The whole crawl is stopped once this time has passed, running until it's done by default
+    pub follow_redirects: bool, // Whether redirects are followed, rather than recorded and crawled as links; followed by default
+    pub max_redirects: usize, // Requests redirected more times than this fail, 10 by default
+    pub ipv4_only: bool, // Whether hosts are only resolved to and connected to over IPv4, rather than both by default
+    pub ipv6_only: bool, // Whether hosts are only resolved to and connected to over IPv6, rather than both by default
+    pub proxy: Option<ProxyConfig>, // The proxy requests are sent through, instead of directly by default
+    pub http_cache: Option<HttpCacheConfig>, // The HTTP cache pages are requested through, following their caching headers; none by default
+    pub max_concurrent_tasks: usize, // The maximum number of pages requested, and of targets crawled, at once, 64 by default
+    pub max_connections_per_host: Option<usize>, // The maximum number of pages of a host requested at once, each holding a connection to it; any number by default
+    pub tcp_keepalive: Option<Duration>, // How often TCP keepalive probes are sent on open connections, as in `reqwest::ClientBuilder::tcp_keepalive`; never by default
+    pub pool_idle_timeout: Option<Duration>, // Idle connections are closed after this long, as in `reqwest::ClientBuilder::pool_idle_timeout`; 90 seconds by default
+    pub db_path: PathBuf, // `crawl.db` by default
+    pub max_depth: Option<usize>, // Pages more links than this away from the seed URLs are skipped, none by default
+    pub max_total_bytes: Option<u64>, // The whole crawl is stopped once it has transferred this many bytes, never by default
+    pub max_query_params: usize, // URLs with more query parameters than this are skipped, 10 by default
+    pub excluded_query_params: Vec<String>, // Query parameters removed from URLs before they are crawled, none by default
+    pub max_response_time: Option<Duration>, // Pages whose server takes longer than this to send their response headers are skipped, none by default
+    pub path_blocklist: Vec<String>, // URLs whose path starts with any of these prefixes are skipped, none by default
+    pub url_filter: UrlFilter, // URLs not matching its allow patterns, or matching its deny patterns, are skipped; none by default
+    pub observer: Option<Arc<dyn CrawlObserver>>, // Notified before and after every page request, none by default
+    pub cross_domain_redirect_policy: CrossDomainRedirectPolicy, // How redirects to unrelated domains are handled, followed by default
+    pub page_rank_threshold: Option<f64>, // Pages with a lower estimated PageRank (1 being average) are crawled last, none by default
+    pub retry_policy: RetryPolicy, // Which failed page requests are retried before the error handler decides about them, network errors and 429, 500, 502, 503 and 504 responses up to 3 times by default
+    pub error_handler: ErrorHandler, // Decides what to do about failed page requests, skipping them by default
+    pub fail_fast_on_error: bool, // Whether the whole crawl is stopped, failing with the error, once any page is given up on; not by default
+    pub tls_min_version: Option<TlsVersion>, // Connections using an older TLS version are refused, any by default
+    pub fail_on_old_tls: bool, // Whether pages served over too old TLS are errors for the error handler, rather than skipped by default
+    pub tls_verify: bool, // Whether the servers' certificates are verified, which only self-signed test servers may need turned off; verified by default
+    pub root_certificates: Vec<Certificate>, // Trusted alongside the system's root certificates, e.g. an internal certificate authority; none by default
+    pub content_type_map: HashMap<String, ContentTypeAction>, // What to do with pages by MIME type prefix, instead of only parsing HTML by default
+    pub max_response_header_size: usize, // Pages whose response headers are larger than this many bytes are errors for the error handler, 32 KiB by default
+    pub max_body_bytes: Option<usize>, // Only this many bytes of a response body are read, and larger bodies are reported; any by default
+    pub middleware: Vec<Box<dyn CrawlMiddleware>>, // Processes every page request and response, in order; none by default
+    pub extract_resource_hints: bool, // Whether to record the preload, prefetch, preconnect and dns-prefetch links of pages; not by default
+    pub url_transforms: Vec<UrlTransform>, // Applied in order to URLs before they are deduplicated, none by default
+    pub query_deduplication: QueryDeduplication, // Whether the order of URLs' query parameters, or their whole query strings, are ignored when deduplicating them; their order by default
+    pub link_selectors: Vec<(String, String)>, // The CSS selectors of the elements followed as links, with the attribute holding each one's URL; `a` and `href` by default
+    pub url_batch_size: usize, // The maximum number of links found on a page that are processed at once, 100 by default
+    pub link_channel_buffer: Option<usize>, // The number of batches of links buffered for each target, any number if zero, or as many as the concurrent tasks but at least 64 if not set, as by default
+    pub target_channel_buffer: Option<usize>, // The number of new targets found buffered for the crawl, or as many as the concurrent tasks but at least 64 if not set, as by default
+    pub politeness_delay: Option<Duration>, // The minimum time between requests to the same host, only as long as robots.txt asks by default
+    pub respect_robots_txt: bool, // Whether the rules and crawl delays of the hosts' robots.txt files are followed, as they are by default
+    pub allow_private_hosts: bool, // Whether hosts with loopback, link-local, private or multicast addresses are requested, e.g. on an intranet; not by default
+    pub respect_nofollow: bool, // Whether rel="nofollow" links, and the links of pages with an X-Robots-Tag: nofollow header, are skipped, as they are by default
+    pub cookie_store: bool, // Whether the cookies set by responses are kept and sent with later requests, like a browser's; not by default
+    pub record_cookies: bool, // Whether the cookies sent and received for each page are recorded in the crawl report, not by default
+    pub timing_enabled: bool, // Whether how long each crawled page took is recorded in the crawl report, not by default
+    pub rotate_targets: bool, // Whether the targets take turns requesting pages, rather than pages being requested in the order they're queued by default
+    pub parse_csp_headers: bool, // Whether the hosts allowed by the pages' Content-Security-Policy headers are crawled, as the scope policy allows; not by default
+    pub use_sitemap: bool, // Whether targets crawled from their main page also start from the pages listed in their sitemaps, as they do by default
+    pub crawl_mode: CrawlMode, // Whether the links of the crawled pages are followed, as by default, or only the pages listed in sitemaps crawled
+    pub robots_cache_ttl: Duration, // How long a host's robots.txt is cached before being fetched again, a day by default
+    pub dns_cache_ttl: Duration, // How long the addresses of domain names compared with IP address hosts are cached, 5 minutes by default
+    pub pre_crawl_hook: Option<TargetHook>, // Run before crawling each target, which is skipped if it fails; none by default
+    pub post_crawl_hook: Option<TargetHook>, // Run after all pages of a target have been crawled, none by default
+    pub priority_fn: Option<PriorityFn>, // Assigns the targets found during the crawl their priority by host; none by default, giving them the default priority
+    pub event_channel_capacity: usize, // The number of crawl events kept for subscribers which haven't received them yet, 1024 by default
+    #[cfg(feature = "dns-over-https")]
+    pub dns_over_https: Option<url::Url> // The DNS-over-HTTPS server used for resolving host names, instead of the system's resolver by default
+}
+
+assert_impl_all!(CrawlerConfig: Send, Sync);
+
+impl CrawlerConfig {
+    /// The default configuration of `Crawler::crawl_with_seed_discovery`: the default one with subdomains crawled,
+    /// but only up to 3 links away from the seed URLs
+    pub fn seed_discovery() -> CrawlerConfig {
+        CrawlerConfig {
+            scope_policy: ScopePolicy::RelatedHosts,
+            max_depth: Some(SEED_DISCOVERY_MAX_DEPTH),
+            ..Default::default()
+        }
+    }
+
+    /// Remove the UTM tracking parameters from URLs before they are crawled
+    pub fn strip_utm_params(&mut self) {
+        self.exclude_query_params(&UTM_PARAMS);
+    }
+
+    /// Remove the UTM and other common tracking parameters from URLs before they are crawled
+    pub fn strip_common_tracking_params(&mut self) {
+        self.exclude_query_params(&UTM_PARAMS);
+        self.exclude_query_params(&TRACKING_PARAMS);
+    }
+
+    /// Add query parameters to the excluded ones, unless they already are
+    fn exclude_query_params(&mut self, params: &[&str]) {
+        for param in params {
+            if !self.excluded_query_params.iter().any(|excluded| excluded == param) {
+                self.excluded_query_params.push(param.to_string());
+            }
+        }
+    }
+
+    /// Add the URLs in a column of a CSV file with a header row as seed URLs, e.g. an analytics export, returning how many were added.
+    /// The file is read one record at a time, and invalid URLs are reported and skipped.
+    pub fn seed_urls_from_csv(&mut self, path: &Path, url_column: &str) -> Result<usize, CrawlerError> {
+        let file = File::open(path)
+            .map_err(|error| CrawlerError::wrap(&format!("Failed to read seed URLs from {}", path.display()), error))?;
+        let mut records = CsvReader::new(BufReader::new(file));
+
+        let header = records
+            .next()
+            .transpose()
+            .map_err(|error| CrawlerError::wrap(&format!("Failed to read seed URLs from {}", path.display()), error))?
+            .unwrap_or_default();
+
+        let Some(column) = header
+            .iter()
+            .position(|name| name.trim_start_matches('\u{feff}').trim() == url_column)
+        else {
+            return Err(CrawlerError::Other(format!("{} has no {} column", path.display(), url_column)));
+        };
+
+        let mut added = 0;
+        for (line, record) in records.enumerate() {
+            let record = record
+                .map_err(|error| CrawlerError::wrap(&format!("Failed to read seed URLs from {}", path.display()), error))?;
+            let Some(url) = record.get(column).map(|url| url.trim()).filter(|url| !url.is_empty()) else { continue; };
+
+            match Url::parse(url) {
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {
+                    self.seed_urls.push(url);
+                    added += 1;
+                }
+                _ => warn!("Skipping invalid seed URL on record {} of {}: {}", line + 2, path.display(), url)
+            }
+        }
+
+        Ok(added)
+    }
+
+    /// Send a header with every request, e.g. Accept-Language, alongside any earlier headers of the same name
+    pub fn header(&mut self, name: &str, value: &str) -> Result<(), CrawlerError> {
+        let name = HeaderName::try_from(name)
+            .map_err(|error| CrawlerError::InvalidConfig(format!("Invalid header name {}: {}", name, error)))?;
+        let value = HeaderValue::try_from(value)
+            .map_err(|error| CrawlerError::InvalidConfig(format!("Invalid value of header {}: {}", name, error)))?;
+
+        self.default_headers.append(name, value);
+        Ok(())
+    }
+
+    /// Trust the certificates signed by a certificate authority, e.g. an internal one, alongside the system's root certificates
+    pub fn add_root_certificate(&mut self, certificate: Certificate) {
+        self.root_certificates.push(certificate);
+    }
+
+    /// Identify the crawler as a web browser
+    pub fn user_agent_from_browser(&mut self, browser: BrowserUA) {
+        self.user_agent = browser.to_string();
+    }
+
+    /// Add middleware to the end of the middleware chain
+    pub fn add_middleware(&mut self, middleware: impl CrawlMiddleware + 'static) {
+        self.middleware.push(Box::new(middleware));
+    }
+
+    /// Returns the number of batches of links buffered for each target, the configured one or else at least one per concurrent task
+    pub fn link_channel_buffer_size(&self) -> usize {
+        self.link_channel_buffer.unwrap_or(DEFAULT_LINK_CHANNEL_BUFFER.max(self.max_concurrent_tasks))
+    }
+
+    /// Returns the number of new targets buffered for the crawl, the configured one or else at least one per concurrent task
+    pub fn target_channel_buffer_size(&self) -> usize {
+        self.target_channel_buffer.unwrap_or(DEFAULT_TARGET_CHANNEL_BUFFER.max(self.max_concurrent_tasks))
+    }
+
+    /// Check the configuration for contradicting or unusable options, returning all problems found
+    pub fn validate(&self) -> Result<(), Vec<CrawlerError>> {
+        let mut errors = Vec::new();
+
+        if self.max_response_time.is_some_and(|max_response_time| max_response_time.is_zero()) {
+            errors.push(CrawlerError::InvalidConfig(String::from("A maximum response time of zero skips every page.")));
+        }
+
+        if self.path_blocklist.iter().any(|prefix| prefix.is_empty() || prefix == "/") {
+            errors.push(CrawlerError::InvalidConfig(String::from("The path blocklist blocks every path.")));
+        }
+
+        if self.request_timeout.is_some_and(|request_timeout| request_timeout.is_zero()) {
+            errors.push(CrawlerError::InvalidConfig(String::from("A request timeout of zero fails every request.")));
+        }
+
+        if self.max_connections_per_host == Some(0) {
+            errors.push(CrawlerError::InvalidConfig(String::from("A maximum of zero connections per host sends no requests.")));
+        }
+
+        if self.connect_timeout.is_some_and(|connect_timeout| connect_timeout.is_zero()) {
+            errors.push(CrawlerError::InvalidConfig(String::from("A connect timeout of zero fails every request.")));
+        }
+
+        if self.retry_policy.max_attempts == 0 {
+            errors.push(CrawlerError::InvalidConfig(String::from("A retry policy of zero attempts sends no requests.")));
+        }
+
+        if self.max_body_bytes == Some(0) {
+            errors.push(CrawlerError::InvalidConfig(String::from("A maximum body size of zero reads nothing of any page.")));
+        }
+
+        if self.ipv4_only && self.ipv6_only {
+            errors.push(CrawlerError::InvalidConfig(String::from("Only one of IPv4 and IPv6 can be used exclusively.")));
+        }
+
+        if self.max_total_bytes == Some(0) {
+            errors.push(CrawlerError::InvalidConfig(String::from("A byte budget of zero stops the crawl before it starts.")));
+        }
+
+        if self.max_concurrent_tasks == 0 {
+            errors.push(CrawlerError::InvalidConfig(String::from("At least one page must be allowed to be requested at once.")));
+        }
+
+        if self.event_channel_capacity == 0 {
+            errors.push(CrawlerError::InvalidConfig(String::from("The event channel capacity must be at least 1.")));
+        }
+
+        // Smaller buffers than the number of tasks sending to them at once keep those tasks waiting on each other
+        if let Some(link_channel_buffer) = self.link_channel_buffer.filter(|buffer| *buffer != 0 && *buffer < self.max_concurrent_tasks) {
+            errors.push(CrawlerError::InvalidConfig(format!(
+                "A link channel buffer of {} is smaller than the {} pages requested at once, which would wait for each other to send their links.",
+                link_channel_buffer, self.max_concurrent_tasks
+            )));
+        }
+
+        if let Some(target_channel_buffer) = self.target_channel_buffer.filter(|buffer| *buffer < self.max_concurrent_tasks) {
+            errors.push(CrawlerError::InvalidConfig(format!(
+                "A target channel buffer of {} is smaller than the {} targets crawled at once, which would wait for each other to send the targets they find.",
+                target_channel_buffer, self.max_concurrent_tasks
+            )));
+        }
+
+        if self.url_batch_size == 0 {
+            errors.push(CrawlerError::InvalidConfig(String::from("The URL batch size must be at least 1.")));
+        }
+
+        if self.page_rank_threshold.is_some_and(|threshold| !threshold.is_finite() || threshold < 0.0) {
+            errors.push(CrawlerError::InvalidConfig(String::from("The PageRank threshold must be a non-negative number.")));
+        }
+
+        if self.fail_on_old_tls && self.tls_min_version.is_none() {
+            errors.push(CrawlerError::InvalidConfig(String::from("Failing on old TLS requires a minimum TLS version.")));
+        }
+
+        if self.tls_min_version == Some(TlsVersion::Tls13) {
+            errors.push(CrawlerError::InvalidConfig(String::from("TLS 1.3 is not supported as a minimum TLS version.")));
+        }
+
+        for (selector, _) in &self.link_selectors {
+            if let Err(error) = Selector::parse(selector) {
+                errors.push(CrawlerError::InvalidSelector { selector: selector.clone(), error: error.to_string() });
+            }
+        }
+
+        #[cfg(feature = "dns-over-https")]
+        if self.dns_over_https.as_ref().is_some_and(|server| server.scheme() != "https" || server.host().is_none()) {
+            errors.push(CrawlerError::InvalidConfig(String::from("The DNS-over-HTTPS server must be an HTTPS URL with a host.")));
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+impl Default for CrawlerConfig {
+    /// A configuration with the defaults noted on each field, most importantly:
+    ///
+    /// - The user agent `cherna_vdovitsa/<version>`, from the package's name and version
+    /// - At most 64 pages requested, and 64 targets crawled, at once, rather than 8 requests per target and 4 targets,
+    ///   since a single limit across all targets was set by `max_concurrent_tasks` (synth-256)
+    /// - Requests time out after 30 seconds, rather than 10, which is the connect timeout instead,
+    ///   as set along with `connect_timeout` (synth-264)
+    /// - Network errors and 429, 500, 502, 503 and 504 responses retried up to 3 times with exponential backoff
+    /// - Robots.txt rules and crawl delays respected
+    /// - No politeness delay between requests to the same host
+    /// - Targets given without a scheme crawled over HTTPS, falling back to HTTP, and HTTP links made HTTPS when normalized
+    /// - URL fragments stripped when normalized
+    fn default() -> Self {
+        CrawlerConfig {
+            initial_targets: HashSet::new(),
+            seed_urls: Vec::new(),
+            scope_policy: ScopePolicy::SameHostOnly,
+            user_agent: String::from(DEFAULT_USER_AGENT),
+            default_headers: HeaderMap::new(),
+            request_timeout: Some(DEFAULT_REQUEST_TIMEOUT),
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            crawl_deadline: None,
+            follow_redirects: true,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            ipv4_only: false,
+            ipv6_only: false,
+            proxy: None,
+            http_cache: None,
+            max_concurrent_tasks: DEFAULT_MAX_CONCURRENT_TASKS,
+            max_connections_per_host: None,
+            tcp_keepalive: None,
+            pool_idle_timeout: Some(DEFAULT_POOL_IDLE_TIMEOUT),
+            db_path: PathBuf::from("crawl.db"),
+            max_depth: None,
+            max_total_bytes: None,
+            max_query_params: 10,
+            excluded_query_params: Vec::new(),
+            max_response_time: None,
+            path_blocklist: Vec::new(),
+            url_filter: UrlFilter::default(),
+            observer: None,
+            cross_domain_redirect_policy: CrossDomainRedirectPolicy::default(),
+            page_rank_threshold: None,
+            retry_policy: RetryPolicy::default(),
+            error_handler: ErrorHandler::default(),
+            fail_fast_on_error: false,
+            tls_min_version: None,
+            fail_on_old_tls: false,
+            tls_verify: true,
+            root_certificates: Vec::new(),
+            content_type_map: HashMap::new(),
+            max_response_header_size: DEFAULT_MAX_RESPONSE_HEADER_SIZE,
+            max_body_bytes: None,
+            middleware: Vec::new(),
+            extract_resource_hints: false,
+            url_transforms: Vec::new(),
+            query_deduplication: QueryDeduplication::NormalizeOrder,
+            link_selectors: vec![(String::from(DEFAULT_LINK_SELECTOR), String::from(DEFAULT_LINK_ATTRIBUTE))],
+            url_batch_size: DEFAULT_URL_BATCH_SIZE,
+            link_channel_buffer: None,
+            target_channel_buffer: None,
+            politeness_delay: None,
+            respect_robots_txt: true,
+            allow_private_hosts: false,
+            respect_nofollow: true,
+            cookie_store: false,
+            record_cookies: false,
+            timing_enabled: false,
+            rotate_targets: false,
+            parse_csp_headers: false,
+            use_sitemap: true,
+            crawl_mode: CrawlMode::FullCrawl,
+            robots_cache_ttl: DEFAULT_ROBOTS_CACHE_TTL,
+            dns_cache_ttl: DEFAULT_DNS_CACHE_TTL,
+            pre_crawl_hook: None,
+            post_crawl_hook: None,
+            priority_fn: None,
+            event_channel_capacity: DEFAULT_EVENT_CHANNEL_CAPACITY,
+            #[cfg(feature = "dns-over-https")]
+            dns_over_https: None
+        }
+    }
+}
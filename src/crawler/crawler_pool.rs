@@ -0,0 +1,103 @@
+use std::{collections::HashSet, sync::Arc};
+
+use reqwest::Client;
+use tokio::{sync::Semaphore, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    crawl_report::CrawlReport,
+    crawl_target::CrawlTarget,
+    crawler_builder::CrawlerBuilder,
+    crawler_config::CrawlerConfig,
+    link::LinkSelectors,
+    rate_limit::DomainRateLimiter,
+    redirect::RedirectLog,
+    Crawler,
+    CrawlerError
+};
+
+/// Runs crawl jobs submitted by any number of callers with the same configuration, a limited number of them at once.
+/// The jobs share their web client's connection pool and the spacing of requests to each host,
+/// while each crawls its own targets and deduplicates its own URLs.
+#[derive(Debug)]
+pub struct CrawlerPool {
+    config: Arc<CrawlerConfig>,
+    client: Client,
+    redirect_log: Arc<RedirectLog>, // The redirects followed by the shared web client
+    rate_limiter: Arc<DomainRateLimiter>,
+    link_selectors: LinkSelectors,  // The compiled link selectors of the configuration
+    job_permits: Arc<Semaphore>     // Limits the number of jobs crawling at once
+}
+
+/// A crawl job submitted to a pool, which runs whether or not its result is awaited
+#[derive(Debug)]
+pub struct JobHandle {
+    cancellation_token: CancellationToken,
+    task: JoinHandle<Result<CrawlReport, CrawlerError>>
+}
+
+impl CrawlerPool {
+    /// Create a pool crawling up to `max_jobs` jobs at once with the configuration, whose initial targets are ignored
+    /// while its seed URLs are crawled by every job.
+    /// Fails with the first problem found if the configuration is invalid.
+    pub fn new(config: CrawlerConfig, max_jobs: usize) -> Result<CrawlerPool, CrawlerError> {
+        if max_jobs == 0 {
+            return Err(CrawlerError::InvalidConfig(String::from("At least one job must be allowed to crawl at once.")));
+        }
+
+        let (config, link_selectors) = CrawlerBuilder::from_config(config).validated()?;
+
+        let redirect_log = Arc::new(RedirectLog::default());
+        let client = Crawler::build_client(&config, Arc::clone(&redirect_log))?;
+
+        Ok(CrawlerPool {
+            config: Arc::new(config),
+            client,
+            redirect_log,
+            rate_limiter: Arc::new(DomainRateLimiter::new()),
+            link_selectors,
+            job_permits: Arc::new(Semaphore::new(max_jobs))
+        })
+    }
+
+    /// Queue a crawl of the targets, which starts once fewer jobs than the limit are crawling
+    pub fn submit(&self, targets: HashSet<CrawlTarget>) -> JobHandle {
+        let crawler = Crawler::with_shared_state(
+            Arc::clone(&self.config),
+            self.client.clone(),
+            Arc::clone(&self.redirect_log),
+            Arc::clone(&self.rate_limiter),
+            self.link_selectors.clone(),
+            targets
+        );
+        let cancellation_token = crawler.cancel_token();
+        let job_permits = Arc::clone(&self.job_permits);
+
+        let task = tokio::spawn(async move {
+            // Jobs cancelled while queued finish without crawling anything
+            let _job_permit = tokio::select! {
+                _ = crawler.cancellation_token.cancelled() => return Ok(CrawlReport { cancelled: true, ..Default::default() }),
+                job_permit = job_permits.acquire_owned() => job_permit
+                    .map_err(|error| CrawlerError::Other(format!("Failed to start crawl job: {}", error)))?
+            };
+
+            crawler.crawl().await
+        });
+
+        JobHandle { cancellation_token, task }
+    }
+}
+
+impl JobHandle {
+    /// Wait for the job to finish, returning what it found, or the error which stopped it if failing fast
+    pub async fn result(self) -> Result<CrawlReport, CrawlerError> {
+        self.task
+            .await
+            .map_err(|error| CrawlerError::wrap("The crawl job failed", error))?
+    }
+
+    /// Stop the job, whose result is what it found until then
+    pub fn cancel(&self) {
+        self.cancellation_token.cancel();
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+
+use url::Url;
+
+/// Finds the hosts a page may load content from or connect to, as declared by its Content-Security-Policy headers,
+/// e.g. `connect-src api.example.com` or `script-src https://cdn.example.com/js/`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CspParser;
+
+impl CspParser {
+    /// Returns the main pages of the hosts the policy allows, over HTTPS unless their source names HTTP, and on the port given if any.
+    /// Keywords such as 'self', nonces, hashes, scheme-only sources such as https: and wildcard hosts are ignored.
+    pub fn main_pages(policy: &str) -> HashSet<Url> {
+        policy
+            .split(';')
+            .flat_map(|directive| directive.split_ascii_whitespace().skip(1)) // Skip the directive's name
+            .filter_map(Self::host_source_main_page)
+            .collect()
+    }
+
+    /// Returns the main page of the host of a source, unless the source isn't a host
+    fn host_source_main_page(source: &str) -> Option<Url> {
+        // Keywords, nonces and hashes are quoted, and scheme-only sources don't name a host
+        if source.starts_with('\'') || source.ends_with(':') { return None; }
+
+        let (scheme, host) = match source.split_once("://") {
+            Some((scheme, host)) => (scheme.to_ascii_lowercase(), host),
+            None => (String::from("https"), source)
+        };
+        if scheme != "https" && scheme != "http" { return None; }
+
+        // Drop the path of the source, which only narrows down what's allowed on the host
+        let host = host.split('/').next().unwrap_or_default();
+        if host.is_empty() || host.contains('*') { return None; }
+
+        Url::parse(&format!("{}://{}/", scheme, host)).ok().filter(|url| url.host().is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn main_pages(policy: &str) -> Vec<String> {
+        let mut main_pages: Vec<String> = CspParser::main_pages(policy).into_iter().map(String::from).collect();
+        main_pages.sort();
+        main_pages
+    }
+
+    #[test]
+    fn host_sources_of_every_directive_are_found() {
+        let policy = "default-src 'self'; connect-src api.example.com cdn.example.com; img-src https://images.example.org/photos/";
+
+        assert_eq!(main_pages(policy), ["https://api.example.com/", "https://cdn.example.com/", "https://images.example.org/"]);
+    }
+
+    #[test]
+    fn keywords_nonces_and_hashes_are_ignored() {
+        let policy = "script-src 'self' 'unsafe-inline' 'nonce-r4nd0m' 'sha256-abc123=' 'strict-dynamic'; object-src 'none'";
+
+        assert!(main_pages(policy).is_empty());
+    }
+
+    #[test]
+    fn wildcard_hosts_are_ignored() {
+        assert_eq!(main_pages("img-src * *.example.com https://*.cdn.example.com static.example.com"), ["https://static.example.com/"]);
+    }
+
+    #[test]
+    fn schemes_and_ports_of_sources_are_kept() {
+        let policy = "connect-src http://legacy.example.com:8080/api https://secure.example.com wss://socket.example.com ftp://files.example.com";
+
+        assert_eq!(main_pages(policy), ["http://legacy.example.com:8080/", "https://secure.example.com/"]);
+    }
+
+    #[test]
+    fn scheme_only_sources_are_ignored() {
+        assert!(main_pages("img-src https: data: blob:; font-src HTTP:").is_empty());
+    }
+
+    #[test]
+    fn malformed_directives_are_skipped() {
+        assert!(main_pages("").is_empty());
+        assert!(main_pages(";;  ; connect-src;").is_empty());
+        assert_eq!(main_pages("connect-src https:// https://[::1 api.example.com;;upgrade-insecure-requests"), ["https://api.example.com/"]);
+    }
+
+    #[test]
+    fn repeated_hosts_are_found_once() {
+        assert_eq!(main_pages("script-src cdn.example.com; style-src cdn.example.com https://cdn.example.com/css/"), ["https://cdn.example.com/"]);
+    }
+}
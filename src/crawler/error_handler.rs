@@ -0,0 +1,56 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use url::Url;
+
+use super::CrawlerError;
+
+/// What to do about a failed page request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorAction {
+    Retry { after: Duration }, // Retry the request after a delay, which doubles with each further attempt, as often as the retry policy allows
+    Skip,                      // Give up on the page
+    Abort,                     // Stop crawling the page's target
+    PropagateGlobally,         // Stop the whole crawl
+}
+
+/// A function deciding what to do about a failed request for a URL
+type HandlerFn = dyn Fn(&CrawlerError, &Url) -> ErrorAction + Send + Sync;
+
+/// Decides what to do about the failed page requests of a crawl
+#[derive(Clone)]
+pub struct ErrorHandler(Arc<HandlerFn>);
+
+impl ErrorHandler {
+    pub fn new(handler: impl Fn(&CrawlerError, &Url) -> ErrorAction + Send + Sync + 'static) -> ErrorHandler {
+        ErrorHandler(Arc::new(handler))
+    }
+
+    /// Decide what to do about a failed request for the URL
+    pub fn handle(&self, error: &CrawlerError, url: &Url) -> ErrorAction {
+        (self.0)(error, url)
+    }
+}
+
+impl Default for ErrorHandler {
+    /// Skips pages with error status codes, failed requests or too large responses, transient failures such as
+    /// server and network errors having already been retried by the crawler's retry policy, and stops crawling targets
+    /// served over too old TLS
+    fn default() -> Self {
+        ErrorHandler::new(|error, _| match error {
+            CrawlerError::HttpStatus { .. } | CrawlerError::Request { .. } => ErrorAction::Skip,
+            CrawlerError::TlsVersionTooOld { .. } => ErrorAction::Abort,
+            CrawlerError::Other(_)
+            | CrawlerError::InvalidConfig(_)
+            | CrawlerError::InvalidSelector { .. }
+            | CrawlerError::Wrapped { .. }
+            | CrawlerError::ConfigParse { .. }
+            | CrawlerError::ResponseTooLarge { .. } => ErrorAction::Skip
+        })
+    }
+}
+
+impl fmt::Debug for ErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ErrorHandler")
+    }
+}
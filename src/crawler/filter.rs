@@ -0,0 +1,31 @@
+use regex::Regex;
+
+/// Filters the URLs to crawl by regular expressions matched against the whole URL
+#[derive(Debug, Clone, Default)]
+pub struct UrlFilter {
+    pub allow_patterns: Vec<Regex>, // If there are any, URLs must match one of them
+    pub deny_patterns: Vec<Regex>   // URLs matching any of these are skipped
+}
+
+impl UrlFilter {
+    pub fn new(allow_patterns: Vec<Regex>, deny_patterns: Vec<Regex>) -> UrlFilter {
+        UrlFilter { allow_patterns, deny_patterns }
+    }
+
+    /// Returns why the URL is filtered out, if it is
+    pub fn rejection(&self, url: &str) -> Option<String> {
+        if !self.allow_patterns.is_empty() && !self.allow_patterns.iter().any(|pattern| pattern.is_match(url)) {
+            return Some(String::from("URL does not match any allow pattern"));
+        }
+
+        self.deny_patterns
+            .iter()
+            .find(|pattern| pattern.is_match(url))
+            .map(|pattern| format!("URL matches deny pattern {}", pattern))
+    }
+
+    /// Returns whether the URL may be crawled
+    pub fn is_allowed(&self, url: &str) -> bool {
+        self.rejection(url).is_none()
+    }
+}
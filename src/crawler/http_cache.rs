@@ -0,0 +1,369 @@
+use std::{collections::HashMap, fmt::Debug, fs, io::ErrorKind, path::PathBuf, sync::{Arc, Mutex}, time::{Duration, SystemTime}};
+
+use hyper::http;
+use reqwest::{header::{self, HeaderMap, HeaderName, HeaderValue}, Response, ResponseBuilderExt, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::error;
+use url::Url;
+
+/// Keeps the responses of an HTTP cache, by the URL requested
+pub trait CacheManager: Debug + Send + Sync {
+    /// Returns the response cached for the URL, if any
+    fn get(&self, url: &Url) -> Option<CachedResponse>;
+
+    /// Cache the response for the URL, replacing any response cached for it
+    fn put(&self, url: &Url, response: CachedResponse);
+
+    /// Remove the response cached for the URL, if any
+    fn delete(&self, url: &Url);
+}
+
+/// A response kept by an HTTP cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResponse {
+    pub status_code: u16,
+    pub headers: Vec<(String, String)>, // The names and values of the response headers, in order
+    pub body: Vec<u8>,
+    pub stored_at: SystemTime           // When the response was received, or last confirmed unchanged by the server
+}
+
+/// Keeps the cached responses in memory, for as long as the manager is kept
+#[derive(Debug, Default)]
+pub struct MemoryCacheManager(Mutex<HashMap<Url, CachedResponse>>);
+
+/// Keeps the cached responses in a directory, so that later crawls can use them too.
+/// Each response is kept in a JSON file of its status code and headers, named by the SHA-256 hash of its URL, next to a file of its body.
+#[derive(Debug, Clone)]
+pub struct FileCacheManager {
+    directory: PathBuf // Created once the first response is cached
+}
+
+/// The status code and headers of a cached response, as kept by `FileCacheManager`
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    status_code: u16,
+    headers: Vec<(String, String)>,
+    stored_at: SystemTime
+}
+
+/// An HTTP cache the pages are requested through, following the Cache-Control, Expires, ETag and Last-Modified headers of
+/// their responses like a browser's private cache. Fresh pages aren't requested again, and stale ones are only downloaded
+/// again if the server reports them changed. Only successful responses to requests which weren't redirected are cached.
+#[derive(Debug, Clone)]
+pub struct HttpCacheConfig {
+    pub manager: Arc<dyn CacheManager> // Where the cached responses are kept
+}
+
+/// What the cache has for a page about to be requested
+pub(crate) enum CacheLookup {
+    Fresh(CachedResponse), // Used without requesting the page
+    Stale(CachedResponse), // Used if the server reports the page unchanged since
+    Miss
+}
+
+impl HttpCacheConfig {
+    pub fn new(manager: impl CacheManager + 'static) -> HttpCacheConfig {
+        HttpCacheConfig { manager: Arc::new(manager) }
+    }
+
+    /// Cache the responses in memory
+    pub fn in_memory() -> HttpCacheConfig {
+        HttpCacheConfig::new(MemoryCacheManager::default())
+    }
+
+    /// Cache the responses in files in the directory
+    pub fn filesystem(directory: impl Into<PathBuf>) -> HttpCacheConfig {
+        HttpCacheConfig::new(FileCacheManager::new(directory))
+    }
+
+    /// Returns the cached response for the URL, and whether it's still fresh
+    pub(crate) fn lookup(&self, url: &Url) -> CacheLookup {
+        match self.manager.get(url) {
+            Some(cached) if cached.is_fresh(SystemTime::now()) => CacheLookup::Fresh(cached),
+            Some(cached) if cached.has_validators() => CacheLookup::Stale(cached),
+            Some(_) => {
+                self.manager.delete(url);
+                CacheLookup::Miss
+            }
+            None => CacheLookup::Miss
+        }
+    }
+
+    /// Update the cache with the response to the request for the URL, given the stale response it was revalidating if any.
+    /// Returns the response to crawl, which is the cached one if the server reported it unchanged, and whether it is.
+    /// Bodies longer than the maximum aren't cached, but are still returned to be crawled.
+    pub(crate) async fn update(&self, url: &Url, mut response: Response, stale: Option<CachedResponse>, max_body_bytes: Option<usize>) -> Result<(Response, bool), reqwest::Error> {
+        if let (StatusCode::NOT_MODIFIED, Some(mut cached)) = (response.status(), stale.clone()) {
+            cached.revalidated(response.headers());
+            self.manager.put(url, cached.clone());
+            return Ok((cached.to_response(url), true));
+        }
+
+        if response.url() != url || !is_storable(response.status(), response.headers()) {
+            if stale.is_some() { self.manager.delete(url); }
+            return Ok((response, false));
+        }
+
+        // Read one byte more than the maximum to tell whether the body is longer
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            body.extend_from_slice(&chunk);
+            if max_body_bytes.is_some_and(|max_body_bytes| body.len() > max_body_bytes) { break; }
+        }
+
+        let cached = CachedResponse {
+            status_code: response.status().as_u16(),
+            headers: response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str().to_owned(), value.to_str().ok()?.to_owned())))
+                .collect(),
+            body,
+            stored_at: SystemTime::now()
+        };
+
+        if max_body_bytes.is_some_and(|max_body_bytes| cached.body.len() > max_body_bytes) {
+            self.manager.delete(url);
+        } else {
+            self.manager.put(url, cached.clone());
+        }
+
+        Ok((cached.to_response(url), false))
+    }
+}
+
+impl CachedResponse {
+    /// Returns the value of a header, comparing names case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(header, _)| header.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the response can be used without asking the server, i.e. it's been cached for less than its freshness lifetime
+    pub fn is_fresh(&self, now: SystemTime) -> bool {
+        let age = self.header("age").and_then(|age| age.trim().parse().ok()).map(Duration::from_secs).unwrap_or_default();
+        let current_age = age + now.duration_since(self.stored_at).unwrap_or_default();
+
+        self.freshness_lifetime() > current_age
+    }
+
+    /// How long after it was generated the response is fresh for, from its max-age directive or else its Expires header.
+    /// Responses without either, or with a no-cache directive, are always revalidated rather than assumed fresh for a while.
+    fn freshness_lifetime(&self) -> Duration {
+        let directives = cache_control(self.header("cache-control"));
+        if directives.contains_key("no-cache") { return Duration::ZERO; }
+
+        if let Some(max_age) = directives.get("max-age") {
+            return max_age.as_deref().and_then(|max_age| max_age.parse().ok()).map(Duration::from_secs).unwrap_or_default();
+        }
+
+        let date = self.header("date").and_then(|date| httpdate::parse_http_date(date).ok()).unwrap_or(self.stored_at);
+        self.header("expires")
+            .map(|expires| httpdate::parse_http_date(expires).ok().and_then(|expires| expires.duration_since(date).ok()).unwrap_or_default())
+            .unwrap_or_default()
+    }
+
+    /// Whether the server can be asked if the response changed, through its ETag or Last-Modified header
+    fn has_validators(&self) -> bool {
+        self.header("etag").is_some() || self.header("last-modified").is_some()
+    }
+
+    /// Add the headers asking the server to only send the page if it changed since the response
+    pub(crate) fn add_conditional_headers(&self, headers: &mut HeaderMap) {
+        let validators = [(header::IF_NONE_MATCH, self.header("etag")), (header::IF_MODIFIED_SINCE, self.header("last-modified"))];
+
+        for (name, value) in validators {
+            if let Some(Ok(value)) = value.map(HeaderValue::from_str) {
+                headers.insert(name, value);
+            }
+        }
+    }
+
+    /// Update the response with the headers of the server's 304 response confirming it's unchanged
+    fn revalidated(&mut self, headers: &HeaderMap) {
+        let updated = |name: &str| name.eq_ignore_ascii_case("age") || headers.contains_key(name);
+        self.headers.retain(|(name, _)| !updated(name) || name.eq_ignore_ascii_case("content-length"));
+
+        for (name, value) in headers {
+            if name == header::CONTENT_LENGTH { continue; }
+            if let Ok(value) = value.to_str() {
+                self.headers.push((name.as_str().to_owned(), value.to_owned()));
+            }
+        }
+
+        self.stored_at = SystemTime::now();
+    }
+
+    /// Returns the response as if it had been received for the URL
+    pub(crate) fn to_response(&self, url: &Url) -> Response {
+        let mut response = http::Response::builder()
+            .status(self.status_code)
+            .url(url.clone());
+
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+                response = response.header(name, value);
+            }
+        }
+
+        match response.body(self.body.clone()) {
+            Ok(response) => Response::from(response),
+            Err(error) => {
+                error!("Failed to rebuild the cached response of {}: {}", url, error);
+                Response::from(http::Response::new(self.body.clone()))
+            }
+        }
+    }
+}
+
+/// Whether a response may be cached: it succeeded, it doesn't forbid being stored or vary in unknown ways,
+/// and it's either fresh for a while or has validators to revalidate it with
+fn is_storable(status_code: StatusCode, headers: &HeaderMap) -> bool {
+    let header = |name: HeaderName| headers.get(name).and_then(|value| value.to_str().ok());
+
+    let directives = cache_control(header(header::CACHE_CONTROL));
+    let fresh_for_a_while = directives.contains_key("max-age") || header(header::EXPIRES).is_some();
+    let has_validators = header(header::ETAG).is_some() || header(header::LAST_MODIFIED).is_some();
+
+    status_code == StatusCode::OK
+        && !directives.contains_key("no-store")
+        && header(header::VARY).map(str::trim) != Some("*")
+        && (fresh_for_a_while || has_validators)
+}
+
+/// Parse a Cache-Control header into its lowercase directives and their values, e.g. "max-age=60" into "max-age" and "60"
+fn cache_control(cache_control: Option<&str>) -> HashMap<String, Option<String>> {
+    cache_control
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .map(|directive| match directive.split_once('=') {
+            Some((name, value)) => (name.trim().to_ascii_lowercase(), Some(value.trim().trim_matches('"').to_owned())),
+            None => (directive.to_ascii_lowercase(), None)
+        })
+        .collect()
+}
+
+impl CacheManager for MemoryCacheManager {
+    fn get(&self, url: &Url) -> Option<CachedResponse> {
+        match self.0.lock() {
+            Ok(responses) => responses.get(url).cloned(),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); None }
+        }
+    }
+
+    fn put(&self, url: &Url, response: CachedResponse) {
+        match self.0.lock() {
+            Ok(mut responses) => { responses.insert(url.clone(), response); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    fn delete(&self, url: &Url) {
+        match self.0.lock() {
+            Ok(mut responses) => { responses.remove(url); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+}
+
+impl FileCacheManager {
+    pub fn new(directory: impl Into<PathBuf>) -> FileCacheManager {
+        FileCacheManager { directory: directory.into() }
+    }
+
+    /// Returns the paths of the files of the URL's response and of its body
+    fn paths(&self, url: &Url) -> (PathBuf, PathBuf) {
+        let name: String = Sha256::digest(url.as_str().as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect();
+        (self.directory.join(format!("{}.json", name)), self.directory.join(format!("{}.body", name)))
+    }
+}
+
+impl CacheManager for FileCacheManager {
+    fn get(&self, url: &Url) -> Option<CachedResponse> {
+        let (entry_path, body_path) = self.paths(url);
+
+        let entry = match fs::read(&entry_path) {
+            Ok(entry) => entry,
+            Err(error) if error.kind() == ErrorKind::NotFound => return None,
+            Err(error) => { error!("Failed to read {}: {}", entry_path.display(), error); return None; }
+        };
+        let entry: CacheEntry = match serde_json::from_slice(&entry) {
+            Ok(entry) => entry,
+            Err(error) => { error!("Failed to parse {}: {}", entry_path.display(), error); return None; }
+        };
+        let body = match fs::read(&body_path) {
+            Ok(body) => body,
+            Err(error) => { error!("Failed to read {}: {}", body_path.display(), error); return None; }
+        };
+
+        Some(CachedResponse { status_code: entry.status_code, headers: entry.headers, body, stored_at: entry.stored_at })
+    }
+
+    fn put(&self, url: &Url, response: CachedResponse) {
+        let (entry_path, body_path) = self.paths(url);
+        let entry = CacheEntry { status_code: response.status_code, headers: response.headers, stored_at: response.stored_at };
+
+        let written = fs::create_dir_all(&self.directory)
+            .and_then(|_| fs::write(&body_path, &response.body))
+            .and_then(|_| fs::write(&entry_path, serde_json::to_vec(&entry)?));
+
+        if let Err(error) = written {
+            error!("Failed to cache the response of {} in {}: {}", url, self.directory.display(), error);
+        }
+    }
+
+    fn delete(&self, url: &Url) {
+        let (entry_path, body_path) = self.paths(url);
+
+        for path in [entry_path, body_path] {
+            match fs::remove_file(&path) {
+                Err(error) if error.kind() != ErrorKind::NotFound => error!("Failed to remove {}: {}", path.display(), error),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::CachedResponse;
+
+    fn cached(headers: &[(&str, &str)], stored_at: SystemTime) -> CachedResponse {
+        CachedResponse {
+            status_code: 200,
+            headers: headers.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect(),
+            body: Vec::new(),
+            stored_at
+        }
+    }
+
+    #[test]
+    fn max_age_counts_the_age_the_response_had_when_cached() {
+        let stored_at = SystemTime::now();
+        let response = cached(&[("Cache-Control", "public, max-age=60"), ("Age", "50")], stored_at);
+
+        assert!(response.is_fresh(stored_at + Duration::from_secs(5)));
+        assert!(!response.is_fresh(stored_at + Duration::from_secs(15)));
+    }
+
+    #[test]
+    fn expires_is_relative_to_the_date_of_the_response() {
+        let date = SystemTime::UNIX_EPOCH + Duration::from_secs(1_445_412_480);
+        let response = cached(&[("Date", &httpdate::fmt_http_date(date)), ("Expires", &httpdate::fmt_http_date(date + Duration::from_secs(60)))], SystemTime::now());
+
+        assert!(response.is_fresh(response.stored_at + Duration::from_secs(30)));
+        assert!(!response.is_fresh(response.stored_at + Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn no_cache_and_missing_freshness_information_make_responses_stale() {
+        let stored_at = SystemTime::now();
+
+        assert!(!cached(&[("Cache-Control", "no-cache, max-age=60")], stored_at).is_fresh(stored_at));
+        assert!(!cached(&[("ETag", "\"v1\"")], stored_at).is_fresh(stored_at));
+    }
+}
@@ -0,0 +1,76 @@
+use std::hash::{Hash, Hasher};
+
+use scraper::{ElementRef, Html, Selector};
+use url::Url;
+
+use super::CrawlerError;
+
+/// A link found on a crawled page, with the visible text of its element
+#[derive(Debug, Clone)]
+pub struct Link {
+    pub href: String,               // The absolute URL linked to, without its fragment
+    pub anchor_text: Option<String> // The text of the link's element with its whitespace collapsed, unless it has none
+}
+
+impl Link {
+    /// Create a link to the URL with the text of its element, which is left out if it's only whitespace
+    pub fn new(url: &Url, anchor_text: &str) -> Link {
+        let anchor_text = anchor_text.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+        Link {
+            href: url.to_string(),
+            anchor_text: (!anchor_text.is_empty()).then_some(anchor_text)
+        }
+    }
+}
+
+/// A link found without an element, such as a canonical URL or a redirect's destination
+impl From<&Url> for Link {
+    fn from(url: &Url) -> Link {
+        Link { href: url.to_string(), anchor_text: None }
+    }
+}
+
+/// The compiled CSS selectors of the elements followed as links, with the attribute holding each one's URL
+#[derive(Debug, Clone, Default)]
+pub struct LinkSelectors(Vec<(Selector, String)>);
+
+impl LinkSelectors {
+    /// Compile the selectors, failing with the first one which isn't valid CSS
+    pub fn compile(selectors: &[(String, String)]) -> Result<LinkSelectors, CrawlerError> {
+        selectors
+            .iter()
+            .map(|(selector, attribute)| {
+                Selector::parse(selector)
+                    .map(|compiled| (compiled, attribute.clone()))
+                    .map_err(|error| CrawlerError::InvalidSelector { selector: selector.clone(), error: error.to_string() })
+            })
+            .collect::<Result<Vec<(Selector, String)>, CrawlerError>>()
+            .map(LinkSelectors)
+    }
+
+    /// Returns the elements of the document matching the selectors which have their attribute, with its value
+    pub fn select<'a>(&'a self, document: &'a Html) -> impl Iterator<Item = (ElementRef<'a>, &'a str)> {
+        self.0.iter().flat_map(move |(selector, attribute)| {
+            document
+                .select(selector)
+                .filter_map(move |element| element.value().attr(attribute).map(|value| (element, value)))
+        })
+    }
+}
+
+// Links are the same if they point to the same URL, so that a page linking to a URL several times only sends it once
+
+impl PartialEq for Link {
+    fn eq(&self, other: &Link) -> bool {
+        self.href == other.href
+    }
+}
+
+impl Eq for Link {}
+
+impl Hash for Link {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.href.hash(state);
+    }
+}
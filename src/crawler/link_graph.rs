@@ -0,0 +1,62 @@
+use std::collections::{HashMap, HashSet};
+
+/// The probability of following a link rather than jumping to a random page
+const DAMPING_FACTOR: f64 = 0.85;
+
+/// An in-memory graph of the links between the pages of a target, used for estimating their PageRank.
+/// Ranks are normalised so that the average page has a rank of 1.
+#[derive(Debug, Default)]
+pub struct LinkGraph {
+    outbound_links: HashMap<String, HashSet<String>>,
+    inbound_links: HashMap<String, HashSet<String>>,
+    ranks: HashMap<String, f64>
+}
+
+impl LinkGraph {
+    pub fn new() -> LinkGraph {
+        LinkGraph::default()
+    }
+
+    /// Add links from a page to other pages
+    pub fn add_links(&mut self, page: &str, links: impl IntoIterator<Item = String>) {
+        for link in links {
+            if link == page { continue; }
+
+            self.inbound_links.entry(link.clone()).or_default().insert(page.to_owned());
+            self.outbound_links.entry(page.to_owned()).or_default().insert(link);
+        }
+    }
+
+    /// Run a single PageRank iteration over the whole graph, starting from the current ranks
+    pub fn update_ranks(&mut self) {
+        let pages: HashSet<&String> = self.outbound_links.keys().chain(self.inbound_links.keys()).collect();
+
+        let ranks = pages
+            .into_iter()
+            .map(|page| (page.clone(), self.estimated_rank(page)))
+            .collect();
+
+        self.ranks = ranks;
+    }
+
+    /// Estimate the rank of a page from the current ranks of the pages linking to it
+    pub fn estimated_rank(&self, page: &str) -> f64 {
+        let inbound_rank: f64 = self
+            .inbound_links
+            .get(page)
+            .into_iter()
+            .flatten()
+            .map(|linking_page| {
+                let outbound_link_count = self.outbound_links.get(linking_page).map_or(1, HashSet::len);
+                self.rank(linking_page) / outbound_link_count as f64
+            })
+            .sum();
+
+        (1.0 - DAMPING_FACTOR) + DAMPING_FACTOR * inbound_rank
+    }
+
+    /// Returns the rank of a page as of the last iteration
+    pub fn rank(&self, page: &str) -> f64 {
+        self.ranks.get(page).copied().unwrap_or(1.0)
+    }
+}
@@ -0,0 +1,79 @@
+use std::{fmt::Debug, time::Duration};
+
+use async_trait::async_trait;
+use reqwest::{header::HeaderMap, Method, StatusCode};
+use tracing::info;
+use url::Url;
+
+use super::{rate_limit::DomainRateLimiter, CrawlerError};
+
+/// A page request about to be sent
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub url: Url,
+    pub method: Method,
+    pub headers: HeaderMap // Headers added to the client's defaults
+}
+
+/// The response to a page request whose body is going to be processed
+#[derive(Debug, Clone)]
+pub struct ResponseContext {
+    pub url: Url,          // The URL of the response, after following redirects
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>
+}
+
+/// Processes page requests before they are sent, and their responses before they are parsed.
+/// Middleware is run in the order it was added, and an error stops the page from being crawled.
+#[async_trait]
+pub trait CrawlMiddleware: Debug + Send + Sync {
+    async fn before_request(&self, _request: &mut RequestContext) -> Result<(), CrawlerError> {
+        Ok(())
+    }
+
+    async fn after_response(&self, _response: &mut ResponseContext) -> Result<(), CrawlerError> {
+        Ok(())
+    }
+}
+
+/// Logs every page request and response
+#[derive(Debug, Default)]
+pub struct LoggingMiddleware;
+
+#[async_trait]
+impl CrawlMiddleware for LoggingMiddleware {
+    async fn before_request(&self, request: &mut RequestContext) -> Result<(), CrawlerError> {
+        info!("{} {}", request.method, request.url);
+        Ok(())
+    }
+
+    async fn after_response(&self, response: &mut ResponseContext) -> Result<(), CrawlerError> {
+        info!("{} {} ({} bytes)", response.status, response.url, response.body.len());
+        Ok(())
+    }
+}
+
+/// Delays requests so that each host is sent at most one request per interval
+#[derive(Debug)]
+pub struct RateLimitMiddleware {
+    interval: Duration,
+    rate_limiter: DomainRateLimiter
+}
+
+impl RateLimitMiddleware {
+    pub fn new(interval: Duration) -> RateLimitMiddleware {
+        RateLimitMiddleware {
+            interval,
+            rate_limiter: DomainRateLimiter::new()
+        }
+    }
+}
+
+#[async_trait]
+impl CrawlMiddleware for RateLimitMiddleware {
+    async fn before_request(&self, request: &mut RequestContext) -> Result<(), CrawlerError> {
+        self.rate_limiter.wait(&request.url, self.interval).await;
+        Ok(())
+    }
+}
@@ -1,285 +1,2959 @@
+pub mod analysis;
+pub mod auth;
+pub mod checkpoint;
+pub mod content_type;
+pub mod cookies;
+pub mod crawl_error;
+pub mod crawl_mode;
+pub mod crawl_event;
+pub mod crawl_report;
+pub mod crawl_result;
+pub mod crawl_stats;
 pub mod crawl_target;
+pub mod csp;
+pub mod crawler_builder;
 pub mod crawler_config;
+pub mod crawler_pool;
+pub mod error_handler;
+pub mod filter;
+pub mod http_cache;
+pub mod link;
+pub mod link_graph;
+pub mod middleware;
+pub mod ndjson;
+pub mod normalize;
+pub mod observer;
+pub mod proxy;
+pub mod rate_limit;
+pub mod redirect;
+pub mod robots;
+pub mod robots_report;
+pub mod scope;
+pub mod sitemap;
+pub mod skip_reason;
+pub mod sqlite;
+#[cfg(feature = "ssl-certificates")]
+pub mod ssl_certificates;
+pub mod target_hook;
+pub mod target_priority;
+pub mod target_rotation;
+pub mod url_transform;
+pub mod validation;
+#[cfg(feature = "warc")]
+pub mod warc;
 
 use core::fmt;
-use std::fs::{self, File};
-use std::sync::{Arc, Mutex};
-use std::{collections::HashSet};
+use std::hash::{Hash, Hasher};
+use std::cmp::{Ordering as CmpOrdering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+use std::sync::{atomic::{AtomicU64, AtomicUsize, Ordering}, Arc, Mutex, RwLock};
+use std::time::Instant;
 
-use reqwest::{header, Client, Url};
+use reqwest::{header::{self, HeaderMap, HeaderValue}, Client, Method, Response, StatusCode, Url};
+use url::Position;
 use rusqlite::{params, Connection};
 use scraper::{Html, Selector};
-use tokio::sync::mpsc;
+use static_assertions::assert_impl_all;
+use tokio::{sync::{broadcast, mpsc, OwnedSemaphorePermit, Semaphore, SemaphorePermit}, task::{self, AbortHandle, JoinSet}};
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, field, info, instrument, warn, Span};
 
 use crate::{
-    util::ChannelPacket,
+    dns::{dns_cache::DnsCache, domain_name::DomainName, public_resolver::PublicAddressResolver},
+    util::{packet_channel, ChannelPacket, PacketSender},
     web::{
         host::{Host, HostRelationship},
-        http,
+        http::{self, HttpVersion, TlsVersion},
+        url_relation::{compare_urls, UrlRelation},
     },
 };
-use crawl_target::CrawlTarget;
+use cookies::CookieJar;
+use crawl_error::{CrawlError, CrawlErrorKind};
+use crawl_event::CrawlEvent;
+use crawl_report::{CrawlReport, TargetSummary, UrlTiming};
+use crawl_result::{CrawlResult, ResourceHint};
+use crawl_stats::CrawlStatsCounters;
+use crawl_target::{CrawlTarget, PreferredScheme, TargetConfig};
+use crawler_builder::CrawlerBuilder;
+use csp::CspParser;
+use link::{Link, LinkSelectors};
+use link_graph::LinkGraph;
 
-use self::crawler_config::CrawlerConfig;
+use self::{
+    analysis::login_walls::LoginWallIndicator,
+    auth::{AuthRequiredTarget, TargetAuth},
+    checkpoint::ResumedTarget,
+    content_type::ContentTypeAction,
+    crawler_config::CrawlerConfig,
+    error_handler::ErrorAction,
+    http_cache::CacheLookup,
+    middleware::{RequestContext, ResponseContext},
+    redirect::{CrossDomainRedirectPolicy, RedirectLog},
+    rate_limit::DomainRateLimiter,
+    robots::RobotsCache,
+    scope::ScopePolicy,
+    sitemap::SitemapParser,
+    skip_reason::SkipReason,
+    target_rotation::{RotationPermit, TargetRotation},
+};
+
+/// The number of crawled pages between PageRank iterations of a target's link graph
+const PAGE_RANK_ITERATION_INTERVAL: usize = 16;
+
+/// The relations of <link> elements pointing to other pages rather than to resources of the page
+const PAGE_LINK_RELATIONS: [&str; 4] = ["canonical", "alternate", "next", "prev"];
+
+/// The links found on a crawled page, sent from crawl_url to crawl_target
+#[derive(Debug)]
+struct PageLinks {
+    page_url: Url,
+    canonical_url: Option<Url>,
+    final_url: Option<Url>,         // The URL on the same host the page redirected to, which was crawled along with it
+    redirect_target: Option<Host>, // A host redirected to, which should be crawled as a new target
+    links: HashSet<Link>,
+    depth: usize // The number of link hops from a seed URL to the page
+}
+
+/// A target waiting for fewer targets than the limit to be crawled, ordered so that the highest priority
+/// and then the earliest found is crawled first
+#[derive(Debug)]
+struct PendingTarget {
+    target: CrawlTarget,
+    seed_urls: Vec<Url>,
+    new_targets: PacketSender<CrawlTarget>, // Keeps the crawl running until the target has been crawled
+    order: u64 // The number of targets which were queued before this one
+}
+
+impl PendingTarget {
+    fn key(&self) -> (i32, Reverse<u64>) {
+        (self.target.priority(), Reverse(self.order))
+    }
+}
+
+impl PartialEq for PendingTarget {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for PendingTarget {}
+
+impl PartialOrd for PendingTarget {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PendingTarget {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// The URLs queued but not yet fetched with their depths, by target host and deduplication key
+type Frontier = HashMap<Host, HashMap<String, (Url, usize)>>;
+
+/// The state shared by the tasks of a crawl
+#[derive(Debug)]
+struct CrawlState {
+    pending_urls: AtomicUsize,                             // URLs queued for crawling, including those being crawled
+    active_tasks: AtomicUsize,                             // crawl_url tasks in flight
+    redirect_log: Arc<RedirectLog>,                        // The redirect chains followed by the web client
+    robots_cache: RobotsCache,                             // The robots.txt files of the crawled hosts
+    robots_disallowed_urls: Mutex<Vec<Url>>,               // The URLs skipped as their host's robots.txt disallows them
+    sitemap_urls: Mutex<HashSet<Url>>,                     // The URLs listed in the sitemaps fetched
+    rate_limiter: Arc<DomainRateLimiter>,                  // Spaces out the requests to each host, shared by the crawlers of a pool
+    task_permits: Semaphore,                               // Limits the number of pages requested at once
+    target_rotation: Option<Arc<TargetRotation>>,          // Limits the number of pages requested at once instead, taking turns between targets, if rotating them
+    host_permits: Mutex<HashMap<Host, Arc<Semaphore>>>,    // Limits the number of pages requested from each host at once, if configured
+    target_permits: Arc<Semaphore>,                        // Limits the number of targets crawled at once
+    first_error: Mutex<Option<CrawlerError>>,              // The error which stopped the crawl, if failing fast
+    errors: Mutex<Vec<CrawlError>>,                        // The errors of the pages given up on
+    discovered_urls: Mutex<HashMap<Host, HashSet<String>>>, // The deduplicated URLs queued for crawling, by target host
+    target_auth: Mutex<HashMap<Host, TargetAuth>>,          // The credentials sent to the targets requiring them
+    target_headers: Mutex<HashMap<Host, HeaderMap>>,        // The headers overriding the default ones for the targets setting them
+    events: broadcast::Sender<CrawlEvent>,                  // Publishes the events of the crawl to its subscribers
+    target_summaries: Mutex<HashMap<Host, TargetSummary>>,  // The data transferred for each target host
+    total_bytes: AtomicU64,                                 // The data transferred across all targets
+    stats: CrawlStatsCounters,                              // The requests made so far
+    resources: Mutex<HashSet<String>>,                      // The scripts, stylesheets, images and other resources of the crawled pages
+    link_graph: Mutex<HashMap<Url, Vec<Link>>>,             // The outbound links of the crawled pages
+    cookie_jar: CookieJar,                                  // The cookies sent with page requests
+    link_selectors: LinkSelectors,                          // The compiled selectors of the elements followed as links
+    cookies: Mutex<HashMap<Url, Vec<(String, String)>>>,    // The cookies sent and received for each page, if recorded
+    timings: Mutex<Vec<UrlTiming>>,                         // How long each crawled page took, if recorded
+    status_codes: Mutex<HashMap<Url, u16>>,                 // The status codes of the crawled pages, in sitemap-only mode
+    redirect_chains: Mutex<HashMap<Url, Vec<Url>>>,         // The redirect hops of the redirected pages
+    prefetched_pages: Mutex<HashMap<Url, Response>>,        // The responses of the main pages requested to choose their target's scheme, crawled instead of requesting them again
+    dns_cache: DnsCache,                                    // The addresses of the domain names compared with IP addresses
+    frontier: Mutex<Frontier>,                              // The URLs queued but not yet fetched
+    resumed_targets: Mutex<HashMap<Host, ResumedTarget>>    // The progress of the targets restored from a checkpoint, until they're crawled
+}
+
+impl CrawlState {
+    fn new(redirect_log: Arc<RedirectLog>, rate_limiter: Arc<DomainRateLimiter>, link_selectors: LinkSelectors, config: &CrawlerConfig) -> CrawlState {
+        CrawlState {
+            pending_urls: AtomicUsize::new(0),
+            active_tasks: AtomicUsize::new(0),
+            redirect_log,
+            robots_cache: RobotsCache::default(),
+            robots_disallowed_urls: Mutex::new(Vec::new()),
+            sitemap_urls: Mutex::new(HashSet::new()),
+            rate_limiter,
+            task_permits: Semaphore::new(config.max_concurrent_tasks),
+            target_rotation: config.rotate_targets.then(|| Arc::new(TargetRotation::new(config.max_concurrent_tasks))),
+            host_permits: Mutex::new(HashMap::new()),
+            link_selectors,
+            target_permits: Arc::new(Semaphore::new(config.max_concurrent_tasks)),
+            first_error: Mutex::new(None),
+            errors: Mutex::new(Vec::new()),
+            discovered_urls: Mutex::new(HashMap::new()),
+            target_auth: Mutex::new(HashMap::new()),
+            target_headers: Mutex::new(HashMap::new()),
+            events: broadcast::channel(config.event_channel_capacity).0,
+            target_summaries: Mutex::new(HashMap::new()),
+            total_bytes: AtomicU64::new(0),
+            stats: CrawlStatsCounters::default(),
+            resources: Mutex::new(HashSet::new()),
+            link_graph: Mutex::new(HashMap::new()),
+            cookie_jar: CookieJar::default(),
+            cookies: Mutex::new(HashMap::new()),
+            timings: Mutex::new(Vec::new()),
+            status_codes: Mutex::new(HashMap::new()),
+            redirect_chains: Mutex::new(HashMap::new()),
+            prefetched_pages: Mutex::new(HashMap::new()),
+            dns_cache: DnsCache::new(config.dns_cache_ttl),
+            frontier: Mutex::new(HashMap::new()),
+            resumed_targets: Mutex::new(HashMap::new())
+        }
+    }
+
+    fn url_enqueued(&self) {
+        self.pending_urls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn task_spawned(&self) {
+        self.active_tasks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a URL skipped as its host's robots.txt disallows it
+    fn disallowed_by_robots(&self, url: &Url) {
+        match self.robots_disallowed_urls.lock() {
+            Ok(mut disallowed_urls) => disallowed_urls.push(url.clone()),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Record the URLs listed in a sitemap
+    fn sitemap_fetched(&self, urls: impl IntoIterator<Item = Url>) {
+        match self.sitemap_urls.lock() {
+            Ok(mut sitemap_urls) => sitemap_urls.extend(urls),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Record the resources a crawled page refers to
+    fn resources_found(&self, resources: &[Url]) {
+        match self.resources.lock() {
+            Ok(mut found) => found.extend(resources.iter().map(Url::to_string)),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Record the outbound links of a crawled page
+    fn links_found(&self, page_url: &Url, links: &HashSet<Link>) {
+        match self.link_graph.lock() {
+            Ok(mut link_graph) => { link_graph.insert(page_url.clone(), links.iter().cloned().collect()); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Record the cookies sent and received for a page
+    fn cookies_exchanged(&self, page_url: &Url, cookies: Vec<(String, String)>) {
+        match self.cookies.lock() {
+            Ok(mut recorded) => recorded.entry(page_url.clone()).or_default().extend(cookies),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Record the status code a page responded with, only kept when checking the pages of sitemaps
+    fn page_responded(&self, url: &Url, status_code: StatusCode, config: &CrawlerConfig) {
+        if config.crawl_mode.follows_links() { return; }
+
+        match self.status_codes.lock() {
+            Ok(mut status_codes) => { status_codes.insert(url.clone(), status_code.as_u16()); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Record the redirect hops of a page, unless it wasn't redirected
+    fn page_redirected(&self, url: &Url, redirect_chain: &[Url]) {
+        if redirect_chain.is_empty() { return; }
+
+        match self.redirect_chains.lock() {
+            Ok(mut redirect_chains) => { redirect_chains.insert(url.clone(), redirect_chain.to_vec()); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Record how long a crawled page took
+    fn page_timed(&self, timing: UrlTiming) {
+        match self.timings.lock() {
+            Ok(mut timings) => timings.push(timing),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Record a URL queued for crawling for the target, by its deduplication key
+    fn url_discovered(&self, target: &Host, url: &Url, dedup_key: String, depth: usize) {
+        match self.frontier.lock() {
+            Ok(mut frontier) => { frontier.entry(target.clone()).or_default().insert(dedup_key.clone(), (url.clone(), depth)); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        match self.discovered_urls.lock() {
+            Ok(mut discovered_urls) => { discovered_urls.entry(target.clone()).or_default().insert(dedup_key); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        self.publish(CrawlEvent::UrlDiscovered(url.clone()));
+    }
+
+    /// Record the data transferred while crawling a page of the target, returning the total transferred by the crawl
+    fn bytes_transferred(&self, target: &Host, downloaded: u64, uploaded: u64) -> u64 {
+        match self.target_summaries.lock() {
+            Ok(mut target_summaries) => {
+                let summary = target_summaries.entry(target.clone()).or_default();
+                summary.bytes_downloaded += downloaded;
+                summary.bytes_uploaded += uploaded;
+            }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        self.total_bytes.fetch_add(downloaded + uploaded, Ordering::Relaxed) + downloaded + uploaded
+    }
+
+    /// Record a queued URL as being fetched, so that it isn't fetched again when resuming from a checkpoint
+    fn url_visited(&self, url: &Url, config: &CrawlerConfig) {
+        let Some(target) = url.host().and_then(|host| Host::try_from(host).ok()) else { return; };
+        let dedup_key = url_transform::dedup_key(config.query_deduplication, &config.url_transforms, url.as_str());
+
+        match self.frontier.lock() {
+            Ok(mut frontier) => if let Some(urls) = frontier.get_mut(&target) { urls.remove(&dedup_key); },
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Take the progress of a target restored from a checkpoint, recording its URLs queued before as discovered
+    fn resume_target(&self, target: &Host) -> Option<ResumedTarget> {
+        let resumed_target = match self.resumed_targets.lock() {
+            Ok(mut resumed_targets) => resumed_targets.remove(target)?,
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); return None; }
+        };
+
+        match self.discovered_urls.lock() {
+            Ok(mut discovered_urls) => discovered_urls.entry(target.clone()).or_default().extend(resumed_target.crawled_urls.iter().cloned()),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        Some(resumed_target)
+    }
+
+    /// Publish an event to the subscribers, if there are any
+    fn publish(&self, event: CrawlEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Returns the private address of the URL's host if it has one and private hosts aren't allowed, resolving domain names
+    async fn blocked_address(&self, url: &Url, config: &CrawlerConfig) -> Option<IpAddr> {
+        if config.allow_private_hosts { return None; }
+
+        let host = url.host().and_then(|host| Host::try_from(host).ok())?;
+        host.private_address(&self.dns_cache).await
+    }
+
+    /// Returns the credentials to send with requests for the URL, if its host requires them
+    fn auth_for(&self, url: &Url) -> Option<TargetAuth> {
+        let host = url.host().and_then(|host| Host::try_from(host).ok())?;
+
+        match self.target_auth.lock() {
+            Ok(target_auth) => target_auth.get(&host).cloned(),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); None }
+        }
+    }
+
+    /// Wait for a permit to request the page, taking turns with the pages of the other targets if rotating them
+    async fn task_permit(&self, url: &Url) -> Option<TaskPermit<'_>> {
+        match &self.target_rotation {
+            Some(target_rotation) => {
+                let target = url.host().and_then(|host| Host::try_from(host).ok()).map(|host| crawl_target::registered_domain(&host));
+                target_rotation.acquire(&target.unwrap_or_default()).await.map(TaskPermit::Rotated)
+            }
+            None => self.task_permits.acquire().await.ok().map(TaskPermit::Shared)
+        }
+    }
+
+    /// Keep the response of a main page requested to choose its target's scheme, for crawling the page
+    fn page_prefetched(&self, url: Url, response: Response) {
+        match self.prefetched_pages.lock() {
+            Ok(mut prefetched_pages) => { prefetched_pages.insert(url, response); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Returns the response of the page if it was already requested to choose its target's scheme
+    fn take_prefetched_page(&self, url: &Url) -> Option<Response> {
+        match self.prefetched_pages.lock() {
+            Ok(mut prefetched_pages) => prefetched_pages.remove(url),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); None }
+        }
+    }
+
+    /// Returns the permits limiting the requests sent to the URL's host at once, unless they aren't limited
+    fn host_permits(&self, url: &Url, config: &CrawlerConfig) -> Option<Arc<Semaphore>> {
+        let max_connections_per_host = config.max_connections_per_host?;
+        let host = url.host().and_then(|host| Host::try_from(host).ok())?;
+
+        match self.host_permits.lock() {
+            Ok(mut host_permits) => Some(Arc::clone(host_permits.entry(host).or_insert_with(|| Arc::new(Semaphore::new(max_connections_per_host))))),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); None }
+        }
+    }
+
+    /// Returns the headers sent with requests for the URL instead of the default ones, empty unless its host sets any
+    fn headers_for(&self, url: &Url) -> HeaderMap {
+        let Some(host) = url.host().and_then(|host| Host::try_from(host).ok()) else { return HeaderMap::new(); };
+
+        match self.target_headers.lock() {
+            Ok(target_headers) => target_headers.get(&host).cloned().unwrap_or_default(),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); HeaderMap::new() }
+        }
+    }
+
+    /// Record the error of a page given up on
+    fn page_failed(&self, error: CrawlError) {
+        self.stats.page_failed();
+
+        match self.errors.lock() {
+            Ok(mut errors) => errors.push(error),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Record the error which stopped the crawl, unless one already has
+    fn record_error(&self, error: CrawlerError) {
+        self.stats.page_failed();
+
+        match self.first_error.lock() {
+            Ok(mut first_error) => { first_error.get_or_insert(error); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+}
+
+/// A permit to request a page, held until the page has been crawled
+#[allow(dead_code)]
+enum TaskPermit<'a> {
+    Shared(SemaphorePermit<'a>),
+    Rotated(RotationPermit)
+}
+
+/// Marks a crawl_url task as complete when dropped
+struct ActiveTask(Arc<CrawlState>);
+
+impl Drop for ActiveTask {
+    fn drop(&mut self) {
+        self.0.pending_urls.fetch_sub(1, Ordering::Relaxed);
+        self.0.active_tasks.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pub struct Crawler {
+    crawl_targets: Mutex<HashSet<CrawlTarget>>,
+    cancellation_token: CancellationToken, // Cancels the whole crawl
+    target_tokens: Mutex<HashMap<Host, CancellationToken>>, // Cancellation tokens of the targets being crawled
+    target_sender: RwLock<Option<mpsc::Sender<CrawlTarget>>>, // Adds targets to the running crawl, if there is one
+    client: Client,
+    config: Arc<CrawlerConfig>,
+    crawl_state: Arc<CrawlState>,
+}
+
+assert_impl_all!(Crawler: Send, Sync);
+
+impl Crawler {
+    /// Create a Vdovitsa crawler with initial targets, as `CrawlerBuilder::from_config(config).build()` does.
+    /// Fails with the first problem found if the configuration is invalid.
+    pub fn new(config: CrawlerConfig) -> Result<Crawler, CrawlerError> {
+        CrawlerBuilder::from_config(config).build()
+    }
+
+    /// Returns a builder of a crawler with the default configuration
+    pub fn builder() -> CrawlerBuilder {
+        CrawlerBuilder::new()
+    }
+
+    /// Create a crawler of the targets which shares its web client, the redirect log the client records to
+    /// and its rate limiter with other crawlers
+    fn with_shared_state(
+        config: Arc<CrawlerConfig>,
+        client: Client,
+        redirect_log: Arc<RedirectLog>,
+        rate_limiter: Arc<DomainRateLimiter>,
+        link_selectors: LinkSelectors,
+        targets: HashSet<CrawlTarget>
+    ) -> Crawler {
+        let crawl_state = Arc::new(CrawlState::new(redirect_log, rate_limiter, link_selectors, &config));
+
+        Crawler {
+            crawl_targets: Mutex::new(targets),
+            cancellation_token: CancellationToken::new(),
+            target_tokens: Mutex::new(HashMap::new()),
+            target_sender: RwLock::new(None),
+            client,
+            config,
+            crawl_state,
+        }
+    }
+
+    /// Build the web client of a crawler, recording the redirects it follows in the log
+    fn build_client(config: &CrawlerConfig, redirect_log: Arc<RedirectLog>) -> Result<Client, CrawlerError> {
+        // Configure the web client
+        let client_config = Client::builder()
+            .user_agent(config.user_agent.as_str())
+            .default_headers(config.default_headers.clone())
+            .redirect(if config.follow_redirects {
+                redirect::redirect_policy(config.cross_domain_redirect_policy, config.max_redirects, config.allow_private_hosts, redirect_log)
+            } else {
+                reqwest::redirect::Policy::none()
+            });
+
+        // Keep connections alive and reuse them as configured, while the number of requests to each host is limited by its host permits
+        let client_config = client_config
+            .tcp_keepalive(config.tcp_keepalive)
+            .pool_idle_timeout(config.pool_idle_timeout);
+
+        // Give up on requests which take too long
+        let client_config = match config.request_timeout {
+            Some(request_timeout) => client_config.timeout(request_timeout),
+            None => client_config
+        };
+        let client_config = match config.connect_timeout {
+            Some(connect_timeout) => client_config.connect_timeout(connect_timeout),
+            None => client_config
+        };
+
+        // Send all requests through the proxy, except those to its exempted hosts
+        let client_config = match &config.proxy {
+            Some(proxy) => client_config.proxy(proxy.to_proxy()?),
+            None => client_config
+        };
+
+        // Refuse connections using older TLS versions
+        let client_config = match config.tls_min_version {
+            Some(tls_min_version) => client_config.min_tls_version(tls_min_version.into()),
+            None => client_config
+        };
+
+        // Trust the extra root certificates, and skip verifying certificates only if asked to
+        let client_config = config.root_certificates
+            .iter()
+            .fold(client_config, |client_config, certificate| client_config.add_root_certificate(certificate.clone()));
+
+        if !config.tls_verify {
+            warn!("TLS certificate verification is disabled, so the crawled servers' identities aren't checked");
+        }
+        let client_config = client_config.danger_accept_invalid_certs(!config.tls_verify);
+
+        // Only connect to addresses of one IP version, by binding to the unspecified local address of that version
+        let client_config = if config.ipv4_only {
+            client_config.local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+        } else if config.ipv6_only {
+            client_config.local_address(IpAddr::V6(Ipv6Addr::UNSPECIFIED))
+        } else {
+            client_config
+        };
+
+        // Resolve host names over HTTPS, only looking up the addresses of the IP version used
+        #[cfg(feature = "dns-over-https")]
+        let doh_resolver = match &config.dns_over_https {
+            Some(dns_over_https) => {
+                let mut options = trust_dns_resolver::config::ResolverOpts::default();
+                if config.ipv4_only {
+                    options.ip_strategy = trust_dns_resolver::config::LookupIpStrategy::Ipv4Only;
+                } else if config.ipv6_only {
+                    options.ip_strategy = trust_dns_resolver::config::LookupIpStrategy::Ipv6Only;
+                }
+
+                let resolver = crate::dns::doh_resolver::DohResolver::with_server(dns_over_https, options)
+                    .map_err(|error| CrawlerError::wrap("Failed to initialise DNS-over-HTTPS resolver", error))?;
+
+                Some(Arc::new(resolver))
+            }
+            None => None
+        };
+
+        // Fail to resolve host names with private addresses unless they're allowed, which redirects and changed DNS records could lead to
+        #[cfg(feature = "dns-over-https")]
+        let client_config = match (config.allow_private_hosts, doh_resolver) {
+            (true, Some(doh_resolver)) => client_config.dns_resolver(doh_resolver),
+            (true, None) => client_config,
+            (false, doh_resolver) => {
+                let doh_resolver = doh_resolver.map(|doh_resolver| doh_resolver as Arc<dyn reqwest::dns::Resolve>);
+                client_config.dns_resolver(Arc::new(PublicAddressResolver::new(doh_resolver)))
+            }
+        };
+        #[cfg(not(feature = "dns-over-https"))]
+        let client_config = if config.allow_private_hosts {
+            client_config
+        } else {
+            client_config.dns_resolver(Arc::new(PublicAddressResolver::new(None)))
+        };
+
+        client_config.build().map_err(|error| CrawlerError::wrap("Failed to initialise web client", error))
+    }
+
+    /// Create a crawler which crawls each of the URLs as an independent seed, without following links to other hosts.
+    /// The URLs' hosts become targets limited to the directories of the URLs' paths, and are crawled from the URLs
+    /// instead of their main pages.
+    pub fn from_url_list(urls: impl IntoIterator<Item = Url>) -> Result<Crawler, CrawlerError> {
+        Self::from_url_list_with_config(urls, CrawlerConfig::default())
+    }
+
+    /// Create a crawler like `from_url_list`, with the configuration otherwise, whose targets, seed URLs and scope policy are replaced
+    pub fn from_url_list_with_config(urls: impl IntoIterator<Item = Url>, config: CrawlerConfig) -> Result<Crawler, CrawlerError> {
+        let mut config = CrawlerConfig { scope_policy: ScopePolicy::SameHostOnly, seed_urls: Vec::new(), ..config };
+        let mut path_prefixes: HashMap<CrawlTarget, String> = HashMap::new();
+
+        for url in urls {
+            let target = CrawlTarget::from_url(&url)?;
+
+            // Seeds of the same target limit it to the directory their paths have in common
+            let directory = url.path().rfind('/').map_or("/", |end| &url.path()[..=end]);
+            let path_prefix = match path_prefixes.remove(&target) {
+                Some(path_prefix) => Self::common_directory(&path_prefix, directory).to_owned(),
+                None => directory.to_owned()
+            };
+            path_prefixes.insert(target, path_prefix);
+
+            if !config.seed_urls.contains(&url) { config.seed_urls.push(url); }
+        }
+
+        config.initial_targets = path_prefixes
+            .into_iter()
+            .map(|(target, path_prefix)| CrawlTarget::with_prefix(target.host().clone(), &path_prefix))
+            .collect();
+
+        Crawler::new(config)
+    }
+
+    /// Create a crawler for the targets listed in a JSON configuration file, with the default configuration otherwise.
+    /// The file holds an array of targets, of which only the host is required:
+    ///
+    /// ```json
+    /// [
+    ///     { "host": "example.com" },
+    ///     {
+    ///         "host": "docs.example.org",
+    ///         "scheme": "Https",
+    ///         "path_prefix": "/api/",
+    ///         "auth": { "basic": { "username": "crawler", "password": "secret" } },
+    ///         "max_depth": 5,
+    ///         "max_pages": 1000,
+    ///         "priority": 10
+    ///     },
+    ///     { "host": "192.168.1.1", "auth": { "bearer": "token" } }
+    /// ]
+    /// ```
+    ///
+    /// Unknown fields are rejected, and files which can't be parsed fail with `CrawlerError::ConfigParse`.
+    pub fn from_config_file(path: &Path) -> Result<Crawler, CrawlerError> {
+        let targets = fs::read_to_string(path)
+            .map_err(|error| CrawlerError::wrap(&format!("Failed to read targets from {}", path.display()), error))?;
+        let targets: Vec<TargetConfig> = serde_json::from_str(&targets)
+            .map_err(|error| CrawlerError::ConfigParse { path: path.to_path_buf(), error })?;
+
+        let initial_targets = targets
+            .into_iter()
+            .map(CrawlTarget::try_from)
+            .collect::<Result<HashSet<CrawlTarget>, CrawlerError>>()?;
+
+        Crawler::new(CrawlerConfig { initial_targets, ..Default::default() })
+    }
+
+    /// Returns the longest directory both paths are in, e.g. "/docs/" for "/docs/api/" and "/docs/guide/"
+    fn common_directory<'a>(path: &'a str, other: &str) -> &'a str {
+        let common_length = path.bytes().zip(other.bytes()).take_while(|(a, b)| a == b).count();
+        let common = &path[..common_length];
+
+        common.rfind('/').map_or("/", |end| &common[..=end])
+    }
+
+    /// Crawl the targets, returning what was found, or the error which stopped the crawl if failing fast
+    pub async fn crawl(&self) -> Result<CrawlReport, CrawlerError> {
+        self.crawl_reporting(None).await
+    }
+
+    /// Crawl the targets like `crawl`, also sending the results of the crawled pages to `results` if given
+    async fn crawl_reporting(&self, results: Option<mpsc::Sender<CrawlResult>>) -> Result<CrawlReport, CrawlerError> {
+        let start = Instant::now();
+
+        self.set_up_database()
+            .map_err(|error| CrawlerError::wrap("Failed to set up DB", error))?;
+
+        // Start crawling the initial targets from their seed URLs or main pages
+        let initial_targets = self.initial_targets()?;
+
+        // Stop the crawl if it's still running at the deadline, leaving the remaining tasks to notice the cancellation
+        match self.config.crawl_deadline {
+            Some(crawl_deadline) => {
+                let crawl = self.crawl_from(initial_targets, results);
+                tokio::pin!(crawl);
+
+                tokio::select! {
+                    _ = &mut crawl => {}
+                    _ = tokio::time::sleep_until(crawl_deadline.into()) => {
+                        warn!("Aborting the crawl after reaching its deadline");
+                        self.cancellation_token.cancel();
+                        crawl.await;
+                    }
+                }
+            }
+            None => self.crawl_from(initial_targets, results).await
+        }
+
+        // Return the error which stopped the crawl, if failing fast
+        match self.crawl_state.first_error.lock() {
+            Ok(mut first_error) => if let Some(error) = first_error.take() { return Err(error); },
+            Err(error) => return Err(CrawlerError::Other(format!("Failed to obtain mutex lock: {}", error)))
+        }
+
+        let mut report = CrawlReport {
+            crawl_duration: start.elapsed(),
+            cancelled: self.cancellation_token.is_cancelled(),
+            ..Default::default()
+        };
+
+        match self.crawl_state.discovered_urls.lock() {
+            Ok(mut discovered_urls) => report.discovered_urls = std::mem::take(&mut discovered_urls),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        match self.crawl_state.errors.lock() {
+            Ok(mut errors) => report.errors = std::mem::take(&mut errors),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        match self.crawl_state.target_summaries.lock() {
+            Ok(mut target_summaries) => report.targets = std::mem::take(&mut target_summaries),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        match self.crawl_state.resources.lock() {
+            Ok(mut resources) => report.resources = std::mem::take(&mut resources),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        match self.crawl_state.link_graph.lock() {
+            Ok(mut link_graph) => report.link_graph = std::mem::take(&mut link_graph),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        match self.crawl_state.cookies.lock() {
+            Ok(mut cookies) => report.cookies = std::mem::take(&mut cookies),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        match self.crawl_state.redirect_chains.lock() {
+            Ok(mut redirect_chains) => report.redirect_chains = std::mem::take(&mut redirect_chains),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+        report.redirects = report.redirect_chains
+            .iter()
+            .filter_map(|(url, redirect_chain)| Some((url.clone(), redirect_chain.last()?.clone())))
+            .collect();
+
+        match self.crawl_state.status_codes.lock() {
+            Ok(mut status_codes) => report.status_codes = std::mem::take(&mut status_codes),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        match self.crawl_state.timings.lock() {
+            Ok(mut timings) => report.timings = std::mem::take(&mut timings),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        report.bytes_downloaded = report.targets.values().map(|summary| summary.bytes_downloaded).sum();
+        report.bytes_uploaded = report.targets.values().map(|summary| summary.bytes_uploaded).sum();
+        report.stats = self.crawl_state.stats.snapshot(report.targets.len(), report.crawl_duration);
+
+        Ok(report)
+    }
+
+    /// Returns the targets to crawl with their configured seed URLs, adding the targets of seed URLs which aren't targets yet
+    fn initial_targets(&self) -> Result<Vec<(CrawlTarget, Vec<Url>)>, CrawlerError> {
+        let mut crawl_targets = self.crawl_targets
+            .lock()
+            .map_err(|error| CrawlerError::Other(format!("Failed to obtain mutex lock: {}", error)))?;
+
+        let mut targets: Vec<(CrawlTarget, Vec<Url>)> = crawl_targets.iter().map(|target| (target.clone(), Vec::new())).collect();
+
+        // Seed URLs belong to the target of their host, or else to one of their registered domain, whichever subdomain they're on
+        for url in &self.config.seed_urls {
+            let Some(host) = url.host().and_then(|host| Host::try_from(host).ok()) else { continue; };
+
+            let registered_domain = crawl_target::registered_domain(&host);
+            let target = targets
+                .iter()
+                .position(|(target, _)| *target.host() == host)
+                .or_else(|| targets.iter().position(|(target, _)| crawl_target::registered_domain(target.host()) == registered_domain));
+
+            match target {
+                Some(target) => targets[target].1.push(url.clone()),
+                None => {
+                    let target = CrawlTarget::new(host);
+                    crawl_targets.insert(target.clone());
+                    targets.push((target, vec![url.clone()]));
+                }
+            }
+        }
+
+        Ok(targets)
+    }
+
+    /// Crawl starting from the given URLs, skipping those which don't match the predicate.
+    /// The hosts of the matching URLs are crawled as targets, starting from those URLs.
+    pub async fn crawl_urls_filtered(&self, urls: Vec<Url>, predicate: impl Fn(&Url) -> bool + Send + Sync + 'static) {
+        if let Err(error) = self.set_up_database() {
+            error!("Failed to set up DB: {}", error);
+            return;
+        }
+
+        let Ok(db) = Connection::open(&self.config.db_path) else { error!("Failed to open DB!"); return; };
+        let db = Mutex::new(db);
+
+        // The URLs on the subdomains of a registered domain are crawled as one target, of the first URL's host
+        let mut seed_urls: HashMap<String, (CrawlTarget, Vec<Url>)> = HashMap::new();
+
+        for url in urls {
+            if !predicate(&url) {
+                Self::record_skipped_url(&db, &self.config, &url, SkipReason::Filtered(String::from("URL does not match the predicate")));
+                continue;
+            }
+
+            let Some(host) = url.host() else { continue; };
+            let Ok(host) = Host::try_from(host) else { continue; };
+
+            seed_urls.entry(crawl_target::registered_domain(&host)).or_insert_with(|| (CrawlTarget::new(host), Vec::new())).1.push(url);
+        }
+
+        match self.crawl_targets.lock() {
+            Ok(mut crawl_targets) => crawl_targets.extend(seed_urls.values().map(|(target, _)| target.clone())),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); return; }
+        }
+
+        self.crawl_from(seed_urls.into_values().collect(), None).await;
+    }
+
+    /// Run a full crawl, yielding its results through the returned receiver.
+    /// Results are compared to those of a previous crawl: a page whose body, links and metadata
+    /// are the same as before is unchanged and is marked as `from_cache`.
+    /// If `changed_only` is set, unchanged pages are not yielded at all.
+    pub fn crawl_incremental_stream(self: Arc<Self>, previous: Vec<CrawlResult>, changed_only: bool) -> mpsc::Receiver<CrawlResult> {
+        let (results_tx, mut results) = mpsc::channel::<CrawlResult>(64);
+        let (stream_tx, stream) = mpsc::channel::<CrawlResult>(64);
+
+        let previous: HashMap<Url, CrawlResult> = previous.into_iter().map(|result| (result.url.clone(), result)).collect();
+
+        tokio::spawn(async move {
+            while let Some(mut result) = results.recv().await {
+                result.from_cache = previous.get(&result.url).is_some_and(|previous| result.is_unchanged_from(previous));
+
+                if changed_only && result.from_cache { continue; }
+
+                if stream_tx.send(result).await.is_err() { break; }
+            }
+        });
+
+        tokio::spawn(async move {
+            if let Err(error) = self.set_up_database() {
+                error!("Failed to set up DB: {}", error);
+                return;
+            }
+
+            let initial_targets = match self.initial_targets() {
+                Ok(initial_targets) => initial_targets,
+                Err(error) => { error!("{}", error); return; }
+            };
+
+            self.crawl_from(initial_targets, Some(results_tx)).await;
+        });
+
+        stream
+    }
+
+    /// Run a full crawl in the background, yielding its events through the returned stream as they happen.
+    /// The crawl waits for the stream to be consumed once the configured event channel capacity of events is buffered,
+    /// and is cancelled if the stream is dropped before the crawl is done.
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    /// use tokio_stream::StreamExt;
+    /// use cherna_vdovitsa::crawler::{crawl_event::CrawlEvent, crawler_config::CrawlerConfig, Crawler};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), cherna_vdovitsa::crawler::CrawlerError> {
+    /// let crawler = Arc::new(Crawler::new(CrawlerConfig::default())?);
+    ///
+    /// // Print the first 100 pages crawled, stopping the crawl after them
+    /// let mut crawled_urls = crawler
+    ///     .crawl_stream()
+    ///     .filter_map(|event| match event {
+    ///         CrawlEvent::UrlCrawled(url) => Some(url),
+    ///         _ => None
+    ///     })
+    ///     .take(100);
+    ///
+    /// while let Some(url) = crawled_urls.next().await {
+    ///     println!("{}", url);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn crawl_stream(self: Arc<Self>) -> impl Stream<Item = CrawlEvent> {
+        let (events_tx, events) = mpsc::channel::<CrawlEvent>(self.config.event_channel_capacity);
+        let mut crawl_events = self.subscribe();
+
+        tokio::spawn(async move {
+            let crawl = self.crawl();
+            tokio::pin!(crawl);
+
+            loop {
+                tokio::select! {
+                    crawled = &mut crawl => {
+                        if let Err(error) = crawled { error!("{}", error); }
+                        break;
+                    }
+                    event = crawl_events.recv() => match event {
+                        Ok(event) => {
+                            // Nothing consumes the events anymore, so stop the crawl, letting it finish what it's doing
+                            if events_tx.send(event).await.is_err() && !self.cancellation_token.is_cancelled() {
+                                info!("Cancelling the crawl after its event stream was dropped");
+                                self.cancellation_token.cancel();
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(missed)) => warn!("The crawl event stream missed {} events", missed),
+                        Err(broadcast::error::RecvError::Closed) => {
+                            if let Err(error) = crawl.await { error!("{}", error); }
+                            break;
+                        }
+                    }
+                }
+            }
+
+            // Yield the events published as the crawl finished
+            while let Ok(event) = crawl_events.try_recv() {
+                if events_tx.send(event).await.is_err() { break; }
+            }
+        });
+
+        ReceiverStream::new(events)
+    }
+
+    /// Crawl everything reachable from a single page, crawling the registrable domain of the page and of each of its links
+    /// as a target, starting from the linked pages. At most 100 pages of each of those targets are crawled.
+    /// Without a configuration, `CrawlerConfig::seed_discovery` is used, crawling subdomains up to 3 links away from the seed URLs.
+    pub async fn crawl_with_seed_discovery(root_url: Url, config: Option<CrawlerConfig>) -> Vec<CrawlResult> {
+        let config = config.unwrap_or_else(CrawlerConfig::seed_discovery);
+
+        let crawler = match Crawler::new(config) {
+            Ok(crawler) => crawler,
+            Err(error) => { error!("Failed to create crawler: {}", error); return Vec::new(); }
+        };
+
+        if let Err(error) = crawler.set_up_database() {
+            error!("Failed to set up DB: {}", error);
+            return Vec::new();
+        }
+
+        let root_page = match http::get_url(&crawler.client, root_url.clone(), None).await {
+            Ok(response) if response.status().is_success() => response.text().await.unwrap_or_default(),
+            Ok(response) => { error!("Failed to fetch {}: {}", root_url, response.status()); return Vec::new(); }
+            Err(error) => { error!("Failed to fetch {}: {}", root_url, error); return Vec::new(); }
+        };
+
+        // Find the links of the root page by the crawler's compiled selectors
+        let links: Vec<Url> = {
+            let document = Html::parse_document(&root_page);
+
+            crawler.crawl_state.link_selectors
+                .select(&document)
+                .filter_map(|(_, href)| root_url.join(href).ok())
+                .filter(|url| url.scheme() == "http" || url.scheme() == "https")
+                .collect()
+        };
+
+        // Group the root page and its links by registrable domain
+        let mut seed_urls: HashMap<CrawlTarget, Vec<Url>> = HashMap::new();
+        for url in [root_url].into_iter().chain(links) {
+            let Some(host) = url.host() else { continue; };
+            let Ok(host) = Host::try_from(host) else { continue; };
+
+            let host = match host {
+                Host::Domain(domain_name) => match DomainName::parse(&format!("{}.{}", domain_name.domain(), domain_name.tld())) {
+                    Ok(registrable_domain) => Host::Domain(registrable_domain),
+                    Err(_) => Host::Domain(domain_name)
+                },
+                host => host
+            };
+
+            let seed_urls = seed_urls.entry(CrawlTarget::with_max_pages(host, crawler_config::SEED_DISCOVERY_MAX_PAGES)).or_default();
+            if !seed_urls.contains(&url) { seed_urls.push(url); }
+        }
+
+        match crawler.crawl_targets.lock() {
+            Ok(mut crawl_targets) => crawl_targets.extend(seed_urls.keys().cloned()),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        crawler.crawl_collecting(seed_urls.into_iter().collect()).await
+    }
+
+    /// Probe the main page of each target and crawl those which don't require logging in,
+    /// returning the crawl results and the targets requiring it. Once their credentials have
+    /// been added with `add_auth_for_target`, the targets can be probed and crawled again.
+    pub async fn crawl_with_auth_detection(&self, targets: Vec<CrawlTarget>) -> (Vec<CrawlResult>, Vec<AuthRequiredTarget>) {
+        if let Err(error) = self.set_up_database() {
+            error!("Failed to set up DB: {}", error);
+            return (Vec::new(), Vec::new());
+        }
+
+        let probes = targets.into_iter().map(|target| async move {
+            let indicator = self.probe_auth(&target).await;
+            (target, indicator)
+        });
+
+        let mut crawled_targets = Vec::new();
+        let mut auth_required_targets = Vec::new();
+
+        for (target, indicator) in futures::future::join_all(probes).await {
+            match indicator {
+                Some(indicator) => auth_required_targets.push(AuthRequiredTarget { target, indicator }),
+                None => crawled_targets.push(target)
+            }
+        }
+
+        match self.crawl_targets.lock() {
+            Ok(mut crawl_targets) => crawl_targets.extend(crawled_targets.iter().cloned()),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        let results = self.crawl_collecting(crawled_targets.into_iter().map(|target| (target, Vec::new())).collect()).await;
+
+        (results, auth_required_targets)
+    }
+
+    /// Subscribe to the events of the crawl as they happen. Subscribers falling more than the configured
+    /// event channel capacity behind miss the oldest events, as reported by the receiver.
+    pub fn subscribe(&self) -> broadcast::Receiver<CrawlEvent> {
+        self.crawl_state.events.subscribe()
+    }
+
+    /// Send the credentials with every request to the target host, e.g. after `crawl_with_auth_detection` found it requires them
+    pub fn add_auth_for_target(&self, host: Host, auth: TargetAuth) {
+        match self.crawl_state.target_auth.lock() {
+            Ok(mut target_auth) => { target_auth.insert(host, auth); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Send a HEAD request to the target's main page, returning why it requires logging in if it does.
+    /// Targets which can't be reached are left for the crawl to report.
+    async fn probe_auth(&self, target: &CrawlTarget) -> Option<LoginWallIndicator> {
+        let url = Url::parse(&format!("{}://{}/", target.scheme().as_str(), target.host())).ok()?;
+
+        let request = self.client.head(url.clone());
+        let request = match target.auth().cloned().or_else(|| self.crawl_state.auth_for(&url)) {
+            Some(auth) => auth.authenticate(request),
+            None => request
+        };
+
+        let response = request.send().await.ok();
+        self.crawl_state.redirect_log.take(&url);
+        let response = response?;
+
+        if response.status() == StatusCode::UNAUTHORIZED || response.status() == StatusCode::FORBIDDEN {
+            Some(LoginWallIndicator::HttpAuth)
+        } else if response.url() != &url && Self::is_login_url(response.url()) {
+            Some(LoginWallIndicator::RedirectToLogin(response.url().clone()))
+        } else {
+            None
+        }
+    }
+
+    /// Crawl the targets from their seed URLs, collecting the results of all pages crawled
+    async fn crawl_collecting(&self, targets: Vec<(CrawlTarget, Vec<Url>)>) -> Vec<CrawlResult> {
+        let (results_tx, mut results) = mpsc::channel::<CrawlResult>(64);
+        let mut crawl_results = Vec::new();
+
+        tokio::join!(
+            self.crawl_from(targets, Some(results_tx)),
+            async {
+                while let Some(result) = results.recv().await {
+                    crawl_results.push(result);
+                }
+            }
+        );
+
+        crawl_results
+    }
+
+    /// Fetch and process a single page the same way as during a crawl, without retrying or saving it.
+    /// The links of the page are recorded in the result, but not crawled.
+    pub async fn crawl_single_url(client: &Client, url: Url, config: &CrawlerConfig) -> Result<CrawlResult, CrawlerError> {
+        let (mut crawl_result, links) = Self::fetch_page(client, url, config, HeaderMap::new(), &None).await?;
+        crawl_result.links = links.iter().filter_map(|link| Url::parse(&link.href).ok()).collect();
+
+        Ok(crawl_result)
+    }
+
+    /// Fetch a single page and return the links found on it with their anchor text, without crawling them.
+    /// The page is requested with the headers, cookies and credentials of its host like during a crawl, regardless of
+    /// the maximum depth and robots.txt, and without retrying.
+    pub async fn crawl_once(&self, url: Url) -> Result<HashSet<Link>, CrawlerError> {
+        if let Some(address) = self.crawl_state.blocked_address(&url, &self.config).await {
+            return Err(CrawlerError::Other(format!("{} has the private address {}, which isn't allowed to be requested", url, address)));
+        }
+
+        let mut headers = self.crawl_state.headers_for(&url);
+        self.crawl_state.cookie_jar.add_cookie_header(&url, &mut headers);
+        let auth = self.crawl_state.auth_for(&url);
+
+        let (_, links) = Self::fetch_page(&self.client, url, &self.config, headers, &auth).await?;
+        Ok(links)
+    }
+
+    /// Fetch and process a single page with the headers and credentials, returning its result and the links found on it
+    async fn fetch_page(
+        client: &Client,
+        url: Url,
+        config: &CrawlerConfig,
+        headers: HeaderMap,
+        auth: &Option<TargetAuth>
+    ) -> Result<(CrawlResult, HashSet<Link>), CrawlerError> {
+        let mut request = RequestContext { url: url.clone(), method: Method::GET, headers };
+        Self::prepare_request(config, &mut request).await?;
+
+        let response = Self::authenticate(client.request(request.method, request.url).headers(request.headers), auth)
+            .send()
+            .await
+            .map_err(|error| match config.tls_min_version {
+                Some(min_version) if http::is_tls_version_error(&error) => CrawlerError::TlsVersionTooOld { url: url.clone(), min_version },
+                _ => CrawlerError::Request { url: url.clone(), error }
+            })?;
+
+        let status_code = response.status();
+        if status_code.is_client_error() || status_code.is_server_error() {
+            return Err(CrawlerError::HttpStatus { url, status_code: status_code.as_u16() });
+        }
+
+        let header_size = http::response_header_size(response.headers());
+        if header_size > config.max_response_header_size {
+            return Err(CrawlerError::ResponseTooLarge { url, header_size });
+        }
+
+        let Some(target) = url.host().and_then(|host| Host::try_from(host).ok()) else {
+            return Err(CrawlerError::Other(format!("{} has no valid host", url)));
+        };
+
+        let mut crawl_result = CrawlResult::new(url.clone(), target, status_code.as_u16(), response.version().into());
+        crawl_result.tls_version = config.tls_min_version.filter(|_| url.scheme() == "https");
+
+        // Pages which weren't fetched successfully, e.g. unfollowed redirects, are recorded without a body
+        if !status_code.is_success() { return Ok((crawl_result, HashSet::new())); }
+
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .unwrap_or_default()
+            .to_owned();
+
+        let content_type_action = content_type::content_type_action(&config.content_type_map, &content_type);
+        if let ContentTypeAction::Skip = content_type_action {
+            return Err(CrawlerError::Other(format!("{} has a skipped content type: {}", url, content_type)));
+        }
+
+        let link_selectors = LinkSelectors::compile(&config.link_selectors)?;
+        let mut links = HashSet::new();
+        Self::read_page(response, &url, &content_type, content_type_action, config, &link_selectors, &mut crawl_result, Some(&mut links)).await?;
+
+        Ok((crawl_result, links))
+    }
+
+    /// Crawl only the pages listed in a sitemap, following sitemap indexes but not the links of the pages
+    pub async fn crawl_sitemap_only(&self, sitemap_url: Url) -> Vec<CrawlResult> {
+        if let Err(error) = self.set_up_database() {
+            error!("Failed to set up DB: {}", error);
+            return Vec::new();
+        }
+
+        let entries = match SitemapParser::fetch_entries(&self.client, sitemap_url).await {
+            Ok(entries) => entries,
+            Err(error) => { error!("Failed to fetch sitemap: {}", error); return Vec::new(); }
+        };
+        self.crawl_state.sitemap_fetched(entries.iter().map(|entry| entry.url.clone()));
+
+        let Ok(db) = Connection::open(&self.config.db_path) else { error!("Failed to open DB!"); return Vec::new(); };
+        let db = Arc::new(Mutex::new(db));
+
+        // The links of the pages are not followed, so nothing receives them
+        let (links_tx, _) = packet_channel::<PageLinks>(1);
+        let (results_tx, mut results) = mpsc::channel::<CrawlResult>(64);
+        let cancellation_token = self.cancellation_token.child_token();
+
+        let mut crawled_urls: HashSet<String> = HashSet::new();
+        let mut sitemap_entries = HashMap::new();
+
+        for entry in entries {
+            if !crawled_urls.insert(url_transform::dedup_key(self.config.query_deduplication, &self.config.url_transforms, entry.url.as_str())) { continue; }
+
+            if let Some(reason) = Self::skip_reason(&entry.url, &self.config) {
+                Self::record_skipped_url(&db, &self.config, &entry.url, reason);
+                continue;
+            }
+
+            if Self::is_disallowed_by_robots(&self.client, &entry.url, &self.config, &self.crawl_state).await {
+                Self::record_skipped_url(&db, &self.config, &entry.url, SkipReason::DisallowedByRobots);
+                continue;
+            }
+
+            self.crawl_state.url_enqueued();
+            self.crawl_state.task_spawned();
+            tokio::spawn(Self::crawl_url(
+                self.client.clone(),
+                entry.url.clone(),
+                0,
+                links_tx.clone(),
+                Arc::clone(&db),
+                Some(results_tx.clone()),
+                Arc::clone(&self.config),
+                Arc::clone(&self.crawl_state),
+                cancellation_token.clone(),
+                self.cancellation_token.clone(),
+            ));
+
+            sitemap_entries.insert(entry.url.clone(), entry);
+        }
+
+        drop(results_tx);
+
+        let mut crawl_results = Vec::new();
+        while let Some(mut result) = results.recv().await {
+            if let Some(entry) = sitemap_entries.get(&result.url) {
+                result.sitemap_priority = entry.priority;
+                result.sitemap_changefreq = entry.changefreq.clone();
+
+                match db.lock() {
+                    Ok(db) => {
+                        if let Err(error) = result.save_sitemap_metadata(&db) {
+                            error!("Failed to update DB: {}", error);
+                        }
+                    }
+                    Err(error) => error!("Failed to obtain mutex lock: {}", error)
+                }
+            }
+
+            crawl_results.push(result);
+        }
+
+        crawl_results
+    }
+
+    /// Create the database tables used by the crawler
+    fn set_up_database(&self) -> Result<(), rusqlite::Error> {
+        let db = Connection::open(&self.config.db_path)?;
+
+        db.execute("CREATE TABLE IF NOT EXISTS targets (
+            id INTEGER PRIMARY KEY,
+            host TEXT)
+            ", ())?;
+
+        db.execute("CREATE TABLE IF NOT EXISTS urls (
+            id INTEGER PRIMARY KEY,
+            url TEXT NOT NULL,
+            target TEXT NOT NULL,
+            response_code INTEGER,
+            http_version TEXT,
+            tls_version TEXT,
+            response_headers TEXT,
+            vary_header TEXT,
+            response_body BLOB,
+            body_sha256 TEXT,
+            page_size_bytes INTEGER,
+            title TEXT,
+            description TEXT,
+            schema_org_types TEXT,
+            canonical_url TEXT,
+            cross_domain_redirect TEXT,
+            redirect_chain TEXT,
+            sitemap_priority REAL,
+            sitemap_changefreq TEXT)
+            ", ())?;
+
+        db.execute("CREATE TABLE IF NOT EXISTS links (
+            id INTEGER PRIMARY KEY,
+            source_url TEXT NOT NULL,
+            target_url TEXT NOT NULL)
+            ", ())?;
+
+        db.execute("CREATE TABLE IF NOT EXISTS resource_hints (
+            id INTEGER PRIMARY KEY,
+            page_url TEXT NOT NULL,
+            url TEXT NOT NULL,
+            relation TEXT NOT NULL,
+            as_attribute TEXT)
+            ", ())?;
+
+        db.execute("CREATE TABLE IF NOT EXISTS skipped_urls (
+            id INTEGER PRIMARY KEY,
+            url TEXT NOT NULL UNIQUE,
+            target TEXT NOT NULL,
+            reason TEXT NOT NULL)
+            ", ())?;
+
+        db.close().map_err(|(_, error)| error)
+    }
+
+    /// Crawl the targets starting from their seed URLs, and any new targets found along the way.
+    /// The results of the crawled pages are also sent to `results`, if given.
+    async fn crawl_from(&self, targets: Vec<(CrawlTarget, Vec<Url>)>, results: Option<mpsc::Sender<CrawlResult>>) {
+        let (tx, mut new_targets) = packet_channel::<CrawlTarget>(self.config.target_channel_buffer_size());
+
+        // The targets waiting to be crawled, the highest priority first, and those being crawled
+        let mut pending_targets: BinaryHeap<PendingTarget> = BinaryHeap::new();
+        let mut target_tasks: JoinSet<Result<(), CrawlerError>> = JoinSet::new();
+        let mut task_hosts: HashMap<task::Id, Host> = HashMap::new();
+        let mut queued_targets: u64 = 0;
+
+        for (target, seed_urls) in targets {
+            pending_targets.push(PendingTarget { target, seed_urls, new_targets: tx.clone(), order: queued_targets });
+            queued_targets += 1;
+        }
+
+        // The crawl is over once every target has been crawled, so targets added from outside only hold on to the channel weakly
+        let weak_tx = tx.downgrade();
+        drop(tx);
+
+        let (target_sender, mut added_targets) = mpsc::channel::<CrawlTarget>(64);
+        self.set_target_sender(Some(target_sender));
+
+        // Process new potential targets, found by the crawl or added from outside it
+        loop {
+            let (new_potential_target, sender) = tokio::select! {
+                _ = self.cancellation_token.cancelled() => break,
+
+                // Start crawling the highest priority target waiting once fewer targets than the limit are being crawled
+                target_permit = Arc::clone(&self.crawl_state.target_permits).acquire_owned(), if !pending_targets.is_empty() => {
+                    let (Ok(target_permit), Some(pending_target)) = (target_permit, pending_targets.pop()) else { break; };
+                    let host = pending_target.target.host().clone();
+                    let task = self.spawn_crawl_target(&mut target_tasks, pending_target, results.clone(), target_permit);
+                    task_hosts.insert(task.id(), host);
+                    continue;
+                }
+
+                // Report the targets which failed or whose task panicked, the rest of the crawl carrying on without them
+                Some(finished_task) = target_tasks.join_next_with_id() => {
+                    self.target_task_finished(finished_task, &mut task_hosts);
+                    continue;
+                }
+
+                new_potential_target = new_targets.recv() => {
+                    let Some(new_potential_target) = new_potential_target else { break; };
+                    let mut new_potential_target_data = new_potential_target.data;
+
+                    // Targets found by the crawl are prioritised by their host
+                    if let Some(priority_fn) = &self.config.priority_fn {
+                        new_potential_target_data.set_priority(priority_fn.priority(new_potential_target_data.host()));
+                    }
+
+                    (new_potential_target_data, new_potential_target.sender)
+                }
+                Some(added_target) = added_targets.recv() => {
+                    let Some(sender) = weak_tx.upgrade() else { break; };
+                    (added_target, sender)
+                }
+            };
+
+            // Targets on the registered domain of one already crawled are covered by it
+            let is_new_target = match self.crawl_targets.lock() {
+                Ok(mut crawl_targets) => {
+                    !crawl_targets.iter().any(|target| target.covers(&new_potential_target)) && crawl_targets.insert(new_potential_target.clone())
+                }
+                Err(error) => { error!("Failed to obtain mutex lock: {}", error); false }
+            };
+
+            if is_new_target {
+                pending_targets.push(PendingTarget { target: new_potential_target, seed_urls: Vec::new(), new_targets: sender, order: queued_targets });
+                queued_targets += 1;
+            }
+        }
+
+        // Report the targets which finished along with the crawl, while those still being crawled after a cancellation notice it themselves
+        if !self.cancellation_token.is_cancelled() {
+            while let Some(finished_task) = target_tasks.join_next_with_id().await {
+                self.target_task_finished(finished_task, &mut task_hosts);
+            }
+        }
+        target_tasks.detach_all();
+        self.set_target_sender(None);
+    }
+
+    /// Report a target whose crawl failed, or whose task panicked, by the hosts of the tasks.
+    /// The failure is added to the report's errors under the target's main page, so a failed target doesn't look like an empty one.
+    fn target_task_finished(&self, finished_task: Result<(task::Id, Result<(), CrawlerError>), task::JoinError>, task_hosts: &mut HashMap<task::Id, Host>) {
+        match finished_task {
+            Ok((id, Ok(()))) => { task_hosts.remove(&id); }
+            Ok((id, Err(error))) => {
+                let host = task_hosts.remove(&id);
+                error!("Failed to crawl target {}: {}", host.as_ref().map(|host| host.to_string()).unwrap_or_default(), error);
+
+                if let Some(url) = error.url().cloned().or_else(|| host.as_ref().and_then(Self::main_page_url)) {
+                    self.crawl_state.page_failed(CrawlError::from_crawler_error(&url, &error));
+                }
+            }
+            Err(join_error) => {
+                let host = task_hosts.remove(&join_error.id());
+                error!("The task crawling target {} failed: {}", host.as_ref().map(|host| host.to_string()).unwrap_or_default(), join_error);
+
+                let Some(host) = host else { return; };
+                if let Some(url) = Self::main_page_url(&host) {
+                    let message = format!("The task crawling target {} failed: {}", host, join_error);
+                    self.crawl_state.page_failed(CrawlError::new(url, CrawlErrorKind::Other, message));
+                }
+
+                // The target's task couldn't say so itself
+                self.crawl_state.publish(CrawlEvent::TargetFinished(host));
+            }
+        }
+    }
+
+    /// Returns the HTTPS main page of a host, e.g. https://[::1]/ for an IPv6 address
+    fn main_page_url(host: &Host) -> Option<Url> {
+        match host {
+            Host::Ipv6(ip) => Url::parse(&format!("https://[{}]/", ip)).ok(),
+            host => Url::parse(&format!("https://{}/", host)).ok()
+        }
+    }
+
+    /// Returns a sender for adding targets to the running crawl, which are crawled from their main pages unless already
+    /// crawled, or None if no crawl is running. Targets sent as the crawl finishes may not be crawled.
+    pub fn target_sender(&self) -> Option<mpsc::Sender<CrawlTarget>> {
+        match self.target_sender.read() {
+            Ok(target_sender) => target_sender.clone(),
+            Err(error) => { error!("Failed to obtain lock: {}", error); None }
+        }
+    }
+
+    /// Add a cookie sent with the requests to the domain and its subdomains, e.g. a session cookie exported from a browser.
+    /// Fails if the cookie's name or value can't be sent in a Cookie header.
+    pub fn add_cookie(&self, domain: &str, name: &str, value: &str) -> Result<(), CrawlerError> {
+        self.crawl_state.cookie_jar.add(domain, name, value)
+    }
+
+    fn set_target_sender(&self, sender: Option<mpsc::Sender<CrawlTarget>>) {
+        match self.target_sender.write() {
+            Ok(mut target_sender) => *target_sender = sender,
+            Err(error) => error!("Failed to obtain lock: {}", error)
+        }
+    }
+
+    /// Returns the number of URLs queued for crawling across all targets, including those being crawled
+    pub fn pending_urls_count(&self) -> usize {
+        self.crawl_state.pending_urls.load(Ordering::Relaxed)
+    }
+
+    /// Returns the robots.txt files fetched so far, keyed by host, which are only fetched while crawling if they are respected
+    pub fn robots_txt_cache(&self) -> HashMap<Host, String> {
+        self.crawl_state.robots_cache.snapshot()
+    }
+
+    /// Returns the number of pages being crawled
+    pub fn active_tasks_count(&self) -> usize {
+        self.crawl_state.active_tasks.load(Ordering::Relaxed)
+    }
+
+    /// Returns the token which stops the whole crawl when cancelled, after which `crawl` returns what has been found so far.
+    /// Pages that are already being fetched are allowed to finish. A cancelled crawler can't crawl again.
+    pub fn cancel_token(&self) -> CancellationToken {
+        self.cancellation_token.clone()
+    }
+
+    /// Stop crawling a target without stopping the rest of the crawl.
+    /// Pages of the target that are already being fetched are allowed to finish.
+    /// Returns whether the target was being crawled.
+    pub fn cancel_target(&self, host: &Host) -> bool {
+        match self.target_tokens.lock() {
+            Ok(target_tokens) => {
+                if let Some(token) = target_tokens.get(host) {
+                    token.cancel();
+                    true
+                } else {
+                    false
+                }
+            }
+            Err(error) => {
+                error!("Failed to obtain mutex lock: {}", error);
+                false
+            }
+        }
+    }
+
+    /// Spawn a task crawling the target into the tasks, registering its cancellation token.
+    /// The target is crawled while the permit is held.
+    fn spawn_crawl_target(
+        &self,
+        target_tasks: &mut JoinSet<Result<(), CrawlerError>>,
+        pending_target: PendingTarget,
+        results: Option<mpsc::Sender<CrawlResult>>,
+        target_permit: OwnedSemaphorePermit
+    ) -> AbortHandle {
+        let PendingTarget { target, seed_urls, new_targets, .. } = pending_target;
+        let token = self.cancellation_token.child_token();
+
+        match self.target_tokens.lock() {
+            Ok(mut target_tokens) => { target_tokens.insert(target.host().to_owned(), token.clone()); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        target_tasks.spawn(Self::crawl_target(
+            self.client.clone(),
+            target,
+            seed_urls,
+            new_targets,
+            results,
+            Arc::clone(&self.config),
+            Arc::clone(&self.crawl_state),
+            token,
+            self.cancellation_token.clone(),
+            target_permit,
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all, fields(host = %crawl_target.host()))]
+    async fn crawl_target(
+        client: Client,
+        crawl_target: CrawlTarget,
+        seed_urls: Vec<Url>,
+        new_targets: PacketSender<CrawlTarget>,
+        results: Option<mpsc::Sender<CrawlResult>>,
+        config: Arc<CrawlerConfig>,
+        crawl_state: Arc<CrawlState>,
+        cancellation_token: CancellationToken,
+        crawl_cancellation_token: CancellationToken,
+        _target_permit: OwnedSemaphorePermit, // Held until the target has been crawled
+    ) -> Result<(), CrawlerError> {
+        let crawl_target_host = crawl_target.host().to_owned();
+
+        // Refuse a target with a private address before sending it any request, unless private hosts are allowed
+        if !config.allow_private_hosts {
+            if let Some(address) = crawl_target_host.private_address(&crawl_state.dns_cache).await {
+                let message = format!("{} has the private address {}, which isn't allowed to be requested", crawl_target_host, address);
+                warn!("{}", message);
+
+                if let Some(url) = seed_urls.first().cloned().or_else(|| Self::main_page_url(&crawl_target_host)) {
+                    crawl_state.publish(CrawlEvent::CrawlError { url: url.clone(), error: message.clone() });
+                    crawl_state.page_failed(CrawlError::new(url, CrawlErrorKind::BlockedByPolicy, message));
+                }
+
+                return Ok(());
+            }
+        }
+
+        // Set up the target, skipping it if that fails
+        if let Some(pre_crawl_hook) = &config.pre_crawl_hook {
+            if let Err(error) = pre_crawl_hook.run(&crawl_target).await {
+                warn!("Skipping target {}: {}", crawl_target_host, error);
+
+                if let Some(observer) = &config.observer {
+                    observer.on_target_skipped(&crawl_target, &error);
+                }
+
+                return Ok(());
+            }
+        }
+
+        // Send the target's credentials with the requests to its host, which other targets found along the way don't inherit
+        if let Some(auth) = crawl_target.auth() {
+            match crawl_state.target_auth.lock() {
+                Ok(mut target_auth) => { target_auth.insert(crawl_target_host.clone(), auth.clone()); }
+                Err(error) => error!("Failed to obtain mutex lock: {}", error)
+            }
+        }
+
+        // Send the target's own headers with its requests
+        if let Some(headers) = crawl_target.headers() {
+            match crawl_state.target_headers.lock() {
+                Ok(mut target_headers) => { target_headers.insert(crawl_target_host.clone(), headers.clone()); }
+                Err(error) => error!("Failed to obtain mutex lock: {}", error)
+            }
+        }
+
+        let mut crawled_urls: HashSet<String> = HashSet::new();
+
+        // The target's own limits, its maximum depth applying only if it's lower than the crawler's
+        let max_depth = config.max_depth.into_iter().chain(crawl_target.max_depth()).min();
+        let mut queued_pages: usize = 0;
+        let mut page_limit_reached = false;
+
+        // Continue a target restored from a checkpoint from the URLs it had queued but not fetched
+        let resumed_target = crawl_state.resume_target(&crawl_target_host);
+        let seed_urls = match &resumed_target {
+            Some(resumed_target) => resumed_target.frontier.iter().map(|(url, _)| url.clone()).collect(),
+            None => seed_urls
+        };
+
+        let (tx, mut new_links) = packet_channel::<PageLinks>(config.link_channel_buffer_size());
+
+        // The canonical URLs declared by the crawled pages
+        let mut canonical_urls: HashMap<Url, Url> = HashMap::new();
+
+        // The graph of links between the target's pages, maintained for estimating their PageRank
+        let mut link_graph = config.page_rank_threshold.map(|_| LinkGraph::new());
+        let mut processed_pages: usize = 0;
+        let mut low_priority_urls: Vec<(Url, usize)> = Vec::new(); // With their depths
+
+        // Create DB table for the target
+        let db = Connection::open(&config.db_path)
+            .map_err(|error| CrawlerError::wrap(&format!("Failed to open DB for {}", crawl_target_host), error))?;
+        db.execute("INSERT INTO targets (host) VALUES (?1)", params![crawl_target_host.to_string()])
+            .map_err(|error| CrawlerError::wrap("Failed to update DB", error))?;
+        let db = Arc::new(Mutex::new(db));
+
+        info!("Crawling target");
+        crawl_state.publish(CrawlEvent::TargetStarted(crawl_target_host.clone()));
+
+        // The scheme of the target's pages, which same-host links are switched to unless HTTPS was only preferred
+        let crawls_main_page = resumed_target.is_none() && config.crawl_mode.follows_links();
+        let (scheme, mut prefetched_page) = Self::resolve_scheme(&client, &crawl_target, &seed_urls, crawls_main_page, &crawl_state).await;
+        let enforce_scheme = crawl_target.scheme() != PreferredScheme::Auto || scheme == PreferredScheme::Http;
+
+        // Crawl the seed URLs, or the target host's main page and the pages listed in its sitemaps if there are none.
+        // Only the pages listed in the sitemaps are crawled if links aren't followed.
+        let seed_urls: Vec<(Url, usize)> = if let Some(resumed_target) = resumed_target {
+            // The queued URLs are marked as crawled again as they're queued
+            crawled_urls = resumed_target.crawled_urls;
+            for (url, _) in &resumed_target.frontier {
+                crawled_urls.remove(&url_transform::dedup_key(config.query_deduplication, &config.url_transforms, url.as_str()));
+            }
+
+            resumed_target.frontier
+        } else if seed_urls.is_empty() {
+            let main_page = Self::main_page(&crawl_target, scheme)
+                .map_err(|error| CrawlerError::wrap(&format!("Failed to build the main page URL of {}", crawl_target_host), error))?;
+            let mut seed_urls = if config.crawl_mode.follows_links() { vec![main_page.clone()] } else { Vec::new() };
+
+            if config.use_sitemap || !config.crawl_mode.follows_links() {
+                for mut url in Self::sitemap_urls(&client, &main_page, &config, &crawl_state).await {
+                    let same_host = url.host().and_then(|host| Host::try_from(host).ok()).is_some_and(|host| {
+                        Host::host_relationship(crawl_target.host(), &host) == HostRelationship::Same
+                    });
+                    if !same_host || !crawl_target.in_scope(&url) { continue; }
+
+                    Self::strip_query_params(&mut url, &config.excluded_query_params);
+
+                    if let Some(reason) = Self::skip_reason(&url, &config) {
+                        Self::record_skipped_url(&db, &config, &url, reason);
+                        continue;
+                    }
+
+                    seed_urls.push(url);
+                }
+            }
+
+            seed_urls.into_iter().map(|url| (url, 0)).collect()
+        } else {
+            seed_urls.into_iter().map(|url| (url, 0)).collect()
+        };
+
+        for (url, depth) in seed_urls {
+            let dedup_key = url_transform::dedup_key(config.query_deduplication, &config.url_transforms, url.as_str());
+            if !crawled_urls.insert(dedup_key.clone()) { continue; }
+
+            if Self::is_disallowed_by_robots(&client, &url, &config, &crawl_state).await {
+                Self::record_skipped_url(&db, &config, &url, SkipReason::DisallowedByRobots);
+                continue;
+            }
+
+            if crawl_target.max_pages().is_some_and(|max_pages| queued_pages >= max_pages) {
+                if !page_limit_reached {
+                    crawl_state.publish(CrawlEvent::TargetLimitReached(crawl_target_host.clone()));
+                    page_limit_reached = true;
+                }
+
+                Self::record_skipped_url(&db, &config, &url, SkipReason::PageLimitReached);
+                continue;
+            }
+            queued_pages += 1;
+
+            crawl_state.url_discovered(&crawl_target_host, &url, dedup_key, depth);
+            prefetched_page.take_if(|prefetched_page| *prefetched_page == url);
+
+            crawl_state.url_enqueued();
+            crawl_state.task_spawned();
+            tokio::spawn(Self::crawl_url(
+                client.clone(),
+                url,
+                depth,
+                tx.clone(),
+                Arc::clone(&db),
+                results.clone(),
+                Arc::clone(&config),
+                Arc::clone(&crawl_state),
+                cancellation_token.clone(),
+                crawl_cancellation_token.clone(),
+            ));
+        }
+
+        // Drop the response of a main page requested to choose the scheme which turned out not to be crawled, e.g. as robots.txt disallows it
+        if let Some(prefetched_page) = prefetched_page {
+            crawl_state.take_prefetched_page(&prefetched_page);
+            crawl_state.redirect_log.take(&prefetched_page);
+        }
+
+        drop(tx);
+
+        loop {
+            let new_potential_links = tokio::select! {
+                _ = cancellation_token.cancelled() => {
+                    // The deprioritised pages will never be crawled
+                    crawl_state.pending_urls.fetch_sub(low_priority_urls.len(), Ordering::Relaxed);
+                    info!("Finished crawling target");
+                    crawl_state.publish(CrawlEvent::TargetFinished(crawl_target_host));
+                    return Ok(());
+                }
+                new_potential_links = new_links.recv() => {
+                    match new_potential_links {
+                        Some(new_potential_links) => new_potential_links,
+                        None if low_priority_urls.is_empty() => break,
+
+                        // Crawl the deprioritised pages once all other pages have been crawled
+                        None => {
+                            let (tx, rx) = packet_channel::<PageLinks>(config.link_channel_buffer_size());
+
+                            for (url, depth) in low_priority_urls.drain(..) {
+                                crawl_state.task_spawned();
+                                tokio::spawn(Self::crawl_url(
+                                    client.clone(),
+                                    url,
+                                    depth,
+                                    tx.clone(),
+                                    Arc::clone(&db),
+                                    results.clone(),
+                                    Arc::clone(&config),
+                                    Arc::clone(&crawl_state),
+                                    cancellation_token.clone(),
+                                    crawl_cancellation_token.clone(),
+                                ));
+                            }
+
+                            new_links = rx;
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let PageLinks { page_url, canonical_url, final_url, redirect_target, mut links, depth } = new_potential_links.data;
+
+            // The page redirected to was crawled as the page itself, so don't crawl it again when it's linked to
+            if let Some(final_url) = final_url {
+                crawled_urls.insert(url_transform::dedup_key(config.query_deduplication, &config.url_transforms, final_url.as_str()));
+            }
+
+            // A page redirected to another domain, which should be crawled as well unless it resolves to the target's host
+            let redirect_target = match redirect_target {
+                Some(redirect_target) if Host::resolved_host_relationship(crawl_target.host(), &redirect_target, &crawl_state.dns_cache).await != HostRelationship::Same => Some(redirect_target),
+                _ => None
+            };
+
+            if let Some(redirect_target) = redirect_target {
+                new_targets
+                    .send(ChannelPacket {
+                        sender: new_targets.clone(),
+                        data: CrawlTarget::new(redirect_target),
+                    })
+                    .await
+                    .map_err(|_| CrawlerError::with_message("The crawl stopped receiving new targets"))?;
+            }
+
+            if let Some(canonical_url) = canonical_url.filter(|canonical_url| compare_urls(canonical_url, &page_url) != UrlRelation::SamePage) {
+                if canonical_urls.get(&canonical_url) == Some(&page_url) {
+                    // The canonical page points back to this page, so treat this page as its own canonical
+                    warn!("Circular canonical URL reference between {} and {}", page_url, canonical_url);
+                    Self::reset_canonical_url(&db, &page_url);
+                } else {
+                    // Follow the canonical URL like any other link
+                    links.insert(Link::from(&canonical_url));
+                    canonical_urls.insert(page_url.clone(), canonical_url);
+                }
+            }
+
+            // The links to crawl on the target's host
+            let mut same_host_urls: Vec<Url> = Vec::new();
+
+            for link in links {
+                // The links have already been resolved against their pages
+                let Ok(mut parsed_url) = Url::parse(&link.href) else { continue; };
+
+                // Only HTTP and HTTPS are supported
+                if !(parsed_url.scheme().eq("https") || parsed_url.scheme().eq("http")) {
+                    Self::record_skipped_url(&db, &config, &parsed_url, SkipReason::UnsupportedScheme);
+                    continue;
+                }
+
+                let Some(parsed_url_host) = parsed_url.host() else { continue; };
+                let Ok(parsed_url_host) = Host::try_from(parsed_url_host) else { continue; };
+
+                match Host::resolved_host_relationship(crawl_target.host(), &parsed_url_host, &crawl_state.dns_cache).await {
+                    // A new link to crawl, including those to related hosts the scope policy follows, which are covered by this target
+                    relationship if relationship == HostRelationship::Same
+                        || (relationship == HostRelationship::Related && config.scope_policy.follows(&parsed_url_host, relationship)) =>
+                    {
+                        // Links to the address a target's domain name resolves to are crawled on the domain name, so they aren't crawled twice
+                        if let (Host::Domain(_), Host::Ipv4(_) | Host::Ipv6(_)) = (crawl_target.host(), &parsed_url_host) {
+                            if parsed_url.set_host(Some(&crawl_target_host.to_string())).is_err() { continue; }
+                        }
+
+                        // Links leaving the target's path prefix are out of scope like those to other hosts
+                        if !crawl_target.in_scope(&parsed_url) {
+                            Self::record_skipped_url(&db, &config, &parsed_url, SkipReason::OutOfScope);
+                            continue;
+                        }
+
+                        if enforce_scheme && parsed_url.scheme() != scheme.as_str() && parsed_url.set_scheme(scheme.as_str()).is_err() { continue; }
+                        Self::strip_query_params(&mut parsed_url, &config.excluded_query_params);
+
+                        if let Some(reason) = Self::skip_reason(&parsed_url, &config) {
+                            Self::record_skipped_url(&db, &config, &parsed_url, reason);
+                            continue;
+                        }
+
+                        same_host_urls.push(parsed_url);
+                    }
+
+                    // A new target to crawl, if the scope policy allows it
+                    relationship => {
+                        if config.scope_policy.follows(&parsed_url_host, relationship) {
+                            new_targets
+                                .send(ChannelPacket {
+                                    sender: new_targets.clone(),
+                                    data: CrawlTarget::new(parsed_url_host),
+                                })
+                                .await
+                                .map_err(|_| CrawlerError::with_message("The crawl stopped receiving new targets"))?;
+                        } else {
+                            Self::record_skipped_url(&db, &config, &parsed_url, SkipReason::OutOfScope);
+                        }
+                    }
+                }
+            }
+
+            if let Some(link_graph) = &mut link_graph {
+                link_graph.add_links(page_url.as_str(), same_host_urls.iter().map(Url::to_string));
+
+                processed_pages += 1;
+                if processed_pages.is_multiple_of(PAGE_RANK_ITERATION_INTERVAL) {
+                    link_graph.update_ranks();
+                }
+            }
+
+            for url in same_host_urls {
+                // Don't descend further from the seed URLs than allowed, without ruling out shorter paths to the page
+                if max_depth.is_some_and(|max_depth| depth >= max_depth) {
+                    Self::record_skipped_url(&db, &config, &url, SkipReason::TooDeep);
+                    continue;
+                }
+
+                let dedup_key = url_transform::dedup_key(config.query_deduplication, &config.url_transforms, url.as_str());
+                if !crawled_urls.insert(dedup_key.clone()) {
+                    Self::record_skipped_url(&db, &config, &url, SkipReason::AlreadyVisited);
+                    continue;
+                }
+
+                if Self::is_disallowed_by_robots(&client, &url, &config, &crawl_state).await {
+                    Self::record_skipped_url(&db, &config, &url, SkipReason::DisallowedByRobots);
+                    continue;
+                }
+
+                // Stop queueing the target's pages once it has as many as allowed, letting those already queued finish
+                if crawl_target.max_pages().is_some_and(|max_pages| queued_pages >= max_pages) {
+                    if !page_limit_reached {
+                        crawl_state.publish(CrawlEvent::TargetLimitReached(crawl_target_host.clone()));
+                        page_limit_reached = true;
+                    }
+
+                    Self::record_skipped_url(&db, &config, &url, SkipReason::PageLimitReached);
+                    continue;
+                }
+                queued_pages += 1;
+
+                crawl_state.url_discovered(&crawl_target_host, &url, dedup_key, depth + 1);
+
+                // Deprioritise pages which are estimated to be unimportant
+                if let (Some(link_graph), Some(threshold)) = (&link_graph, config.page_rank_threshold) {
+                    if link_graph.estimated_rank(url.as_str()) < threshold {
+                        crawl_state.url_enqueued();
+                        low_priority_urls.push((url, depth + 1));
+                        continue;
+                    }
+                }
+
+                crawl_state.url_enqueued();
+                crawl_state.task_spawned();
+                tokio::spawn(Self::crawl_url(
+                    client.clone(),
+                    url,
+                    depth + 1,
+                    new_potential_links.sender.clone(),
+                    Arc::clone(&db),
+                    results.clone(),
+                    Arc::clone(&config),
+                    Arc::clone(&crawl_state),
+                    cancellation_token.clone(),
+                    crawl_cancellation_token.clone(),
+                ));
+            }
+        }
+
+        if let Some(post_crawl_hook) = &config.post_crawl_hook {
+            if let Err(error) = post_crawl_hook.run(&crawl_target).await {
+                error!("Failed to clean up target {}: {}", crawl_target_host, error);
+            }
+        }
+
+        info!("Finished crawling target");
+        crawl_state.publish(CrawlEvent::TargetFinished(crawl_target_host));
+        Ok(())
+    }
+
+    /// Returns why a URL should not be crawled, if it should be skipped
+    fn skip_reason(url: &Url, config: &CrawlerConfig) -> Option<SkipReason> {
+        if url.query_pairs().count() > config.max_query_params {
+            return Some(SkipReason::TooManyQueryParams);
+        }
+
+        if config.path_blocklist.iter().any(|prefix| url.path().starts_with(prefix.as_str())) {
+            return Some(SkipReason::BlockedPath);
+        }
+
+        if let Some(rejection) = config.url_filter.rejection(url.as_str()) {
+            return Some(SkipReason::Filtered(rejection));
+        }
+
+        None
+    }
+
+    /// Run the middleware on a request before it is sent
+    async fn prepare_request(config: &CrawlerConfig, request: &mut RequestContext) -> Result<(), CrawlerError> {
+        for middleware in &config.middleware {
+            middleware.before_request(request).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Read the body of a page through the middleware, and record it and the links found in it as the content type requires
+    #[allow(clippy::too_many_arguments)]
+    async fn read_page(
+        mut response: Response,
+        url: &Url,
+        content_type: &str,
+        content_type_action: &ContentTypeAction,
+        config: &CrawlerConfig,
+        link_selectors: &LinkSelectors,
+        crawl_result: &mut CrawlResult,
+        mut links: Option<&mut HashSet<Link>> // The page's links are only extracted if given
+    ) -> Result<(), CrawlerError> {
+        let mut response_context = ResponseContext {
+            url: response.url().clone(),
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: Vec::new()
+        };
+
+        // Read the body a chunk at a time, stopping at the maximum size if there is one
+        while let Some(chunk) = response.chunk().await.map_err(|error| CrawlerError::Request { url: url.clone(), error })? {
+            match config.max_body_bytes {
+                Some(max_body_bytes) if response_context.body.len() + chunk.len() > max_body_bytes => {
+                    response_context.body.extend_from_slice(&chunk[..max_body_bytes - response_context.body.len()]);
+                    crawl_result.body_truncated = true;
+                    break;
+                }
+                _ => response_context.body.extend_from_slice(&chunk)
+            }
+        }
+        crawl_result.page_size_bytes = response_context.body.len() as u64;
+
+        for middleware in &config.middleware {
+            middleware.after_response(&mut response_context).await?;
+        }
+
+        let response_body = response_context.body;
+        crawl_result.set_body_hash(&response_body);
+        crawl_result.vary_header = response_context.headers
+            .get(header::VARY)
+            .map(|vary_header| String::from_utf8_lossy(vary_header.as_bytes()).into_owned());
+        crawl_result.response_headers = response_context.headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+            .collect();
+
+        match content_type_action {
+            ContentTypeAction::ParseLinks => {
+                let response_text = http::decode_body(content_type, &response_body);
+                Self::parse_html_page(url, &response_text, config, link_selectors, crawl_result, links.as_deref_mut());
+                crawl_result.response_body = response_text;
+            }
+            ContentTypeAction::RecordOnly | ContentTypeAction::Skip => {}
+            ContentTypeAction::Custom(parser) => {
+                if let Some(links) = links.as_deref_mut() {
+                    links.extend(parser.parse(url, &response_body).iter().map(Link::from));
+                }
+            }
+        }
+
+        // Follow none of the page's links if its X-Robots-Tag header asks so, e.g. "nofollow" or "googlebot: noindex, nofollow"
+        let nofollow = response_context.headers
+            .get_all("x-robots-tag")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split([',', ':']))
+            .any(|directive| directive.trim().eq_ignore_ascii_case("nofollow"));
+
+        if let (true, Some(links)) = (config.respect_nofollow && nofollow, links) {
+            links.clear();
+        }
+
+        crawl_result.body_bytes = response_body;
+        Ok(())
+    }
+
+    /// Parse the canonical URL, title, meta description, optionally the resource hints and, if given the set to add them to, the links of an HTML page
+    fn parse_html_page(
+        url: &Url,
+        response_text: &str,
+        config: &CrawlerConfig,
+        link_selectors: &LinkSelectors,
+        crawl_result: &mut CrawlResult,
+        mut links: Option<&mut HashSet<Link>>
+    ) {
+        let document = Html::parse_document(response_text);
+
+        // Resolve relative URLs against the page's <base> element, which may itself be relative to the page, or the page itself
+        let base_selector = Selector::parse("base[href]").unwrap();
+        let base_url = document
+            .select(&base_selector)
+            .find_map(|element| element.value().attr("href"))
+            .and_then(|href| url.join(href).ok())
+            .unwrap_or_else(|| url.clone());
+
+        // Parse links from the webpage by each selector's attribute
+        let link_elements = links.is_some().then(|| link_selectors.select(&document));
+        for (element, href) in link_elements.into_iter().flatten() {
+            // Skip the links the page asks not to be followed
+            let rel = element.value().attr("rel").unwrap_or_default();
+            if config.respect_nofollow && rel.split_ascii_whitespace().any(|relation| relation.eq_ignore_ascii_case("nofollow")) {
+                continue;
+            }
+
+            // Resolve the link's URL without its fragment so that links within a page don't crawl it again,
+            // along with the visible text of the link
+            if let Ok(mut link) = base_url.join(href) {
+                link.set_fragment(None);
+                if let Some(links) = links.as_deref_mut() {
+                    links.insert(Link::new(&link, &element.text().collect::<String>()));
+                }
+            }
+        }
 
-pub struct Crawler {
-    crawl_targets: HashSet<CrawlTarget>,
-    client: Client,
-    config: Arc<CrawlerConfig>,
-}
+        // Parse the scripts, stylesheets, images and other resources of the webpage, which aren't crawled as pages
+        let resource_selector = Selector::parse("script[src], link[href], img[src], img[srcset], source[srcset]").unwrap();
+        for element in document.select(&resource_selector) {
+            let element = element.value();
 
-impl Crawler {
-    /// Create a Vdovitsa crawler with initial targets.
-    pub fn new(config: CrawlerConfig) -> Result<Crawler, CrawlerError> {
-        // Configure the web client
-        let client_config = Client::builder().user_agent(concat!(
-            env!("CARGO_PKG_NAME"),
-            "/",
-            env!("CARGO_PKG_VERSION")
-        ));
-
-        if let Ok(client) = client_config.build() {
-            Ok(Crawler {
-                crawl_targets: config.initial_targets.clone(),
-                client,
-                config: Arc::new(config),
-            })
+            let rel = element.attr("rel").unwrap_or_default();
+            if element.name() == "link" && rel.split_ascii_whitespace().any(|relation| PAGE_LINK_RELATIONS.contains(&relation.to_ascii_lowercase().as_str())) {
+                continue;
+            }
+
+            // Each candidate of a srcset is a URL, optionally followed by a descriptor such as 2x or 480w
+            let srcset = element.attr("srcset").unwrap_or_default().split(',').filter_map(|candidate| candidate.split_ascii_whitespace().next());
+
+            for source in element.attr("src").into_iter().chain(element.attr("href")).chain(srcset) {
+                let Ok(resource) = base_url.join(source) else { continue; };
+                if !crawl_result.resources.contains(&resource) { crawl_result.resources.push(resource); }
+            }
+        }
+
+        // Parse the canonical URL of the webpage
+        let canonical_selector = Selector::parse("link[rel=canonical]").unwrap();
+        crawl_result.canonical_url = document
+            .select(&canonical_selector)
+            .find_map(|element| element.value().attr("href"))
+            .and_then(|href| base_url.join(href).ok());
+
+        // Parse the title of the webpage
+        let title_selector = Selector::parse("title").unwrap();
+        crawl_result.title = document
+            .select(&title_selector)
+            .next()
+            .map(|element| element.text().collect::<String>().trim().to_owned());
+
+        // Parse the meta description of the webpage
+        let description_selector = Selector::parse("meta[name=description]").unwrap();
+        crawl_result.description = document
+            .select(&description_selector)
+            .find_map(|element| element.value().attr("content"))
+            .map(|content| content.trim().to_owned());
+
+        // Parse the Schema.org types of the webpage's JSON-LD blocks, skipping invalid ones
+        let json_ld_selector = Selector::parse("script[type=\"application/ld+json\"]").unwrap();
+        for element in document.select(&json_ld_selector) {
+            let Ok(json_ld) = serde_json::from_str::<serde_json::Value>(&element.text().collect::<String>()) else { continue; };
+            Self::collect_schema_org_types(&json_ld, &mut crawl_result.schema_org_types);
+        }
+
+        // Parse the resource hints of the webpage
+        if config.extract_resource_hints {
+            let resource_hint_selector = Selector::parse("link[rel][href]").unwrap();
+            for element in document.select(&resource_hint_selector) {
+                let Some(rel) = element.value().attr("rel") else { continue; };
+                let Some(relation) = rel
+                    .split_ascii_whitespace()
+                    .find(|relation| ["preload", "prefetch", "preconnect", "dns-prefetch"].contains(&relation.to_ascii_lowercase().as_str()))
+                else { continue; };
+
+                let Some(hint_url) = element.value().attr("href").and_then(|href| base_url.join(href).ok()) else { continue; };
+
+                crawl_result.resource_hints.push(ResourceHint {
+                    url: hint_url,
+                    relation: relation.to_ascii_lowercase(),
+                    as_attribute: element.value().attr("as").map(str::to_owned)
+                });
+            }
+        }
+    }
+
+    /// Add the @type values of a JSON-LD value and the values nested in it, e.g. in its @graph, unless already added
+    fn collect_schema_org_types(json_ld: &serde_json::Value, types: &mut Vec<String>) {
+        match json_ld {
+            serde_json::Value::Object(object) => {
+                let schema_types = match object.get("@type") {
+                    Some(serde_json::Value::String(schema_type)) => vec![schema_type.as_str()],
+                    Some(serde_json::Value::Array(schema_types)) => schema_types.iter().filter_map(serde_json::Value::as_str).collect(),
+                    _ => Vec::new()
+                };
+
+                for schema_type in schema_types {
+                    // Types may be given as full URLs, e.g. https://schema.org/Product
+                    let schema_type = schema_type.rsplit('/').next().unwrap_or(schema_type).trim().to_owned();
+                    if !schema_type.is_empty() && !types.contains(&schema_type) { types.push(schema_type); }
+                }
+
+                for value in object.values() {
+                    Self::collect_schema_org_types(value, types);
+                }
+            }
+            serde_json::Value::Array(values) => {
+                for value in values {
+                    Self::collect_schema_org_types(value, types);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Remove the given query parameters from a URL
+    fn strip_query_params(url: &mut Url, excluded_params: &[String]) {
+        if excluded_params.is_empty() || url.query().is_none() { return; }
+
+        let kept_params: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(name, _)| !excluded_params.iter().any(|excluded| excluded == name))
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+
+        if kept_params.is_empty() {
+            url.set_query(None);
         } else {
-            Err(CrawlerError::with_message(
-                "Failed to initialse web client.",
-            ))
+            url.query_pairs_mut().clear().extend_pairs(kept_params);
         }
     }
 
-    pub async fn crawl(&mut self) {
-        let (tx, mut new_targets) = mpsc::channel::<ChannelPacket<CrawlTarget>>(64);
+    /// Make a page its own canonical URL in the database
+    fn reset_canonical_url(db: &Mutex<Connection>, url: &Url) {
+        match db.lock() {
+            Ok(db) => {
+                if let Err(error) = db.execute("UPDATE urls SET canonical_url = url WHERE url = ?1", params![url.to_string()]) {
+                    error!("Failed to update DB: {}", error);
+                }
+            }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
 
-        // Set up URLs table
-        let Ok(db) = Connection::open(&self.config.db_path) else { eprintln!("Failed to open DB!"); return; };
+    /// Record a skipped URL and the reason for skipping it in the database
+    /// Notify the observer of a skipped URL, and record it unless it has already been crawled
+    fn record_skipped_url(db: &Mutex<Connection>, config: &CrawlerConfig, url: &Url, reason: SkipReason) {
+        debug!(url = %url, reason = %reason, "Skipped URL");
 
-        db.execute("CREATE TABLE IF NOT EXISTS urls (
-            id INTEGER PRIMARY KEY,
-            url TEXT NOT NULL,
-            target TEXT NOT NULL,
-            response_code INTEGER,
-            response_body BLOB)
-            ", ()).unwrap();
+        if let Some(observer) = &config.observer {
+            observer.on_page_skipped(url, &reason);
+        }
 
-        db.close().unwrap();
+        if reason == SkipReason::AlreadyVisited { return; }
 
-        // Start crawling the initial targets
-        for target in &self.crawl_targets {
-            tokio::spawn(Self::crawl_target(
-                self.client.clone(),
-                target.clone(),
-                tx.clone(),
-                Arc::clone(&self.config),
-            ));
+        match db.lock() {
+            Ok(db) => {
+                if let Err(error) = db.execute(
+                    "INSERT OR IGNORE INTO skipped_urls (url, target, reason) VALUES (?1, ?2, ?3)",
+                    params![url.to_string(), url.host_str().unwrap_or_default(), reason.to_string()]
+                ) {
+                    error!("Failed to update DB: {}", error);
+                }
+            }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
         }
+    }
 
-        drop(tx);
+    /// Returns the URL of the target's main page over the scheme, under its path prefix if it has one
+    fn main_page(crawl_target: &CrawlTarget, scheme: PreferredScheme) -> Result<Url, url::ParseError> {
+        Url::parse(&format!("{}://{}{}", scheme.as_str(), crawl_target.host(), crawl_target.path_prefix().unwrap_or("/")))
+    }
 
-        // Process new potential targets
-        while let Some(new_potential_target) = new_targets.recv().await {
-            if self.crawl_targets.insert(new_potential_target.data.clone()) {
-                tokio::spawn(Self::crawl_target(
-                    self.client.clone(),
-                    new_potential_target.data,
-                    new_potential_target.sender,
-                    Arc::clone(&self.config),
-                ));
+    /// Resolve the scheme a target is crawled over. Targets preferring neither are crawled over the scheme of their first seed URL,
+    /// or over HTTPS if their main page can be connected to over it, and over HTTP if only that succeeds. The main page is requested
+    /// with a GET request like any page, whose response is kept for crawling the page rather than requesting it again if it's crawled.
+    /// Returns the scheme, and the main page if its response was kept.
+    async fn resolve_scheme(client: &Client, crawl_target: &CrawlTarget, seed_urls: &[Url], crawls_main_page: bool, crawl_state: &CrawlState) -> (PreferredScheme, Option<Url>) {
+        if crawl_target.scheme() != PreferredScheme::Auto { return (crawl_target.scheme(), None); }
+
+        if let Some(seed_url) = seed_urls.first() {
+            return (if seed_url.scheme() == "http" { PreferredScheme::Http } else { PreferredScheme::Https }, None);
+        }
+
+        let (Ok(https_url), Ok(http_url)) = (
+            Self::main_page(crawl_target, PreferredScheme::Https),
+            Self::main_page(crawl_target, PreferredScheme::Http)
+        ) else { return (PreferredScheme::Https, None); };
+
+        // Only connection failures fall back to HTTP, error responses show the target serves HTTPS
+        let (scheme, main_page, response) = match Self::request_main_page(client, &https_url, crawl_state).await {
+            Err(error) if error.is_connect() => {
+                crawl_state.redirect_log.take(&https_url);
+
+                match Self::request_main_page(client, &http_url, crawl_state).await {
+                    Ok(response) => (PreferredScheme::Http, http_url, Ok(response)),
+                    Err(error) => (PreferredScheme::Https, http_url, Err(error))
+                }
+            }
+            response => (PreferredScheme::Https, https_url, response)
+        };
+
+        match response {
+            Ok(response) if crawls_main_page => {
+                crawl_state.page_prefetched(main_page.clone(), response);
+                (scheme, Some(main_page))
+            }
+            _ => {
+                crawl_state.redirect_log.take(&main_page);
+                (scheme, None)
             }
         }
+    }
 
-        println!("Crawling done");
+    /// Request a target's main page to choose its scheme, with its host's headers, cookies and credentials like any page
+    async fn request_main_page(client: &Client, url: &Url, crawl_state: &CrawlState) -> Result<Response, reqwest::Error> {
+        let mut headers = crawl_state.headers_for(url);
+        crawl_state.cookie_jar.add_cookie_header(url, &mut headers);
+
+        Self::send_timed(Self::authenticate(client.get(url.clone()).headers(headers), &crawl_state.auth_for(url)), crawl_state).await
     }
 
-    async fn crawl_target(
+    /// Send a page request, recording it and how long its response took in the crawl statistics
+    async fn send_timed(request: reqwest::RequestBuilder, crawl_state: &CrawlState) -> Result<Response, reqwest::Error> {
+        let start = Instant::now();
+        let response = request.send().await;
+        crawl_state.stats.request_sent(start.elapsed());
+
+        response
+    }
+
+    /// Add the credentials of the page's target to a request, if it requires them
+    fn authenticate(request: reqwest::RequestBuilder, auth: &Option<TargetAuth>) -> reqwest::RequestBuilder {
+        match auth {
+            Some(auth) => auth.authenticate(request),
+            None => request
+        }
+    }
+
+    /// Returns whether crawling the URL is disallowed by the robots.txt of its host, if it is respected.
+    /// The disallowed URLs are recorded for the robots compliance report, as they're skipped.
+    async fn is_disallowed_by_robots(client: &Client, url: &Url, config: &CrawlerConfig, crawl_state: &CrawlState) -> bool {
+        if !config.respect_robots_txt { return false; }
+
+        let disallowed = crawl_state.robots_cache
+            .get(client, url, config.robots_cache_ttl, &config.user_agent)
+            .await
+            .is_some_and(|robots_txt| !robots_txt.is_allowed(&url[Position::BeforePath..Position::AfterQuery]));
+
+        if disallowed { crawl_state.disallowed_by_robots(url); }
+        disallowed
+    }
+
+    /// Fetch the pages listed in the sitemaps declared by the host's robots.txt, or in its /sitemap.xml if none are declared.
+    /// Sitemaps which can't be fetched or parsed are ignored.
+    async fn sitemap_urls(client: &Client, main_page: &Url, config: &CrawlerConfig, crawl_state: &CrawlState) -> Vec<Url> {
+        let declared_sitemaps: Vec<Url> = crawl_state.robots_cache
+            .get(client, main_page, config.robots_cache_ttl, &config.user_agent)
+            .await
+            .map(|robots_txt| robots_txt.sitemaps().iter().filter_map(|sitemap| main_page.join(sitemap).ok()).collect())
+            .unwrap_or_default();
+
+        let sitemaps = if declared_sitemaps.is_empty() {
+            main_page.join("/sitemap.xml").into_iter().collect()
+        } else {
+            declared_sitemaps
+        };
+
+        let mut urls = Vec::new();
+        for sitemap in sitemaps {
+            if let Ok(entries) = SitemapParser::fetch_entries(client, sitemap).await {
+                urls.extend(entries.into_iter().map(|entry| entry.url));
+            }
+        }
+
+        crawl_state.sitemap_fetched(urls.iter().cloned());
+        urls
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all, fields(url = %url, status_code = field::Empty, content_length = field::Empty, elapsed_ms = field::Empty, link_count = field::Empty))]
+    async fn crawl_url(
         client: Client,
-        crawl_target: CrawlTarget,
-        new_targets: mpsc::Sender<ChannelPacket<CrawlTarget>>,
+        url: Url,
+        depth: usize,
+        new_links: PacketSender<PageLinks>,
+        db: Arc<Mutex<Connection>>,
+        results: Option<mpsc::Sender<CrawlResult>>,
         config: Arc<CrawlerConfig>,
+        crawl_state: Arc<CrawlState>,
+        target_cancellation_token: CancellationToken,
+        crawl_cancellation_token: CancellationToken
     ) {
-        let crawl_target_host = crawl_target.host().to_owned();
-        println!("Crawling target... {}", crawl_target_host);
+        let _active_task = ActiveTask(Arc::clone(&crawl_state));
 
-        let mut crawled_urls: HashSet<String> = HashSet::new();
-        crawled_urls.insert(format!("{}", crawl_target.host()).clone());
+        // Wait until fewer pages than the limit are being requested, unless the page's target is cancelled in the meantime
+        let _task_permit = tokio::select! {
+            _ = target_cancellation_token.cancelled() => return,
+            task_permit = crawl_state.task_permit(&url) => {
+                let Some(task_permit) = task_permit else { return; };
+                task_permit
+            }
+        };
 
-        let (tx, mut new_links) = mpsc::channel::<ChannelPacket<HashSet<String>>>(64);
+        // Wait until fewer pages of the host than its limit are being requested
+        let _host_permit = match crawl_state.host_permits(&url, &config) {
+            Some(host_permits) => tokio::select! {
+                _ = target_cancellation_token.cancelled() => return,
+                host_permit = host_permits.acquire_owned() => {
+                    let Ok(host_permit) = host_permit else { return; };
+                    Some(host_permit)
+                }
+            },
+            None => None
+        };
 
-        // Create DB table for the target
-        let Ok(db) = Connection::open(&config.db_path) else { eprintln!("Failed to create database table for: {}", crawl_target_host); return;};
-        let db = Arc::new(Mutex::new(db));
+        // Don't request hosts with private addresses, such as cloud metadata endpoints, unless allowed
+        if let Some(address) = crawl_state.blocked_address(&url, &config).await {
+            let message = format!("{} has the private address {}, which isn't allowed to be requested", url, address);
+            warn!("{}", message);
 
-        if let Ok(db) = db.lock() {
-            if let Err(err) = db.execute("INSERT INTO targets (host) VALUES (?1)", params![crawl_target_host.to_string()]) {
-                eprintln!("Failed to update DB: {}", err);
-                return;
-            }
-        }
-        else {
-            eprintln!("Failed to acquire lock on database.");
+            crawl_state.publish(CrawlEvent::CrawlError { url: url.clone(), error: message.clone() });
+            crawl_state.page_failed(CrawlError::new(url.clone(), CrawlErrorKind::BlockedByPolicy, message));
             return;
         }
 
-        // Crawl the target host's main page
-        tokio::spawn(Self::crawl_url(
-            client.clone(),
-            Url::parse(&format!("https://{}/", crawl_target_host)).unwrap(),
-            tx.clone(),
-            Arc::clone(&db),
-        ));
+        let mut new_links_to_crawl: HashSet<Link> = HashSet::new();
 
-        drop(tx);
+        // Fetch the host's robots.txt for its crawl delay unless it's cached, or not respected anyway
+        let crawl_delay = if config.respect_robots_txt {
+            crawl_state.robots_cache.get(&client, &url, config.robots_cache_ttl, &config.user_agent).await.and_then(|robots_txt| robots_txt.crawl_delay())
+        } else {
+            None
+        };
 
-        while let Some(new_potential_links) = new_links.recv().await {
-            for link in new_potential_links.data {
-                // If the URL is relative
-                if link.starts_with('/') && link.len() > 1 {
-                    let absolute_link = format!("{}{}", crawl_target.host().to_string(), link);
-
-                    if crawled_urls.insert(absolute_link.clone()) {
-                        tokio::spawn(Self::crawl_url(
-                            client.clone(),
-                            Url::parse(&format!("https://{}", absolute_link)).unwrap(),
-                            new_potential_links.sender.clone(),
-                            Arc::clone(&db),
-                        ));
-                    }
-                } else {
-                    let Ok(parsed_url) = Url::parse(&link) else { continue; };
-                    // Only HTTP and HTTPS are supported
-
-                    if parsed_url.scheme().eq("https") || parsed_url.scheme().eq("http") {
-                        let Some(parsed_url_host) = parsed_url.host() else { continue; };
-                        let Ok(parsed_url_host) = Host::try_from(parsed_url_host) else { continue; };
-
-                        match Host::host_relationship(crawl_target.host(), &parsed_url_host) {
-                            // A new link to crawl
-                            HostRelationship::Same => {
-                                if crawled_urls.insert(parsed_url.to_string()) {
-                                    tokio::spawn(Self::crawl_url(
-                                        client.clone(),
-                                        parsed_url.clone(),
-                                        new_potential_links.sender.clone(),
-                                        Arc::clone(&db),
-                                    ));
+        // Wait between requests to the host for as long as configured, or as its robots.txt asks for if that's longer
+        if let Some(delay) = config.politeness_delay.max(crawl_delay) {
+            crawl_state.rate_limiter.wait(&url, delay).await;
+        }
+
+        if target_cancellation_token.is_cancelled() { return; }
+        crawl_state.url_visited(&url, &config);
+
+        // Send the cookies of the page's host along with its headers
+        let mut headers = crawl_state.headers_for(&url);
+        let sent_cookies = crawl_state.cookie_jar.add_cookie_header(&url, &mut headers);
+
+        if let Some(observer) = &config.observer {
+            observer.on_before_request(&url);
+        }
+
+        // Use the page's cached response while it's fresh, or ask the server whether the stale one changed
+        let (mut fresh_response, stale_response) = match config.http_cache.as_ref().map(|http_cache| http_cache.lookup(&url)) {
+            Some(CacheLookup::Fresh(cached)) => (Some(cached), None),
+            Some(CacheLookup::Stale(cached)) => {
+                cached.add_conditional_headers(&mut headers);
+                (None, Some(cached))
+            }
+            Some(CacheLookup::Miss) | None => (None, None)
+        };
+        let mut from_http_cache = fresh_response.is_some();
+
+        let auth = crawl_state.auth_for(&url);
+        let request_start = Instant::now();
+
+        // Send get request through the middleware, retrying transient failures and deciding what to do about others with the error handler
+        let mut attempt: usize = 0; // The number of failed attempts
+        let response = loop {
+            if let Some(cached) = fresh_response.take() { break cached.to_response(&url); }
+
+            let mut error_response: Option<(StatusCode, HttpVersion)> = None; // A response with an error status code
+            let mut retry_after: Option<HeaderValue> = None; // How long the server asked to wait before retrying
+            let mut request = RequestContext { url: url.clone(), method: Method::GET, headers: headers.clone() };
+
+            let error = match Self::prepare_request(&config, &mut request).await {
+                Err(error) => error,
+                Ok(()) => {
+                    // Skip the page if the server takes too long to send its response headers.
+                    // The main page of a target may have been requested already to choose its scheme.
+                    let request = Self::send_timed(Self::authenticate(client.request(request.method, request.url).headers(request.headers), &auth), &crawl_state);
+                    let response = match (crawl_state.take_prefetched_page(&url), config.max_response_time) {
+                        (Some(response), _) => Ok(Ok(response)),
+                        (None, Some(max_response_time)) => tokio::time::timeout(max_response_time, request).await,
+                        (None, None) => Ok(request.await)
+                    };
+                    let Ok(response) = response else {
+                        Self::record_skipped_url(&db, &config, &url, SkipReason::TooSlow);
+                        return;
+                    };
+
+                    match response {
+                        Ok(response) if response.status().is_client_error() || response.status().is_server_error() => {
+                            error_response = Some((response.status(), response.version().into()));
+                            retry_after = response.headers().get(header::RETRY_AFTER).cloned();
+                            CrawlerError::HttpStatus { url: url.clone(), status_code: response.status().as_u16() }
+                        }
+                        Ok(response) if http::response_header_size(response.headers()) > config.max_response_header_size => {
+                            CrawlerError::ResponseTooLarge { url: url.clone(), header_size: http::response_header_size(response.headers()) }
+                        }
+                        Ok(response) => break response,
+                        Err(error) => match config.tls_min_version {
+                            Some(min_version) if http::is_tls_version_error(&error) => {
+                                if !config.fail_on_old_tls {
+                                    Self::record_skipped_url(&db, &config, &url, SkipReason::OldTls);
+                                    return;
                                 }
+
+                                CrawlerError::TlsVersionTooOld { url: url.clone(), min_version }
                             }
+                            _ => CrawlerError::Request { url: url.clone(), error }
+                        }
+                    }
+                }
+            };
+
+            // Retry network errors and the responses the retry policy considers transient while it allows
+            let transient_status_code = match &error {
+                CrawlerError::HttpStatus { .. } => Some(error_response.map(|(status_code, _)| status_code)),
+                CrawlerError::Request { error, .. } if !error.is_redirect() => Some(None),
+                _ => None
+            };
+            let retry_delay = transient_status_code
+                .and_then(|status_code| config.retry_policy.retry_delay(attempt + 1, status_code, retry_after.as_ref()));
+
+            if let Some(retry_delay) = retry_delay {
+                tokio::time::sleep(retry_delay).await;
+                attempt += 1;
+                continue;
+            }
+
+            let action = config.error_handler.handle(&error, &url);
+            let can_retry = attempt + 1 < config.retry_policy.max_attempts;
+
+            // Record the error of a page given up on, stopping the whole crawl if failing fast
+            if !matches!(action, ErrorAction::Retry { .. } if can_retry) {
+                error!("Failed to crawl {}: {}", url, error);
+                crawl_state.publish(CrawlEvent::CrawlError { url: url.clone(), error: error.to_string() });
 
-                            // A new target to crawl
-                            HostRelationship::Related => {
-                                if config.crawl_subdomains {
-                                    new_targets
-                                        .send(ChannelPacket {
-                                            sender: new_targets.clone(),
-                                            data: CrawlTarget::new(parsed_url_host),
-                                        })
-                                        .await
-                                        .unwrap();
+                if config.fail_fast_on_error {
+                    warn!("Aborting the crawl after failing to crawl {}", url);
+                    crawl_state.record_error(error);
+                    crawl_cancellation_token.cancel();
+                    return;
+                }
+
+                crawl_state.page_failed(CrawlError::from_crawler_error(&url, &error));
+            }
+
+            match action {
+                ErrorAction::Retry { after } if can_retry => {
+                    tokio::time::sleep(after * 2u32.saturating_pow(attempt as u32)).await;
+                    attempt += 1;
+                }
+                ErrorAction::Retry { .. } | ErrorAction::Skip => {
+                    // Record the status code of pages given up on, e.g. those requiring authentication
+                    if let (Some((status_code, http_version)), Some(host)) = (error_response, url.host()) {
+                        let Ok(target) = Host::try_from(host) else { return; };
+                        crawl_state.page_responded(&url, status_code, &config);
+
+                        let mut crawl_result = CrawlResult::new(url.clone(), target, status_code.as_u16(), http_version);
+                        crawl_result.redirect_chain = crawl_state.redirect_log.take(&url);
+                        crawl_state.page_redirected(&url, &crawl_result.redirect_chain);
+
+                        match db.lock() {
+                            Ok(db) => {
+                                if let Err(error) = crawl_result.save(&db) {
+                                    error!("Failed to update DB: {}", error);
                                 }
                             }
+                            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+                        }
 
-                            HostRelationship::Unrelated => {
-                                continue;
-                            }
+                        if let Some(results) = &results {
+                            let _ = results.send(crawl_result).await;
                         }
                     }
+
+                    return;
+                }
+                ErrorAction::Abort => {
+                    warn!("Aborting the crawl of {} after failing to crawl {}", url.host_str().unwrap_or_default(), url);
+                    target_cancellation_token.cancel();
+                    return;
+                }
+                ErrorAction::PropagateGlobally => {
+                    warn!("Aborting the crawl after failing to crawl {}", url);
+                    crawl_cancellation_token.cancel();
+                    return;
                 }
             }
-        }
+        };
 
-        println!("Finished crawling target: {}", crawl_target_host);
-    }
+        // Cache the response, or use the cached one instead if the server reported it unchanged
+        let response = match config.http_cache.as_ref().filter(|_| !from_http_cache) {
+            Some(http_cache) => match http_cache.update(&url, response, stale_response, config.max_body_bytes).await {
+                Ok((response, unchanged)) => {
+                    from_http_cache = unchanged;
+                    response
+                }
+                Err(error) => {
+                    let error = CrawlerError::Request { url: url.clone(), error };
+                    error!("Failed to process the response of {}: {}", url, error);
+                    crawl_state.publish(CrawlEvent::CrawlError { url: url.clone(), error: error.to_string() });
+                    crawl_state.page_failed(CrawlError::from_crawler_error(&url, &error));
+                    return;
+                }
+            },
+            None => response
+        };
+        
+        // Keep the cookies the page sets if storing them, or only read them if recording them
+        let received_cookies = if config.cookie_store {
+            crawl_state.cookie_jar.store_response_cookies(response.url(), response.headers())
+        } else if config.record_cookies {
+            cookies::response_cookies(response.url(), response.headers())
+        } else {
+            Vec::new()
+        };
 
-    async fn crawl_url(
-        client: Client,
-        url: Url,
-        new_links: mpsc::Sender<ChannelPacket<HashSet<String>>>,
-        db: Arc<Mutex<Connection>>
-    ) {
-        let mut new_links_to_crawl: HashSet<String> = HashSet::new();
+        if config.record_cookies {
+            crawl_state.cookies_exchanged(&url, sent_cookies.into_iter().chain(received_cookies).collect());
+        }
 
-        // Send get request
-        let Ok(response) = http::get_url(&client, url.clone()).await else { return; };
-        
         let status_code = response.status();
-        if !status_code.is_success() { return; }
+        let http_version: HttpVersion = response.version().into();
+        let redirect_chain = crawl_state.redirect_log.take(&url);
+        crawl_state.page_redirected(&url, &redirect_chain);
+        crawl_state.page_responded(&url, status_code, &config);
 
-        // Check if the URL returns an HTML page
-        let Some(content_type) = response.headers().get(header::CONTENT_TYPE) else { return; };
-        let Ok(content_type) = content_type.to_str() else { return; };
-        if !content_type.starts_with("text/html") {
-            return;
+        // A followed redirect within the host, whose page is the one crawled
+        let final_url = (!redirect_chain.is_empty() && response.url().host_str() == url.host_str()).then(|| response.url().clone());
+
+        // A followed redirect to another host, whose page is crawled if the target's scope includes it
+        if response.url().host_str() != url.host_str() && config.crawl_mode.follows_links() {
+            crawl_state.publish(CrawlEvent::CrossHostRedirect { from: url.clone(), to: response.url().clone() });
+            new_links_to_crawl.insert(Link::from(response.url()));
         }
 
-        if let Ok(response_text) = response.text().await {
-            // Check content for links
-            let document = Html::parse_document(&response_text);
-            let selector = Selector::parse("a").unwrap();
+        // The negotiated TLS version isn't exposed, but it's at least the required minimum
+        let tls_version = config.tls_min_version.filter(|_| url.scheme() == "https");
 
-            // Parse links from the webpage
-            for element in document.select(&selector) {
-                // Try to get the href attribute
-                if let Some(href) = element.value().attr("href") {
-                    new_links_to_crawl.insert(href.to_owned());
+        // Record redirects to other domains which were not followed, leaving other redirects to be handled below
+        if status_code.is_redirection() && config.cross_domain_redirect_policy == CrossDomainRedirectPolicy::Block {
+            let destination = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .and_then(|location| url.join(location).ok())
+                .filter(|destination| redirect::is_cross_domain(&url, destination));
+
+            if let (Some(destination), Some(host)) = (destination, url.host()) {
+                let Ok(target) = Host::try_from(host) else { return; };
+
+                let mut crawl_result = CrawlResult::new(url.clone(), target, status_code.as_u16(), http_version);
+                crawl_result.tls_version = tls_version;
+                crawl_result.redirect_chain = redirect_chain;
+                crawl_result.cross_domain_redirect = Some(destination);
+
+                match db.lock() {
+                    Ok(db) => {
+                        if let Err(error) = crawl_result.save(&db) {
+                            error!("Failed to update DB: {}", error);
+                        }
+                    }
+                    Err(error) => error!("Failed to obtain mutex lock: {}", error)
+                }
+
+                if let Some(results) = &results {
+                    let _ = results.send(crawl_result).await;
                 }
+
+                return;
             }
+        }
 
-            match db.lock() {
-                Ok(db) => {
-                    if let Err(error) = db.execute(
-                        "INSERT INTO urls (url, target, response_code, response_body) VALUES (?1, ?2, ?3, ?4)", 
-                        params![url.to_string(), url.host_str().unwrap(), status_code.as_u16(), response_text]
-                    ) {
-                        eprintln!("Failed to update DB: {}", error);
-                        return;
+        // Record unfollowed redirects, crawling where they lead as a link of the page, leaving those without a location to be reported below
+        if status_code.is_redirection() && !config.follow_redirects {
+            let destination = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .and_then(|location| url.join(location).ok());
+
+            if let (Some(destination), Some(host)) = (destination, url.host()) {
+                let Ok(target) = Host::try_from(host) else { return; };
+
+                let mut crawl_result = CrawlResult::new(url.clone(), target, status_code.as_u16(), http_version);
+                crawl_result.tls_version = tls_version;
+                crawl_result.links = vec![destination.clone()];
+
+                let links = HashSet::from([Link::from(&destination)]);
+                crawl_state.links_found(&url, &links);
+
+                match db.lock() {
+                    Ok(db) => {
+                        if let Err(error) = crawl_result.save(&db) {
+                            error!("Failed to update DB: {}", error);
+                        }
                     }
+                    Err(error) => error!("Failed to obtain mutex lock: {}", error)
                 }
-                Err(error) => {
-                    eprintln!("Failed to obtain mutex lock: {}", error);
+
+                if let Some(results) = &results {
+                    let _ = results.send(crawl_result).await;
+                }
+
+                if !config.crawl_mode.follows_links() { return; }
+
+                let _ = new_links
+                    .send(ChannelPacket {
+                        sender: new_links.clone(),
+                        data: PageLinks {
+                            page_url: url,
+                            canonical_url: None,
+                            final_url: None,
+                            redirect_target: None,
+                            links,
+                            depth
+                        },
+                    })
+                    .await;
+
+                return;
+            }
+        }
+
+        // Report responses which are neither successful nor handled above, e.g. redirects stopped by the redirect policy or without a location
+        if !status_code.is_success() {
+            let kind = if status_code.is_redirection() { CrawlErrorKind::TooManyRedirects } else { CrawlErrorKind::HttpError };
+            let error = CrawlError::new(url.clone(), kind, format!("{} responded with status code {}", url, status_code.as_u16()));
+
+            crawl_state.publish(CrawlEvent::CrawlError { url: url.clone(), error: error.message.clone() });
+            crawl_state.page_failed(error);
+            return;
+        }
+
+        // A followed redirect to another domain whose host should be crawled as well
+        let redirect_target = if config.cross_domain_redirect_policy == CrossDomainRedirectPolicy::FollowAndCrawl
+            && redirect::is_cross_domain(&url, response.url())
+        {
+            response.url().host().and_then(|host| Host::try_from(host).ok())
+        } else {
+            None
+        };
+
+        // Decide what to do with the page by its content type
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .unwrap_or_default()
+            .to_owned();
+
+        let content_type_action = content_type::content_type_action(&config.content_type_map, &content_type);
+        if let ContentTypeAction::Skip = content_type_action {
+            warn!("Skipping {} of the content type {}", url, content_type);
+            Self::record_skipped_url(&db, &config, &url, SkipReason::WrongContentType);
+            crawl_state.page_failed(CrawlError::new(url.clone(), CrawlErrorKind::NonHtmlContent, format!("{} is of the skipped content type {}", url, content_type)));
+            return;
+        }
+
+        let Some(host) = url.host() else { return; };
+        let Ok(target) = Host::try_from(host) else { return; };
+
+        let mut crawl_result = CrawlResult::new(url.clone(), target, status_code.as_u16(), http_version);
+        crawl_result.tls_version = tls_version;
+        crawl_result.redirect_chain = redirect_chain;
+        crawl_result.from_http_cache = from_http_cache;
+
+        let header_size = http::response_header_size(response.headers()) as u64;
+        let content_length = response.content_length();
+
+        // Reject bodies declared larger than allowed without reading them
+        if let Some(max_body_bytes) = config.max_body_bytes {
+            if content_length.is_some_and(|content_length| content_length > max_body_bytes as u64) {
+                let message = format!("{} has a body of {} bytes, more than the maximum of {}", url, content_length.unwrap_or_default(), max_body_bytes);
+
+                crawl_state.publish(CrawlEvent::CrawlError { url: url.clone(), error: message.clone() });
+                crawl_state.page_failed(CrawlError::new(url.clone(), CrawlErrorKind::BodyTooLarge, message));
+                return;
+            }
+        }
+
+        // Only extract the page's links if they're followed
+        let links = config.crawl_mode.follows_links().then_some(&mut new_links_to_crawl);
+        if let Err(error) = Self::read_page(response, &url, &content_type, content_type_action, &config, &crawl_state.link_selectors, &mut crawl_result, links).await {
+            error!("Failed to process the response of {}: {}", url, error);
+            crawl_state.publish(CrawlEvent::CrawlError { url: url.clone(), error: error.to_string() });
+            crawl_state.page_failed(CrawlError::from_crawler_error(&url, &error));
+            return;
+        }
+
+        // Report bodies which turned out larger than allowed, their beginning having been processed like any other page
+        if let (true, Some(max_body_bytes)) = (crawl_result.body_truncated, config.max_body_bytes) {
+            let message = format!("{} has a body of more than the maximum of {} bytes", url, max_body_bytes);
+
+            crawl_state.publish(CrawlEvent::CrawlError { url: url.clone(), error: message.clone() });
+            crawl_state.page_failed(CrawlError::new(url.clone(), CrawlErrorKind::BodyTooLarge, message));
+        }
+
+        crawl_state.stats.body_received(content_length.unwrap_or(crawl_result.page_size_bytes));
+
+        // Stop the whole crawl once it has transferred more data than allowed, keeping this page
+        let total_bytes = crawl_state.bytes_transferred(&crawl_result.target, header_size + crawl_result.page_size_bytes, 0);
+        if config.max_total_bytes.is_some_and(|max_total_bytes| total_bytes >= max_total_bytes) && !crawl_cancellation_token.is_cancelled() {
+            warn!("Aborting the crawl after transferring {} bytes", total_bytes);
+            crawl_cancellation_token.cancel();
+        }
+        let canonical_url = crawl_result.canonical_url.clone();
+        crawl_state.resources_found(&crawl_result.resources);
+
+        crawl_state.links_found(&url, &new_links_to_crawl);
+        crawl_result.links = new_links_to_crawl.iter().filter_map(|link| Url::parse(&link.href).ok()).collect();
+
+        // Time the page from before its request to after its links were extracted, including any retries
+        let elapsed = request_start.elapsed();
+        let span = Span::current();
+        span.record("status_code", status_code.as_u16());
+        span.record("content_length", content_length.unwrap_or(crawl_result.page_size_bytes));
+        span.record("elapsed_ms", elapsed.as_millis() as u64);
+        span.record("link_count", new_links_to_crawl.len());
+
+        if config.timing_enabled {
+            crawl_state.page_timed(UrlTiming { url: url.clone(), elapsed, status: status_code.as_u16(), link_count: new_links_to_crawl.len() });
+        }
+
+        // Follow the hosts the page's Content-Security-Policy allows like links, without recording them as the page's links
+        if config.parse_csp_headers && config.crawl_mode.follows_links() {
+            let csp_main_pages = crawl_result.response_headers
+                .iter()
+                .filter(|(name, _)| name.eq_ignore_ascii_case(header::CONTENT_SECURITY_POLICY.as_str()))
+                .flat_map(|(_, policy)| CspParser::main_pages(policy));
+
+            new_links_to_crawl.extend(csp_main_pages.map(|main_page| Link::from(&main_page)));
+        }
+
+        if let Some(observer) = &config.observer {
+            observer.on_after_request(&crawl_result);
+        }
+
+        match db.lock() {
+            Ok(db) => {
+                if let Err(error) = crawl_result.save(&db) {
+                    error!("Failed to update DB: {}", error);
                     return;
                 }
+
+                debug!("Crawled page");
+                crawl_state.publish(CrawlEvent::UrlCrawled(url.clone()));
+            }
+            Err(error) => {
+                error!("Failed to obtain mutex lock: {}", error);
+                return;
             }
         }
 
-        // Send the new links to the parent crawl_target
-        if !new_links_to_crawl.is_empty() {
-            new_links
-                .send(ChannelPacket {
-                    sender: new_links.clone(),
-                    data: new_links_to_crawl,
-                })
-                .await
-                .unwrap();
+        if let Some(results) = &results {
+            let _ = results.send(crawl_result).await;
+        }
+
+        // Send the new links to the parent crawl_target in batches, unless it has been cancelled or links aren't followed
+        if config.crawl_mode.follows_links() && (!new_links_to_crawl.is_empty() || canonical_url.is_some() || final_url.is_some() || redirect_target.is_some()) {
+            let mut links = new_links_to_crawl.into_iter().peekable();
+            let (mut canonical_url, mut final_url, mut redirect_target) = (canonical_url, final_url, redirect_target);
+
+            loop {
+                let batch: HashSet<Link> = links.by_ref().take(config.url_batch_size.max(1)).collect();
+
+                let sent = new_links
+                    .send(ChannelPacket {
+                        sender: new_links.clone(),
+                        data: PageLinks {
+                            page_url: url.clone(),
+                            canonical_url: canonical_url.take(),
+                            final_url: final_url.take(),
+                            redirect_target: redirect_target.take(),
+                            links: batch,
+                            depth
+                        },
+                    })
+                    .await;
+
+                if sent.is_err() || links.peek().is_none() { break; }
+            }
         }
     }
 }
 
 #[derive(Debug)]
-pub struct CrawlerError {
-    message: String,
+pub enum CrawlerError {
+    Other(String),                               // A general error with a message
+    HttpStatus { url: Url, status_code: u16 },   // The server responded with an error status code
+    Request { url: Url, error: reqwest::Error }, // The request failed or its response could not be received
+    TlsVersionTooOld { url: Url, min_version: TlsVersion }, // The server only supports an older TLS version than allowed
+    InvalidConfig(String),                       // The crawler configuration is contradicting or unusable
+    InvalidSelector { selector: String, error: String }, // A configured CSS selector could not be parsed
+    ResponseTooLarge { url: Url, header_size: usize }, // The response headers are larger than allowed
+    Wrapped { message: String, source: Box<dyn std::error::Error + Send + Sync> }, // An underlying error, with what failed because of it
+    ConfigParse { path: PathBuf, error: serde_json::Error }, // A configuration file isn't valid JSON of the expected format
 }
 
+assert_impl_all!(CrawlerError: Send, Sync);
+
 impl CrawlerError {
-    fn new() -> CrawlerError {
-        CrawlerError {
-            message: String::from("Crawl Target Error"),
+    fn with_message(message: &str) -> CrawlerError {
+        CrawlerError::Other(String::from(message))
+    }
+
+    /// Wrap an underlying error, keeping it as the source of the returned error
+    pub fn wrap(message: &str, error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> CrawlerError {
+        CrawlerError::Wrapped { message: String::from(message), source: error.into() }
+    }
+
+    /// Wrap a web client error, as a failed request to its URL if it has one
+    pub fn from_reqwest(error: reqwest::Error) -> CrawlerError {
+        match error.url().cloned() {
+            Some(url) => CrawlerError::Request { url, error },
+            None => CrawlerError::wrap("Web client error", error)
         }
     }
 
-    fn with_message(message: &str) -> CrawlerError {
-        CrawlerError {
-            message: String::from(message),
+    /// Returns the URL the error occurred for, if any
+    pub fn url(&self) -> Option<&Url> {
+        match self {
+            Self::HttpStatus { url, .. } | Self::Request { url, .. } | Self::TlsVersionTooOld { url, .. } | Self::ResponseTooLarge { url, .. } => Some(url),
+            Self::Other(_) | Self::InvalidConfig(_) | Self::InvalidSelector { .. } | Self::Wrapped { .. } | Self::ConfigParse { .. } => None
+        }
+    }
+}
+
+impl PartialEq for CrawlerError {
+    /// Errors are equal if they are of the same kind and occurred for the same URL.
+    /// Errors without a URL are equal if they have the same message.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Other(message), Self::Other(other_message)) => message == other_message,
+            (Self::Wrapped { message, source }, Self::Wrapped { message: other_message, source: other_source }) => {
+                message == other_message && source.to_string() == other_source.to_string()
+            }
+            (Self::InvalidConfig(message), Self::InvalidConfig(other_message)) => message == other_message,
+            (Self::ConfigParse { path, error }, Self::ConfigParse { path: other_path, error: other_error }) => {
+                path == other_path && error.to_string() == other_error.to_string()
+            }
+            (Self::InvalidSelector { selector, error }, Self::InvalidSelector { selector: other_selector, error: other_error }) => {
+                selector == other_selector && error == other_error
+            }
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other) && self.url() == other.url()
+        }
+    }
+}
+
+impl Eq for CrawlerError {}
+
+impl Hash for CrawlerError {
+    /// Hashes the kind of the error and the URL it occurred for, consistently with equality
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        self.url().hash(state);
+    }
+}
+
+impl std::error::Error for CrawlerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Request { error, .. } => Some(error),
+            Self::Wrapped { source, .. } => Some(source.as_ref()),
+            Self::ConfigParse { error, .. } => Some(error),
+            _ => None
         }
     }
 }
 
-impl std::error::Error for CrawlerError {}
+impl From<CrawlerError> for std::io::Error {
+    /// Wraps the error in an `io::Error` of kind `Other`, which can be unwrapped with `into_inner`
+    fn from(error: CrawlerError) -> Self {
+        std::io::Error::other(error)
+    }
+}
 
 impl fmt::Display for CrawlerError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.message)
+        match self {
+            Self::Other(message) => write!(f, "{}", message),
+            Self::Wrapped { message, source } => write!(f, "{}: {}", message, source),
+            Self::ConfigParse { path, error } => write!(f, "Failed to parse {}: {}", path.display(), error),
+            Self::InvalidConfig(message) => write!(f, "Invalid configuration: {}", message),
+            Self::InvalidSelector { selector, error } => write!(f, "Invalid CSS selector {}: {}", selector, error),
+            Self::ResponseTooLarge { url, header_size } => write!(f, "{} responded with {} bytes of headers", url, header_size),
+            Self::HttpStatus { url, status_code } => write!(f, "{} responded with status code {}", url, status_code),
+            Self::Request { url, error } => write!(f, "Request to {} failed: {}", url, error),
+            Self::TlsVersionTooOld { url, min_version } => write!(f, "{} does not support {} or newer", url, min_version)
+        }
     }
 }
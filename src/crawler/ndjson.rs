@@ -0,0 +1,44 @@
+use std::time::SystemTime;
+
+use tokio::{io::{AsyncWrite, AsyncWriteExt}, sync::mpsc};
+
+use super::{crawl_report::CrawlReport, crawl_result::CrawlResult, Crawler, CrawlerError};
+
+impl Crawler {
+    /// Crawl the targets like `crawl`, also writing a line of JSON to the writer for every page as soon as it's crawled,
+    /// e.g. `{"url": "https://example.com/", "host": "example.com", "timestamp": "2024-01-01T00:00:00Z"}`.
+    /// Writes are unbuffered, so wrapping the writer in a `BufWriter` is recommended for files. The crawl isn't stopped by
+    /// a failed write, it's returned as the error once the crawl is done.
+    pub async fn crawl_to_writer<W: AsyncWrite + Unpin>(&self, mut writer: W) -> Result<CrawlReport, CrawlerError> {
+        let (results_tx, mut results_rx) = mpsc::channel::<CrawlResult>(64);
+
+        let write_records = async {
+            let mut write_error = None;
+
+            // Keep receiving after a failed write, so that the crawl isn't blocked on the channel
+            while let Some(result) = results_rx.recv().await {
+                if write_error.is_some() { continue; }
+
+                let record = serde_json::json!({
+                    "url": result.url.as_str(),
+                    "host": result.url.host_str(),
+                    "timestamp": humantime::format_rfc3339_seconds(SystemTime::now()).to_string()
+                });
+
+                if let Err(error) = writer.write_all(format!("{}\n", record).as_bytes()).await {
+                    write_error = Some(error);
+                }
+            }
+
+            match write_error {
+                Some(error) => Err(error),
+                None => writer.flush().await
+            }
+        };
+
+        let (report, written) = tokio::join!(self.crawl_reporting(Some(results_tx)), write_records);
+
+        written.map_err(|error| CrawlerError::wrap("Failed to write crawled URLs", error))?;
+        report
+    }
+}
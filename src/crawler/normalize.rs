@@ -0,0 +1,123 @@
+use url::Url;
+
+/// Normalises URLs so that different spellings of the same page are deduplicated as one.
+/// The scheme and host are lowercased and default ports removed by parsing, after which HTTP URLs are made HTTPS,
+/// the fragment and trailing slashes of the path are removed, the path lowercased, the query parameters sorted
+/// and percent-encoding made consistent. So http://example.com/Page/ and https://example.com/page are the same page.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UrlNormalizer;
+
+impl UrlNormalizer {
+    /// Returns the normal form of the URL
+    pub fn normalize(url: &Url) -> Url {
+        Self::normalize_with_query_order(url, true)
+    }
+
+    /// Returns the normal form of the URL, sorting its query parameters only if asked to
+    pub fn normalize_with_query_order(url: &Url, sort_query: bool) -> Url {
+        let mut url = url.clone();
+        url.set_fragment(None);
+
+        if url.cannot_be_a_base() { return url; }
+
+        // The same page served over both schemes is crawled once, switching between the special schemes always succeeding
+        if url.scheme() == "http" {
+            let _ = url.set_scheme("https");
+        }
+
+        // Lowercased before the percent-encoding, whose hex digits are uppercased
+        let path = normalize_percent_encoding(&url.path().to_lowercase());
+        let path = match path.trim_end_matches('/') {
+            "" => String::from("/"),
+            path => path.to_owned()
+        };
+        url.set_path(&path);
+
+        let query = url.query().map(|query| {
+            let mut params: Vec<String> = query
+                .split('&')
+                .filter(|param| !param.is_empty())
+                .map(normalize_percent_encoding)
+                .collect();
+            if sort_query { params.sort(); }
+            params.join("&")
+        });
+        url.set_query(query.as_deref().filter(|query| !query.is_empty()));
+
+        url
+    }
+}
+
+/// Decode the percent-encoded characters which don't need to be encoded, and uppercase the hex digits of the others
+fn normalize_percent_encoding(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut normalized = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escape = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .filter(|hex| hex.bytes().all(|digit| digit.is_ascii_hexdigit()))
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escape {
+            Some(byte) if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) => normalized.push(byte as char),
+            Some(byte) => normalized.push_str(&format!("%{:02X}", byte)),
+            None => {
+                // Copy the whole character, which may be longer than a byte
+                let Some(character) = text[i..].chars().next() else { break; };
+                normalized.push(character);
+                i += character.len_utf8();
+                continue;
+            }
+        }
+
+        i += 3;
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(url: &str) -> String {
+        UrlNormalizer::normalize(&Url::parse(url).unwrap()).to_string()
+    }
+
+    #[test]
+    fn variants_of_a_page_normalize_to_one_url() {
+        let variants = ["http://example.com/page", "https://example.com/page", "https://example.com/page/", "https://example.com/Page"];
+
+        for variant in variants {
+            assert_eq!(normalize(variant), "https://example.com/page", "{}", variant);
+        }
+    }
+
+    #[test]
+    fn scheme_host_and_default_ports_are_normalized() {
+        assert_eq!(normalize("HTTP://EXAMPLE.com:80/"), "https://example.com/");
+        assert_eq!(normalize("https://example.com:443/docs"), "https://example.com/docs");
+        assert_eq!(normalize("http://example.com:8080/docs"), "https://example.com:8080/docs");
+    }
+
+    #[test]
+    fn fragments_are_removed_and_queries_sorted() {
+        assert_eq!(normalize("https://example.com/search?sort=asc&page=1#results"), "https://example.com/search?page=1&sort=asc");
+        assert_eq!(normalize("https://example.com/search?"), "https://example.com/search");
+    }
+
+    #[test]
+    fn percent_encoding_is_made_consistent() {
+        assert_eq!(normalize("https://example.com/%7Euser/a%2fb"), "https://example.com/~user/a%2Fb");
+        assert_eq!(normalize("https://example.com/?q=%61%3d"), "https://example.com/?q=a%3D");
+    }
+
+    #[test]
+    fn query_order_is_kept_unless_sorted() {
+        let url = Url::parse("https://example.com/?b=2&a=1").unwrap();
+        assert_eq!(UrlNormalizer::normalize_with_query_order(&url, false).as_str(), "https://example.com/?b=2&a=1");
+    }
+}
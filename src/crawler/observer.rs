@@ -0,0 +1,20 @@
+use std::fmt::Debug;
+
+use url::Url;
+
+use super::{crawl_result::CrawlResult, crawl_target::CrawlTarget, skip_reason::SkipReason, CrawlerError};
+
+/// Observes the requests made by the crawler
+pub trait CrawlObserver: Debug + Send + Sync {
+    /// Called before a page is requested
+    fn on_before_request(&self, url: &Url);
+
+    /// Called after a page has been fetched and processed
+    fn on_after_request(&self, result: &CrawlResult);
+
+    /// Called when a discovered URL is not crawled
+    fn on_page_skipped(&self, _url: &Url, _reason: &SkipReason) {}
+
+    /// Called when a target is not crawled because its setup failed
+    fn on_target_skipped(&self, _target: &CrawlTarget, _error: &CrawlerError) {}
+}
@@ -0,0 +1,31 @@
+use reqwest::{NoProxy, Proxy};
+use url::Url;
+
+use super::CrawlerError;
+
+/// A proxy all requests are sent through, e.g. a corporate HTTP proxy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    pub url: Url,                                // The proxy's URL, e.g. http://proxy.example.com:8080
+    pub credentials: Option<(String, String)>,   // The username and password the proxy requires, if any
+    pub no_proxy: Vec<String>                    // The hosts requested directly, e.g. "localhost" or ".internal.example.com"
+}
+
+impl ProxyConfig {
+    pub fn new(url: Url) -> ProxyConfig {
+        ProxyConfig { url, credentials: None, no_proxy: Vec::new() }
+    }
+
+    /// Build the client's proxy, failing if the proxy URL isn't usable
+    pub fn to_proxy(&self) -> Result<Proxy, CrawlerError> {
+        let proxy = Proxy::all(self.url.as_str())
+            .map_err(|error| CrawlerError::InvalidConfig(format!("Invalid proxy {}: {}", self.url, error)))?;
+
+        let proxy = match &self.credentials {
+            Some((username, password)) => proxy.basic_auth(username, password),
+            None => proxy
+        };
+
+        Ok(proxy.no_proxy(NoProxy::from_string(&self.no_proxy.join(","))))
+    }
+}
@@ -0,0 +1,40 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use tokio::time::Instant;
+use tracing::error;
+use url::Url;
+
+use crate::web::host::Host;
+
+/// Spaces out the requests to each host. Concurrent requests to a host each reserve the next free slot,
+/// so they are sent one interval apart rather than all at once when the interval has elapsed.
+#[derive(Debug, Default)]
+pub struct DomainRateLimiter {
+    next_request_times: Mutex<HashMap<Host, Instant>> // The earliest time the next request to each host may be sent
+}
+
+impl DomainRateLimiter {
+    pub fn new() -> DomainRateLimiter {
+        DomainRateLimiter::default()
+    }
+
+    /// Wait until a request may be sent to the URL's host, sending requests to it at most once per interval
+    pub async fn wait(&self, url: &Url, interval: Duration) {
+        let Some(host) = url.host().and_then(|host| Host::try_from(host).ok()) else { return; };
+
+        // Reserve the next free slot for the host, then wait for it
+        let request_time = match self.next_request_times.lock() {
+            Ok(mut next_request_times) => {
+                let request_time = next_request_times.get(&host).map_or(Instant::now(), |next| (*next).max(Instant::now()));
+                next_request_times.insert(host, request_time + interval);
+                request_time
+            }
+            Err(error) => {
+                error!("Failed to obtain mutex lock: {}", error);
+                Instant::now()
+            }
+        };
+
+        tokio::time::sleep_until(request_time).await;
+    }
+}
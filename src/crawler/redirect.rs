@@ -0,0 +1,91 @@
+use std::{collections::HashMap, net::IpAddr, sync::{Arc, Mutex}};
+
+use clap::ValueEnum;
+use reqwest::redirect::Policy;
+use tracing::error;
+use url::Url;
+
+use crate::web::host::{is_private_address, Host, HostRelationship, PrivateAddressError};
+
+/// How redirects to an unrelated domain are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum CrossDomainRedirectPolicy {
+    #[default]
+    Follow,         // Follow the redirect
+    Block,          // Don't follow the redirect, only record where it leads
+    FollowAndCrawl, // Follow the redirect and crawl the other domain as a new target
+}
+
+/// Returns whether a redirect from one URL to another crosses into an unrelated domain
+pub fn is_cross_domain(from: &Url, to: &Url) -> bool {
+    let (Some(from_host), Some(to_host)) = (from.host(), to.host()) else { return false; };
+    let (Ok(from_host), Ok(to_host)) = (Host::try_from(from_host), Host::try_from(to_host)) else { return false; };
+
+    Host::host_relationship(&from_host, &to_host) == HostRelationship::Unrelated
+}
+
+/// Records the redirect chains of the client's requests, keyed by the requested URL
+#[derive(Debug, Default)]
+pub struct RedirectLog(Mutex<HashMap<Url, Vec<Url>>>);
+
+impl RedirectLog {
+    /// Record a redirect hop, given the URLs visited so far starting with the requested one
+    fn record(&self, previous: &[Url], next: &Url) {
+        let Some((requested_url, hops)) = previous.split_first() else { return; };
+
+        match self.0.lock() {
+            Ok(mut chains) => { chains.insert(requested_url.clone(), hops.iter().chain([next]).cloned().collect()); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+    }
+
+    /// Remove and return the redirect hops of the last request for the URL, in order
+    pub fn take(&self, url: &Url) -> Vec<Url> {
+        match self.0.lock() {
+            Ok(mut chains) => chains.remove(url).unwrap_or_default(),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); Vec::new() }
+        }
+    }
+}
+
+/// Returns the private address a URL's host is an IP address of, if any.
+/// Domain names are checked as they're resolved, by `PublicAddressResolver`.
+pub fn private_ip_host(url: &Url) -> Option<IpAddr> {
+    let address = match url.host()? {
+        url::Host::Ipv4(ip) => IpAddr::V4(ip),
+        url::Host::Ipv6(ip) => IpAddr::V6(ip),
+        url::Host::Domain(_) => return None
+    };
+
+    Some(address).filter(is_private_address)
+}
+
+/// Build the redirect policy of the web client, recording every redirect hop in the log.
+/// Requests fail once they redirect more than the maximum number of times, back to a URL already visited,
+/// or to a private IP address unless private hosts are allowed.
+pub fn redirect_policy(cross_domain_policy: CrossDomainRedirectPolicy, max_redirects: usize, allow_private_hosts: bool, redirect_log: Arc<RedirectLog>) -> Policy {
+    Policy::custom(move |attempt| {
+        redirect_log.record(attempt.previous(), attempt.url());
+
+        if let Some(address) = private_ip_host(attempt.url()).filter(|_| !allow_private_hosts) {
+            return attempt.error(PrivateAddressError { host: address.to_string(), address });
+        }
+
+        if attempt.previous().len() > max_redirects {
+            return attempt.error("too many redirects");
+        }
+
+        if attempt.previous().contains(attempt.url()) {
+            return attempt.error("redirect loop");
+        }
+
+        let is_blocked = cross_domain_policy == CrossDomainRedirectPolicy::Block
+            && attempt.previous().last().is_some_and(|previous| is_cross_domain(previous, attempt.url()));
+
+        if is_blocked {
+            attempt.stop()
+        } else {
+            attempt.follow()
+        }
+    })
+}
@@ -0,0 +1,197 @@
+use std::{collections::HashMap, sync::{Arc, Mutex}, time::Duration};
+
+use regex::Regex;
+use reqwest::Client;
+use tokio::time::Instant;
+use tracing::error;
+use url::Url;
+
+use crate::web::{host::Host, http};
+
+/// An Allow or Disallow rule of a robots.txt file
+#[derive(Debug, Clone)]
+struct RobotsRule {
+    allow: bool,
+    path: String,         // The rule's path as written
+    pattern: Regex,       // The path prefix, where * matches any characters and a trailing $ the end of the path
+    pattern_length: usize // The length of the rule's path, the longest matching rule being the one applied
+}
+
+/// The rules of a robots.txt file which apply to a user agent
+#[derive(Debug, Clone)]
+pub struct RobotsTxt {
+    content: String, // The raw robots.txt file
+    rules: Vec<RobotsRule>,
+    crawl_delay: Option<Duration>,
+    sitemaps: Vec<String> // The URLs of the Sitemap directives, which apply to all user agents
+}
+
+impl RobotsTxt {
+    /// Parse the rules of a robots.txt file for the user agent. The groups naming the user agent apply if there are any,
+    /// and the groups for all user agents (*) otherwise.
+    pub fn parse(content: &str, user_agent: &str) -> RobotsTxt {
+        let user_agent = user_agent.to_ascii_lowercase();
+
+        // The user agents, rules and crawl delay of each group
+        let mut groups: Vec<(Vec<String>, Vec<RobotsRule>, Option<Duration>)> = Vec::new();
+        let mut in_rules = true; // Whether the last line was a rule, so that a user agent line starts a new group
+        let mut sitemaps = Vec::new();
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once(':') else { continue; };
+            let (key, value) = (key.trim().to_ascii_lowercase(), value.trim());
+
+            if key == "user-agent" {
+                if in_rules { groups.push((Vec::new(), Vec::new(), None)); }
+                in_rules = false;
+
+                if let Some((agents, _, _)) = groups.last_mut() {
+                    agents.push(value.to_ascii_lowercase());
+                }
+                continue;
+            }
+
+            // Sitemap directives aren't part of any group
+            if key == "sitemap" {
+                if !value.is_empty() { sitemaps.push(value.to_owned()); }
+                continue;
+            }
+
+            let Some((_, rules, crawl_delay)) = groups.last_mut() else { continue; };
+            in_rules = true;
+
+            match key.as_str() {
+                "allow" | "disallow" if !value.is_empty() => {
+                    if let Some(rule) = RobotsRule::new(key == "allow", value) {
+                        rules.push(rule);
+                    }
+                }
+                "crawl-delay" => {
+                    *crawl_delay = value.parse::<f64>().ok().and_then(|seconds| Duration::try_from_secs_f64(seconds).ok());
+                }
+                _ => {}
+            }
+        }
+
+        let named_groups: Vec<_> = groups
+            .iter()
+            .filter(|(agents, _, _)| agents.iter().any(|agent| !agent.is_empty() && agent != "*" && user_agent.contains(agent.as_str())))
+            .collect();
+
+        let applied_groups = if named_groups.is_empty() {
+            groups.iter().filter(|(agents, _, _)| agents.iter().any(|agent| agent == "*")).collect()
+        } else {
+            named_groups
+        };
+
+        RobotsTxt {
+            content: content.to_owned(),
+            rules: applied_groups.iter().flat_map(|(_, rules, _)| rules.iter().cloned()).collect(),
+            crawl_delay: applied_groups.iter().find_map(|(_, _, crawl_delay)| *crawl_delay),
+            sitemaps
+        }
+    }
+
+    /// Returns whether the path, including its query, may be crawled, the longest matching rule winning and Allow winning ties
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.applied_rule(path).is_none_or(|(allow, _)| allow)
+    }
+
+    /// Returns the rule deciding whether the path may be crawled, as whether it's an Allow rule and its path, if any matches
+    pub fn applied_rule(&self, path: &str) -> Option<(bool, &str)> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.pattern.is_match(path))
+            .max_by_key(|rule| (rule.pattern_length, rule.allow))
+            .map(|rule| (rule.allow, rule.path.as_str()))
+    }
+
+    /// Returns the rules which apply to the user agent, as whether each is an Allow rule and its path, in order
+    pub fn rules(&self) -> impl Iterator<Item = (bool, &str)> {
+        self.rules.iter().map(|rule| (rule.allow, rule.path.as_str()))
+    }
+
+    /// Returns the minimum delay between requests asked for
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+
+    /// Returns the URLs of the sitemaps declared, which may be relative to the robots.txt file
+    pub fn sitemaps(&self) -> &[String] {
+        &self.sitemaps
+    }
+
+    /// Returns the raw robots.txt file
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+impl RobotsRule {
+    fn new(allow: bool, rule_path: &str) -> Option<RobotsRule> {
+        let (path, anchored) = match rule_path.strip_suffix('$') {
+            Some(path) => (path, true),
+            None => (rule_path, false)
+        };
+
+        let pattern = path.split('*').map(regex::escape).collect::<Vec<String>>().join(".*");
+        let pattern = Regex::new(&format!("^{}{}", pattern, if anchored { "$" } else { "" })).ok()?;
+
+        Some(RobotsRule { allow, path: rule_path.to_owned(), pattern, pattern_length: path.len() })
+    }
+}
+
+/// Caches the robots.txt files of the crawled hosts, keyed by host.
+/// Each file is fetched on the first request to its host, and again once it's older than the TTL.
+#[derive(Debug, Default)]
+pub struct RobotsCache(Mutex<HashMap<Host, (Instant, Arc<RobotsTxt>)>>); // When each file was fetched and its rules
+
+impl RobotsCache {
+    /// Return the robots.txt rules of the URL's host for the user agent, fetching them if they aren't cached or have expired.
+    /// A missing or unreachable robots.txt is cached as empty, allowing everything.
+    pub async fn get(&self, client: &Client, url: &Url, ttl: Duration, user_agent: &str) -> Option<Arc<RobotsTxt>> {
+        let host = Host::try_from(url.host()?).ok()?;
+
+        match self.0.lock() {
+            Ok(robots_txts) => {
+                if let Some((fetched_at, robots_txt)) = robots_txts.get(&host) {
+                    if fetched_at.elapsed() < ttl { return Some(Arc::clone(robots_txt)); }
+                }
+            }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        // Concurrent requests to an uncached host may each fetch the file, the last one is kept
+        let robots_url = url.join("/robots.txt").ok()?;
+        let content = match http::get_url(client, robots_url, None).await {
+            Ok(response) if response.status().is_success() => response.text().await.unwrap_or_default(),
+            _ => String::new()
+        };
+
+        let robots_txt = Arc::new(RobotsTxt::parse(&content, user_agent));
+
+        match self.0.lock() {
+            Ok(mut robots_txts) => { robots_txts.insert(host, (Instant::now(), Arc::clone(&robots_txt))); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        Some(robots_txt)
+    }
+
+    /// Returns the parsed robots.txt files cached, including expired ones
+    pub fn parsed(&self) -> HashMap<Host, Arc<RobotsTxt>> {
+        match self.0.lock() {
+            Ok(robots_txts) => robots_txts.iter().map(|(host, (_, robots_txt))| (host.clone(), Arc::clone(robots_txt))).collect(),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); HashMap::new() }
+        }
+    }
+
+    /// Returns a copy of the cached robots.txt files, including expired ones
+    pub fn snapshot(&self) -> HashMap<Host, String> {
+        match self.0.lock() {
+            Ok(robots_txts) => robots_txts.iter().map(|(host, (_, robots_txt))| (host.clone(), robots_txt.content().to_owned())).collect(),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); HashMap::new() }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+use tracing::error;
+use url::{Position, Url};
+
+use crate::{util::csv::csv_record, web::host::Host};
+
+use super::{crawl_result::CrawlResult, Crawler, CrawlerError};
+
+/// How a crawl complied with the robots.txt files of its targets, for auditing the crawler or checking a site's robots.txt
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RobotsReport {
+    pub targets: Vec<TargetRobotsTxt>,       // The robots.txt file of each host fetched, by host
+    pub skipped_urls: Vec<RobotsUrl>,        // The URLs skipped as robots.txt disallows them
+    pub disallowed_crawled: Vec<RobotsUrl>,  // The crawled pages robots.txt disallows, e.g. those crawled before it was fetched again with new rules
+    pub conflicts: Vec<RobotsUrl>            // The URLs listed in a sitemap which robots.txt disallows
+}
+
+/// The robots.txt file of a host, and the rules of it applied to the crawler's user agent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TargetRobotsTxt {
+    pub host: String,
+    pub robots_txt: String,              // The raw file, empty if the host has none
+    pub applied_rules: Vec<String>,      // The rules for the user agent, e.g. "Disallow: /private/", in order
+    pub crawl_delay_seconds: Option<f64>,
+    pub sitemaps: Vec<String>            // The sitemaps the file declares
+}
+
+/// A URL disallowed by the robots.txt of its host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RobotsUrl {
+    pub url: String,
+    pub host: String,
+    pub rule: Option<String> // The rule disallowing the URL, if the robots.txt file is still cached
+}
+
+impl Crawler {
+    /// Report the robots.txt files the crawl fetched, the URLs it skipped because of them and those listed in sitemaps
+    /// although disallowed, along with the crawled pages among the results which robots.txt disallows.
+    /// Only the URLs checked against robots.txt while crawling are skipped, which are all of them if it's respected,
+    /// while robots.txt files aren't fetched at all if it isn't, leaving the report empty.
+    pub fn export_robots_compliance_report(&self, results: &[CrawlResult]) -> RobotsReport {
+        let robots_txts = self.crawl_state.robots_cache.parsed();
+
+        let mut targets: Vec<TargetRobotsTxt> = robots_txts
+            .iter()
+            .map(|(host, robots_txt)| TargetRobotsTxt {
+                host: host.to_string(),
+                robots_txt: robots_txt.content().to_owned(),
+                applied_rules: robots_txt.rules().map(|(allow, path)| rule_directive(allow, path)).collect(),
+                crawl_delay_seconds: robots_txt.crawl_delay().map(|delay| delay.as_secs_f64()),
+                sitemaps: robots_txt.sitemaps().to_vec()
+            })
+            .collect();
+        targets.sort_by(|target, other| target.host.cmp(&other.host));
+
+        // The rule disallowing a URL, if its host's robots.txt disallows it
+        let disallowing_rule = |url: &Url| -> Option<String> {
+            let host = Host::try_from(url.host()?).ok()?;
+            let robots_txt = robots_txts.get(&host)?;
+
+            match robots_txt.applied_rule(&url[Position::BeforePath..Position::AfterQuery]) {
+                Some((false, path)) => Some(rule_directive(false, path)),
+                _ => None
+            }
+        };
+        let robots_url = |url: &Url, rule: Option<String>| RobotsUrl { url: url.to_string(), host: url.host_str().unwrap_or_default().to_owned(), rule };
+
+        let skipped_urls: Vec<Url> = match self.crawl_state.robots_disallowed_urls.lock() {
+            Ok(disallowed_urls) => disallowed_urls.clone(),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); Vec::new() }
+        };
+        let disallowed_crawled: Vec<(&Url, String)> = results
+            .iter()
+            .filter_map(|result| disallowing_rule(&result.url).map(|rule| (&result.url, rule)))
+            .collect();
+
+        let sitemap_urls: HashSet<Url> = match self.crawl_state.sitemap_urls.lock() {
+            Ok(sitemap_urls) => sitemap_urls.clone(),
+            Err(error) => { error!("Failed to obtain mutex lock: {}", error); HashSet::new() }
+        };
+        let conflicts = skipped_urls
+            .iter()
+            .chain(disallowed_crawled.iter().map(|(url, _)| *url))
+            .filter(|url| sitemap_urls.contains(*url))
+            .map(|url| robots_url(url, disallowing_rule(url)))
+            .collect();
+
+        RobotsReport {
+            targets,
+            skipped_urls: skipped_urls.iter().map(|url| robots_url(url, disallowing_rule(url))).collect(),
+            disallowed_crawled: disallowed_crawled.into_iter().map(|(url, rule)| robots_url(url, Some(rule))).collect(),
+            conflicts
+        }
+    }
+}
+
+impl RobotsReport {
+    pub fn to_json(&self) -> Result<String, CrawlerError> {
+        serde_json::to_string_pretty(self).map_err(|error| CrawlerError::wrap("Failed to serialize robots report", error))
+    }
+
+    /// Returns the report as CSV with a header, one row per applied rule and per URL, e.g. "skipped,example.com,https://example.com/private/,Disallow: /private/".
+    /// The rows of applied rules have no URL, and the full robots.txt files are left out.
+    pub fn to_csv(&self) -> String {
+        let mut csv = csv_record(&["kind", "host", "url", "rule"]);
+        csv.push('\n');
+
+        for target in &self.targets {
+            for rule in &target.applied_rules {
+                csv.push_str(&csv_record(&["rule", &target.host, "", rule]));
+                csv.push('\n');
+            }
+        }
+
+        for (kind, urls) in [("skipped", &self.skipped_urls), ("disallowed_crawled", &self.disallowed_crawled), ("conflict", &self.conflicts)] {
+            for url in urls {
+                csv.push_str(&csv_record(&[kind, &url.host, &url.url, url.rule.as_deref().unwrap_or_default()]));
+                csv.push('\n');
+            }
+        }
+
+        csv
+    }
+}
+
+/// Returns a rule as it's written in robots.txt files, e.g. "Disallow: /private/"
+fn rule_directive(allow: bool, path: &str) -> String {
+    format!("{}: {}", if allow { "Allow" } else { "Disallow" }, path)
+}
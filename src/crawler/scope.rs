@@ -0,0 +1,29 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::web::host::{Host, HostRelationship};
+
+use super::crawl_target::CrawlTarget;
+
+/// Which hosts the links found on a target's pages are followed to, as new targets
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ScopePolicy {
+    #[default]
+    SameHostOnly,                            // Only the target's own host
+    RelatedHosts,                            // Also the subdomains and parent domains of the target's host, crawled as part of the target
+    AnyHost,                                 // Every host linked to
+    ExplicitAllowList(HashSet<CrawlTarget>)  // Also the hosts of the listed targets
+}
+
+impl ScopePolicy {
+    /// Returns whether a link to another host, related to the target's host as given, is followed to crawl that host
+    pub fn follows(&self, host: &Host, relationship: HostRelationship) -> bool {
+        match self {
+            Self::SameHostOnly => relationship == HostRelationship::Same,
+            Self::RelatedHosts => relationship != HostRelationship::Unrelated,
+            Self::AnyHost => true,
+            Self::ExplicitAllowList(targets) => relationship == HostRelationship::Same || targets.iter().any(|target| target.host() == host)
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::{HashSet, VecDeque};
+
+use reqwest::Client;
+use url::Url;
+
+use crate::web::http;
+
+use super::CrawlerError;
+
+/// The maximum number of sitemaps fetched when following sitemap indexes
+const MAX_SITEMAPS: usize = 1000;
+
+/// A page listed in a sitemap
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapEntry {
+    pub url: Url,
+    pub priority: Option<f64>,     // The priority of the page relative to the site's other pages, from 0 to 1
+    pub changefreq: Option<String> // How often the page is expected to change, e.g. daily
+}
+
+/// A parsed sitemap document
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sitemap {
+    UrlSet(Vec<SitemapEntry>), // A <urlset> listing pages
+    Index(Vec<Url>)            // A <sitemapindex> listing other sitemaps
+}
+
+/// Parses sitemap documents and fetches the pages they list
+pub struct SitemapParser;
+
+impl SitemapParser {
+    /// Parse a <urlset> or <sitemapindex> document, ignoring entries without a valid <loc>
+    pub fn parse(xml: &str) -> Result<Sitemap, CrawlerError> {
+        let document = roxmltree::Document::parse(xml)
+            .map_err(|error| CrawlerError::wrap("Failed to parse sitemap", error))?;
+
+        let root = document.root_element();
+
+        // Returns the trimmed text of a child element
+        let child_text = |node: roxmltree::Node, name: &str| {
+            node.children()
+                .find(|child| child.tag_name().name() == name)
+                .and_then(|child| child.text())
+                .map(|text| text.trim().to_owned())
+        };
+
+        match root.tag_name().name() {
+            "urlset" => Ok(Sitemap::UrlSet(
+                root.children()
+                    .filter(|node| node.tag_name().name() == "url")
+                    .filter_map(|node| {
+                        Some(SitemapEntry {
+                            url: Url::parse(&child_text(node, "loc")?).ok()?,
+                            priority: child_text(node, "priority").and_then(|priority| priority.parse().ok()),
+                            changefreq: child_text(node, "changefreq")
+                        })
+                    })
+                    .collect()
+            )),
+            "sitemapindex" => Ok(Sitemap::Index(
+                root.children()
+                    .filter(|node| node.tag_name().name() == "sitemap")
+                    .filter_map(|node| Url::parse(&child_text(node, "loc")?).ok())
+                    .collect()
+            )),
+            name => Err(CrawlerError::Other(format!("Unknown sitemap root element: {}", name)))
+        }
+    }
+
+    /// Fetch a sitemap, following any sitemap indexes, and return all the pages listed
+    pub async fn fetch_entries(client: &Client, sitemap_url: Url) -> Result<Vec<SitemapEntry>, CrawlerError> {
+        let mut entries = Vec::new();
+        let mut fetched_sitemaps: HashSet<Url> = HashSet::new();
+        let mut sitemaps = VecDeque::from([sitemap_url]);
+
+        while let Some(sitemap_url) = sitemaps.pop_front() {
+            if fetched_sitemaps.len() >= MAX_SITEMAPS { break; }
+            if !fetched_sitemaps.insert(sitemap_url.clone()) { continue; }
+
+            let response = http::get_url(client, sitemap_url.clone(), None)
+                .await
+                .map_err(|error| CrawlerError::Request { url: sitemap_url.clone(), error })?;
+
+            if !response.status().is_success() {
+                return Err(CrawlerError::HttpStatus { url: sitemap_url, status_code: response.status().as_u16() });
+            }
+
+            let xml = response
+                .text()
+                .await
+                .map_err(|error| CrawlerError::Request { url: sitemap_url.clone(), error })?;
+
+            match SitemapParser::parse(&xml)? {
+                Sitemap::UrlSet(sitemap_entries) => entries.extend(sitemap_entries),
+                Sitemap::Index(sitemap_urls) => sitemaps.extend(sitemap_urls)
+            }
+        }
+
+        Ok(entries)
+    }
+}
@@ -0,0 +1,37 @@
+use std::fmt;
+
+/// The reason a discovered URL was not crawled
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SkipReason {
+    TooManyQueryParams, // The URL has more query parameters than allowed
+    TooSlow,            // The server took too long to respond
+    BlockedPath,        // The URL path starts with a blocked prefix
+    Filtered(String),   // The URL was filtered out, for the given reason
+    OldTls,             // The server only supports an older TLS version than allowed
+    WrongContentType,   // The page's content type is skipped
+    AlreadyVisited,     // The URL has already been crawled
+    OutOfScope,         // The URL's host is not part of the crawl
+    UnsupportedScheme,  // The URL is neither HTTP nor HTTPS
+    TooDeep,            // The URL is further from the seed URLs than allowed
+    DisallowedByRobots, // The host's robots.txt disallows crawling the URL
+    PageLimitReached,   // The URL's target already has as many pages queued as it allows
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyQueryParams => write!(f, "too many query parameters"),
+            Self::TooSlow => write!(f, "too slow to respond"),
+            Self::BlockedPath => write!(f, "blocked path"),
+            Self::Filtered(reason) => write!(f, "filtered: {}", reason),
+            Self::OldTls => write!(f, "TLS version too old"),
+            Self::WrongContentType => write!(f, "wrong content type"),
+            Self::AlreadyVisited => write!(f, "already visited"),
+            Self::OutOfScope => write!(f, "out of scope"),
+            Self::UnsupportedScheme => write!(f, "unsupported scheme"),
+            Self::TooDeep => write!(f, "too many links away from the seed URLs"),
+            Self::DisallowedByRobots => write!(f, "disallowed by robots.txt"),
+            Self::PageLimitReached => write!(f, "the target's page limit was reached")
+        }
+    }
+}
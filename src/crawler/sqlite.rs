@@ -0,0 +1,141 @@
+use std::{collections::HashMap, path::Path};
+
+use rusqlite::{named_params, Connection};
+use scraper::Html;
+use url::Url;
+
+use crate::web::host::{Host, HostRelationship};
+
+use super::{crawl_result::CrawlResult, Crawler, CrawlerError};
+
+impl Crawler {
+    /// Write the crawl results to a new SQLite database of pages, links and errors, for querying them with SQL.
+    /// The crawl time, depth and soft 404 status of pages and the anchor text of links aren't recorded, so they are left empty.
+    pub fn serialize_to_sqlite(results: &[CrawlResult], path: &Path) -> Result<(), CrawlerError> {
+        let mut db = Connection::open(path).map_err(Self::sqlite_error)?;
+        let transaction = db.transaction().map_err(Self::sqlite_error)?;
+
+        transaction.execute_batch("
+            CREATE TABLE IF NOT EXISTS pages (
+                url TEXT PRIMARY KEY,
+                status_code INTEGER,
+                title TEXT,
+                description TEXT,
+                word_count INTEGER,
+                depth INTEGER,
+                crawled_at TEXT,
+                body_sha256 TEXT,
+                content_type TEXT,
+                is_soft_404 INTEGER);
+            CREATE TABLE IF NOT EXISTS links (
+                source_url TEXT NOT NULL,
+                target_url TEXT NOT NULL,
+                anchor_text TEXT,
+                rel TEXT,
+                link_type TEXT);
+            CREATE TABLE IF NOT EXISTS errors (
+                url TEXT NOT NULL,
+                error_type TEXT,
+                error_message TEXT,
+                retries INTEGER);
+        ").map_err(Self::sqlite_error)?;
+
+        for result in results {
+            let content_type = result.response_headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+                .map(|(_, value)| value.as_str());
+
+            // Count the words of the page's text, if its body was recorded
+            let word_count = (!result.response_body.is_empty()).then(|| {
+                Html::parse_document(&result.response_body)
+                    .root_element()
+                    .text()
+                    .map(|text| text.split_whitespace().count())
+                    .sum::<usize>()
+            });
+
+            transaction.execute(
+                "INSERT OR REPLACE INTO pages (url, status_code, title, description, word_count, body_sha256, content_type)
+                VALUES (:url, :status_code, :title, :description, :word_count, :body_sha256, :content_type)",
+                named_params! {
+                    ":url": result.url,
+                    ":status_code": result.status_code,
+                    ":title": result.title,
+                    ":description": result.description,
+                    ":word_count": word_count,
+                    ":body_sha256": result.body_sha256,
+                    ":content_type": content_type
+                }
+            ).map_err(Self::sqlite_error)?;
+
+            let page_host = result.url.host().and_then(|host| Host::try_from(host).ok());
+
+            for link in &result.links {
+                let link_host = link.host().and_then(|host| Host::try_from(host).ok());
+                let link_type = match (&page_host, &link_host) {
+                    (Some(page_host), Some(link_host)) if Host::host_relationship(page_host, link_host) == HostRelationship::Same => "internal",
+                    _ => "external"
+                };
+
+                transaction.execute(
+                    "INSERT INTO links (source_url, target_url, link_type) VALUES (:source_url, :target_url, :link_type)",
+                    named_params! { ":source_url": result.url, ":target_url": link, ":link_type": link_type }
+                ).map_err(Self::sqlite_error)?;
+            }
+
+            // Pages given up on are recorded with their error status code
+            if result.status_code >= 400 {
+                transaction.execute(
+                    "INSERT INTO errors (url, error_type, error_message) VALUES (:url, :error_type, :error_message)",
+                    named_params! {
+                        ":url": result.url,
+                        ":error_type": "http_status",
+                        ":error_message": format!("{} responded with status code {}", result.url, result.status_code)
+                    }
+                ).map_err(Self::sqlite_error)?;
+            }
+        }
+
+        transaction.commit().map_err(Self::sqlite_error)
+    }
+
+    /// Read the pages and links of a database written by `serialize_to_sqlite` back into crawl results
+    pub fn import_from_sqlite(path: &Path) -> Result<Vec<CrawlResult>, CrawlerError> {
+        let db = Connection::open(path).map_err(Self::sqlite_error)?;
+
+        let mut links: HashMap<Url, Vec<Url>> = HashMap::new();
+
+        let mut statement = db.prepare("SELECT source_url, target_url FROM links").map_err(Self::sqlite_error)?;
+        let mut rows = statement.query([]).map_err(Self::sqlite_error)?;
+        while let Some(row) = rows.next().map_err(Self::sqlite_error)? {
+            let (Ok(source_url), Ok(target_url)) = (row.get::<_, Url>("source_url"), row.get::<_, Url>("target_url")) else { continue; };
+            links.entry(source_url).or_default().push(target_url);
+        }
+
+        let mut results = Vec::new();
+
+        let mut statement = db.prepare("SELECT url, status_code, title, description, body_sha256 FROM pages").map_err(Self::sqlite_error)?;
+        let mut rows = statement.query([]).map_err(Self::sqlite_error)?;
+        while let Some(row) = rows.next().map_err(Self::sqlite_error)? {
+            let Ok(url) = row.get::<_, Url>("url") else { continue; };
+            let Some(target) = url.host().and_then(|host| Host::try_from(host).ok()) else { continue; };
+
+            let status_code = row.get::<_, Option<u16>>("status_code").map_err(Self::sqlite_error)?.unwrap_or_default();
+
+            let mut result = CrawlResult::new(url.clone(), target, status_code, Default::default());
+            result.title = row.get("title").map_err(Self::sqlite_error)?;
+            result.description = row.get("description").map_err(Self::sqlite_error)?;
+            result.body_sha256 = row.get("body_sha256").map_err(Self::sqlite_error)?;
+            result.links = links.remove(&url).unwrap_or_default();
+
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    fn sqlite_error(error: rusqlite::Error) -> CrawlerError {
+        CrawlerError::wrap("SQLite error", error)
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::{Duration, SystemTime};
+
+use openssl::{asn1::Asn1Time, nid::Nid, x509::{X509NameRef, X509}};
+use reqwest::{tls::TlsInfo, Client};
+use tracing::error;
+use url::Url;
+
+use crate::web::{host::Host, http};
+
+use super::{crawl_target::CrawlTarget, Crawler, CrawlerError};
+
+/// Certificates expiring within this many days are flagged with a warning
+const EXPIRY_WARNING_DAYS: i64 = 30;
+
+/// The TLS certificate a target's main page is served with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SslCertInfo {
+    pub host: Host,
+    pub expiry: SystemTime,         // When the certificate stops being valid
+    pub days_until_expiry: i64,     // Negative if the certificate has expired
+    pub subject_cn: String,         // The common name of the certificate's subject, empty if it has none
+    pub issuer: String,             // The distinguished name of the certificate's issuer, e.g. C=US, O=Let's Encrypt, CN=R3
+    pub is_valid: bool,             // Whether the certificate is trusted for the host by the web client
+    pub alt_names: Vec<String>,     // The DNS names of the certificate's subject alternative names
+    pub warning: Option<String>     // Why the certificate needs attention, e.g. because it expires soon
+}
+
+impl Crawler {
+    /// Fetch the TLS certificates of the targets' main pages. Targets which can't be connected to over HTTPS are left out.
+    /// Certificates which aren't trusted are still inspected, and reported as invalid.
+    pub async fn verify_ssl_certificates(&self, targets: &[CrawlTarget]) -> Vec<SslCertInfo> {
+        let (Ok(client), Ok(insecure_client)) = (self.tls_info_client(false), self.tls_info_client(true)) else {
+            error!("Failed to initialise web client for checking certificates.");
+            return Vec::new();
+        };
+
+        let checks = targets.iter().map(|target| Self::certificate_info(&client, &insecure_client, target));
+
+        futures::future::join_all(checks).await.into_iter().flatten().collect()
+    }
+
+    /// Create a web client which records the certificates of the servers it connects to
+    fn tls_info_client(&self, accept_invalid_certs: bool) -> Result<Client, CrawlerError> {
+        Client::builder()
+            .user_agent(self.config.user_agent.as_str())
+            .redirect(reqwest::redirect::Policy::none())
+            .tls_info(true)
+            .danger_accept_invalid_certs(accept_invalid_certs)
+            .build()
+            .map_err(|error| CrawlerError::wrap("Failed to initialise web client", error))
+    }
+
+    async fn certificate_info(client: &Client, insecure_client: &Client, target: &CrawlTarget) -> Option<SslCertInfo> {
+        let url = Url::parse(&format!("https://{}/", target.host())).ok()?;
+
+        // The certificate is valid if the connection succeeds with it being verified
+        let (response, is_valid) = match http::head_url(client, url.clone()).await {
+            Ok(response) => (response, true),
+            Err(_) => match http::head_url(insecure_client, url.clone()).await {
+                Ok(response) => (response, false),
+                Err(error) => { error!("Failed to connect to {}: {}", url, error); return None; }
+            }
+        };
+
+        let certificate = X509::from_der(response.extensions().get::<TlsInfo>()?.peer_certificate()?).ok()?;
+
+        let days_until_expiry = i64::from(Asn1Time::days_from_now(0).ok()?.diff(certificate.not_after()).ok()?.days);
+        let since_epoch = Asn1Time::from_unix(0).ok()?.diff(certificate.not_after()).ok()?;
+        let expiry = SystemTime::UNIX_EPOCH + Duration::from_secs((i64::from(since_epoch.days) * 24 * 60 * 60 + i64::from(since_epoch.secs)).max(0) as u64);
+
+        let warning = if !is_valid {
+            Some(String::from("The certificate is not trusted for the host"))
+        } else if days_until_expiry < EXPIRY_WARNING_DAYS {
+            Some(format!("The certificate expires in {} days", days_until_expiry))
+        } else {
+            None
+        };
+
+        Some(SslCertInfo {
+            host: target.host().clone(),
+            expiry,
+            days_until_expiry,
+            subject_cn: certificate
+                .subject_name()
+                .entries_by_nid(Nid::COMMONNAME)
+                .next()
+                .and_then(|entry| entry.data().to_string().ok())
+                .unwrap_or_default(),
+            issuer: distinguished_name(certificate.issuer_name()),
+            is_valid,
+            alt_names: certificate
+                .subject_alt_names()
+                .map(|alt_names| alt_names.iter().filter_map(|alt_name| alt_name.dnsname().map(str::to_owned)).collect())
+                .unwrap_or_default(),
+            warning
+        })
+    }
+}
+
+/// Format an X.509 name as its short attribute names and values, e.g. C=US, O=Let's Encrypt, CN=R3
+fn distinguished_name(name: &X509NameRef) -> String {
+    name.entries()
+        .filter_map(|entry| {
+            let attribute = entry.object().nid().short_name().ok()?;
+            let value = entry.data().to_string().ok()?;
+            Some(format!("{}={}", attribute, value))
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
@@ -0,0 +1,29 @@
+use std::{fmt, sync::Arc};
+
+use futures::future::BoxFuture;
+
+use super::{crawl_target::CrawlTarget, CrawlerError};
+
+/// The future of a target hook's task
+pub type TargetHookFuture = BoxFuture<'static, Result<(), CrawlerError>>;
+
+/// A setup or cleanup task run for each crawl target
+#[derive(Clone)]
+pub struct TargetHook(Arc<dyn Fn(&CrawlTarget) -> TargetHookFuture + Send + Sync>);
+
+impl TargetHook {
+    pub fn new(hook: impl Fn(&CrawlTarget) -> TargetHookFuture + Send + Sync + 'static) -> TargetHook {
+        TargetHook(Arc::new(hook))
+    }
+
+    /// Run the task for the target
+    pub async fn run(&self, target: &CrawlTarget) -> Result<(), CrawlerError> {
+        (self.0)(target).await
+    }
+}
+
+impl fmt::Debug for TargetHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TargetHook")
+    }
+}
@@ -0,0 +1,24 @@
+use std::{fmt, sync::Arc};
+
+use crate::web::host::Host;
+
+/// Assigns the targets found during a crawl their priority by host, higher priority targets being crawled sooner
+#[derive(Clone)]
+pub struct PriorityFn(Arc<dyn Fn(&Host) -> i32 + Send + Sync>);
+
+impl PriorityFn {
+    pub fn new(priority_fn: impl Fn(&Host) -> i32 + Send + Sync + 'static) -> PriorityFn {
+        PriorityFn(Arc::new(priority_fn))
+    }
+
+    /// Returns the priority of a target with the host
+    pub fn priority(&self, host: &Host) -> i32 {
+        (self.0)(host)
+    }
+}
+
+impl fmt::Debug for PriorityFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PriorityFn")
+    }
+}
@@ -0,0 +1,93 @@
+use std::{collections::{HashMap, VecDeque}, sync::{Arc, Mutex, MutexGuard}};
+
+use tokio::sync::oneshot;
+
+/// Hands out the permits to request pages to the targets in turn, a page of each target waiting for one at a time,
+/// rather than to the pages in the order they asked for them. A target queueing many pages then doesn't hold up the others.
+#[derive(Debug)]
+pub(crate) struct TargetRotation {
+    state: Mutex<RotationState>
+}
+
+#[derive(Debug)]
+struct RotationState {
+    available: usize,                                                // The permits not held by any page
+    waiting: HashMap<String, VecDeque<oneshot::Sender<RotationPermit>>>, // The pages waiting for a permit, by target
+    turns: VecDeque<String>                                          // The targets with pages waiting, in the order they get permits
+}
+
+/// A permit to request a page, handed to the next target's page when dropped
+#[derive(Debug)]
+pub(crate) struct RotationPermit {
+    rotation: Option<Arc<TargetRotation>> // None once the permit has been handed on
+}
+
+impl TargetRotation {
+    pub(crate) fn new(permits: usize) -> TargetRotation {
+        TargetRotation {
+            state: Mutex::new(RotationState { available: permits, waiting: HashMap::new(), turns: VecDeque::new() })
+        }
+    }
+
+    /// Wait for a permit to request a page of the target, given as the key of the target, e.g. its registered domain
+    pub(crate) async fn acquire(self: &Arc<Self>, target: &str) -> Option<RotationPermit> {
+        let receiver = {
+            let mut state = self.lock();
+
+            if state.available > 0 {
+                state.available -= 1;
+                return Some(RotationPermit { rotation: Some(Arc::clone(self)) });
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            let waiting = state.waiting.entry(target.to_owned()).or_default();
+            waiting.push_back(sender);
+            if waiting.len() == 1 { state.turns.push_back(target.to_owned()); }
+
+            receiver
+        };
+
+        receiver.await.ok()
+    }
+
+    /// Hand a released permit to the first page of the target whose turn it is, or keep it if no page is waiting
+    fn release(self: &Arc<Self>) {
+        let mut state = self.lock();
+
+        while let Some(target) = state.turns.pop_front() {
+            let Some(waiting) = state.waiting.get_mut(&target) else { continue; };
+            let Some(sender) = waiting.pop_front() else { continue; };
+
+            if waiting.is_empty() {
+                state.waiting.remove(&target);
+            } else {
+                state.turns.push_back(target);
+            }
+
+            // Pages which stopped waiting, e.g. as their target was cancelled, pass their turn on
+            if let Err(mut permit) = sender.send(RotationPermit { rotation: Some(Arc::clone(self)) }) {
+                permit.rotation = None;
+                continue;
+            }
+            return;
+        }
+
+        state.available += 1;
+    }
+
+    // The state is updated as a whole while locked, so it's consistent even if a holder of the lock panicked
+    fn lock(&self) -> MutexGuard<'_, RotationState> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(error) => error.into_inner()
+        }
+    }
+}
+
+impl Drop for RotationPermit {
+    fn drop(&mut self) {
+        if let Some(rotation) = self.rotation.take() {
+            rotation.release();
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use clap::ValueEnum;
+use regex::Regex;
+use url::Url;
+
+use super::normalize::UrlNormalizer;
+
+/// Rewrites URLs matching a pattern before they are deduplicated, e.g. to ignore changing version numbers.
+/// The original URL is still the one fetched.
+#[derive(Debug, Clone)]
+pub struct UrlTransform {
+    pub pattern: Regex,
+    pub replacement: String // May refer to the pattern's capture groups, e.g. $1
+}
+
+impl UrlTransform {
+    pub fn new(pattern: Regex, replacement: &str) -> UrlTransform {
+        UrlTransform { pattern, replacement: replacement.to_owned() }
+    }
+
+    /// Rewrite every match of the pattern in the URL
+    pub fn apply(&self, url: &str) -> String {
+        self.pattern.replace_all(url, self.replacement.as_str()).into_owned()
+    }
+}
+
+/// How the query strings of URLs are treated when deduplicating them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum QueryDeduplication {
+    None,           // URLs are only the same if their query parameters are in the same order
+    #[default]
+    NormalizeOrder, // URLs with the same query parameters in any order are the same, e.g. ?page=1&sort=asc and ?sort=asc&page=1
+    StripAll        // The query strings are ignored, so that e.g. every page of paginated content is crawled once
+}
+
+/// Returns the key a URL is deduplicated by, its normal form with its query treated as configured and the transforms applied in order
+pub fn dedup_key(query_deduplication: QueryDeduplication, transforms: &[UrlTransform], url: &str) -> String {
+    let url = match Url::parse(url) {
+        Ok(mut url) => {
+            if query_deduplication == QueryDeduplication::StripAll { url.set_query(None); }
+            UrlNormalizer::normalize_with_query_order(&url, query_deduplication == QueryDeduplication::NormalizeOrder).to_string()
+        }
+        Err(_) => url.to_owned()
+    };
+
+    transforms
+        .iter()
+        .fold(url, |url, transform| transform.apply(&url))
+}
@@ -0,0 +1,124 @@
+use std::collections::{HashMap, HashSet};
+
+use tracing::error;
+use url::{Position, Url};
+
+use super::{crawl_result::CrawlResult, crawl_target::CrawlTarget, Crawler};
+
+/// The assertions a crawl is checked against, e.g. in CI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrawlSpec {
+    pub all_urls_must_return_200: bool, // Whether every crawled page must respond with 200 OK
+    pub max_broken_links: usize,        // The maximum number of links to pages which responded with an error status code
+    pub required_pages: Vec<Url>,       // Pages which must be crawled successfully
+    pub forbidden_pages: Vec<Url>,      // Pages which must not be reachable
+    pub max_redirect_depth: usize,      // The maximum number of redirects followed to reach any page
+    pub required_schema_types: Vec<String> // Schema.org types which must be declared by at least one page
+}
+
+impl Default for CrawlSpec {
+    /// A spec allowing no broken links and at most 10 redirects per page, without requiring or forbidding any pages
+    fn default() -> Self {
+        CrawlSpec {
+            all_urls_must_return_200: false,
+            max_broken_links: 0,
+            max_redirect_depth: 10,
+            required_pages: Vec::new(),
+            forbidden_pages: Vec::new(),
+            required_schema_types: Vec::new()
+        }
+    }
+}
+
+/// A way a crawl failed its spec
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationFailure {
+    NonOkStatus { url: Url, status_code: u16 },                       // A page responded with another status code than 200
+    TooManyBrokenLinks { broken_links: Vec<(Url, Url)>, max: usize }, // The links, from page to broken page, exceeding the maximum
+    MissingRequiredPage(Url),                                         // A required page wasn't crawled, or failed
+    ForbiddenPageCrawled(Url),                                        // A forbidden page was crawled
+    RedirectChainTooLong { url: Url, redirects: usize, max: usize },  // A page was only reached through too many redirects
+    MissingSchemaType(String)                                         // No page declared a required Schema.org type
+}
+
+/// The outcome of checking a crawl against a spec
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValidationResult {
+    pub passed: bool,
+    pub failures: Vec<ValidationFailure>
+}
+
+impl Crawler {
+    /// Crawl the targets from their main pages and check the results against the spec
+    pub async fn crawl_and_validate(&self, targets: Vec<CrawlTarget>, spec: CrawlSpec) -> ValidationResult {
+        if let Err(error) = self.set_up_database() {
+            error!("Failed to set up DB: {}", error);
+            return ValidationResult::default();
+        }
+
+        match self.crawl_targets.lock() {
+            Ok(mut crawl_targets) => crawl_targets.extend(targets.iter().cloned()),
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        let results = self.crawl_collecting(targets.into_iter().map(|target| (target, Vec::new())).collect()).await;
+
+        Self::validate_crawl(&results, &spec)
+    }
+
+    /// Check crawl results against a spec. URLs differing only in their fragment are the same page.
+    pub fn validate_crawl(results: &[CrawlResult], spec: &CrawlSpec) -> ValidationResult {
+        let crawled_pages: HashMap<&str, &CrawlResult> = results
+            .iter()
+            .map(|result| (&result.url[..Position::AfterQuery], result))
+            .collect();
+
+        let mut failures = Vec::new();
+
+        if spec.all_urls_must_return_200 {
+            for result in results.iter().filter(|result| result.status_code != 200) {
+                failures.push(ValidationFailure::NonOkStatus { url: result.url.clone(), status_code: result.status_code });
+            }
+        }
+
+        let broken_links: Vec<(Url, Url)> = results
+            .iter()
+            .flat_map(|result| result.links.iter().map(move |link| (result, link)))
+            .filter(|(_, link)| crawled_pages.get(&link[..Position::AfterQuery]).is_some_and(|linked| linked.status_code >= 400))
+            .map(|(result, link)| (result.url.clone(), link.clone()))
+            .collect();
+
+        if broken_links.len() > spec.max_broken_links {
+            failures.push(ValidationFailure::TooManyBrokenLinks { broken_links, max: spec.max_broken_links });
+        }
+
+        for url in &spec.required_pages {
+            let crawled = crawled_pages
+                .get(&url[..Position::AfterQuery])
+                .is_some_and(|result| (200..300).contains(&result.status_code));
+
+            if !crawled { failures.push(ValidationFailure::MissingRequiredPage(url.clone())); }
+        }
+
+        for url in &spec.forbidden_pages {
+            if crawled_pages.contains_key(&url[..Position::AfterQuery]) {
+                failures.push(ValidationFailure::ForbiddenPageCrawled(url.clone()));
+            }
+        }
+
+        for result in results.iter().filter(|result| result.redirect_chain.len() > spec.max_redirect_depth) {
+            failures.push(ValidationFailure::RedirectChainTooLong {
+                url: result.url.clone(),
+                redirects: result.redirect_chain.len(),
+                max: spec.max_redirect_depth
+            });
+        }
+
+        let schema_types: HashSet<&String> = results.iter().flat_map(|result| &result.schema_org_types).collect();
+        for schema_type in spec.required_schema_types.iter().filter(|schema_type| !schema_types.contains(schema_type)) {
+            failures.push(ValidationFailure::MissingSchemaType(schema_type.clone()));
+        }
+
+        ValidationResult { passed: failures.is_empty(), failures }
+    }
+}
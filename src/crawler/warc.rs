@@ -0,0 +1,82 @@
+use std::io::{self, Write};
+
+use reqwest::StatusCode;
+use url::Position;
+
+use super::{crawl_result::CrawlResult, Crawler};
+
+/// Response headers describing the original transfer of the body, which no longer applies to the stored one
+const TRANSFER_HEADERS: [&str; 2] = ["content-length", "transfer-encoding"];
+
+impl Crawler {
+    /// Write the crawl results to a WARC 1.1 file, as a request record followed by a response record for each page.
+    /// Records are written one at a time, so wrapping the writer in a `BufWriter` is recommended for files.
+    /// The records of each page are dated with when its response was received, and hold its body as received.
+    pub fn export_warc(results: &[CrawlResult], mut writer: impl Write) -> io::Result<()> {
+        for result in results {
+            let date = humantime::format_rfc3339_seconds(result.fetched_at).to_string();
+            let response_id = record_id();
+
+            let request = format!(
+                "GET {} HTTP/1.1\r\nHost: {}\r\n\r\n",
+                &result.url[Position::BeforePath..Position::AfterQuery],
+                &result.url[Position::BeforeHost..Position::AfterPort]
+            );
+            write_record(&mut writer, &[
+                ("WARC-Type", "request"),
+                ("WARC-Record-ID", &record_id()),
+                ("WARC-Concurrent-To", &response_id),
+                ("WARC-Date", &date),
+                ("WARC-Target-URI", result.url.as_str()),
+                ("Content-Type", "application/http; msgtype=request")
+            ], request.as_bytes())?;
+
+            let status_code = StatusCode::from_u16(result.status_code).ok();
+            let mut response = format!(
+                "{} {} {}\r\n",
+                result.http_version,
+                result.status_code,
+                status_code.and_then(|status_code| status_code.canonical_reason()).unwrap_or_default()
+            );
+
+            // The stored body isn't chunked, so its original length and transfer encoding are replaced
+            for (name, value) in &result.response_headers {
+                if TRANSFER_HEADERS.contains(&name.to_ascii_lowercase().as_str()) { continue; }
+                response.push_str(&format!("{}: {}\r\n", name, value));
+            }
+            response.push_str(&format!("Content-Length: {}\r\n\r\n", result.body_bytes.len()));
+
+            let mut response = response.into_bytes();
+            response.extend_from_slice(&result.body_bytes);
+
+            write_record(&mut writer, &[
+                ("WARC-Type", "response"),
+                ("WARC-Record-ID", &response_id),
+                ("WARC-Date", &date),
+                ("WARC-Target-URI", result.url.as_str()),
+                ("Content-Type", "application/http; msgtype=response")
+            ], &response)?;
+        }
+
+        writer.flush()
+    }
+}
+
+/// Write a WARC record with the given named fields, followed by its length and block
+fn write_record(writer: &mut impl Write, fields: &[(&str, &str)], block: &[u8]) -> io::Result<()> {
+    writer.write_all(b"WARC/1.1\r\n")?;
+    for (name, value) in fields {
+        write!(writer, "{}: {}\r\n", name, value)?;
+    }
+    write!(writer, "Content-Length: {}\r\n\r\n", block.len())?;
+    writer.write_all(block)?;
+    writer.write_all(b"\r\n\r\n")
+}
+
+/// Generate a random version 4 UUID record ID
+fn record_id() -> String {
+    let uuid = (rand::random::<u128>() & !(0xf << 76) & !(0x3 << 62)) | (0x4 << 76) | (0x2 << 62);
+    let hex = format!("{:032x}", uuid);
+
+    format!("<urn:uuid:{}-{}-{}-{}-{}>", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
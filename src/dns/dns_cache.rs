@@ -0,0 +1,58 @@
+use std::{collections::HashMap, net::IpAddr, sync::{Arc, Mutex}, time::Duration};
+
+use tokio::time::Instant;
+use tracing::error;
+
+use super::domain_name::DomainName;
+
+/// When a domain name was resolved and the addresses it resolved to
+type ResolvedAddresses = (Instant, Arc<Vec<IpAddr>>);
+
+/// Caches the addresses domain names resolve to with the system resolver, for comparing them with IP address hosts.
+/// Each domain name is resolved on its first lookup, and again once its addresses are older than the TTL.
+#[derive(Debug)]
+pub struct DnsCache {
+    ttl: Duration,
+    addresses: Mutex<HashMap<DomainName, ResolvedAddresses>>
+}
+
+impl DnsCache {
+    pub fn new(ttl: Duration) -> DnsCache {
+        DnsCache { ttl, addresses: Mutex::new(HashMap::new()) }
+    }
+
+    /// Return the addresses of the domain name, resolving it if it isn't cached or has expired.
+    /// A domain name which fails to resolve is cached as having no addresses.
+    pub async fn resolve(&self, domain_name: &DomainName) -> Arc<Vec<IpAddr>> {
+        match self.addresses.lock() {
+            Ok(addresses) => {
+                if let Some((resolved_at, addresses)) = addresses.get(domain_name) {
+                    if resolved_at.elapsed() < self.ttl { return Arc::clone(addresses); }
+                }
+            }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        // Concurrent lookups of an uncached domain name may each resolve it, the last one is kept
+        let resolved_addresses: Vec<IpAddr> = match tokio::net::lookup_host((domain_name.to_string(), 0)).await {
+            Ok(socket_addrs) => socket_addrs.map(|socket_addr| socket_addr.ip()).collect(),
+            Err(_) => Vec::new()
+        };
+        let resolved_addresses = Arc::new(resolved_addresses);
+
+        match self.addresses.lock() {
+            Ok(mut addresses) => { addresses.insert(domain_name.clone(), (Instant::now(), Arc::clone(&resolved_addresses))); }
+            Err(error) => error!("Failed to obtain mutex lock: {}", error)
+        }
+
+        resolved_addresses
+    }
+
+    /// Cache the addresses of the domain name as if it had just been resolved to them
+    #[cfg(test)]
+    pub(crate) fn insert(&self, domain_name: DomainName, addresses: Vec<IpAddr>) {
+        if let Ok(mut cached_addresses) = self.addresses.lock() {
+            cached_addresses.insert(domain_name, (Instant::now(), Arc::new(addresses)));
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use trust_dns_resolver::{
+    config::{NameServerConfig, Protocol, ResolverConfig, ResolverOpts},
+    TokioAsyncResolver,
+};
+use url::Url;
+
+/// A DNS resolver for the web client which resolves host names over HTTPS
+#[derive(Clone)]
+pub struct DohResolver {
+    resolver: TokioAsyncResolver
+}
+
+impl DohResolver {
+    /// Create a resolver using Cloudflare's DNS-over-HTTPS servers
+    pub fn cloudflare() -> DohResolver {
+        DohResolver {
+            resolver: TokioAsyncResolver::tokio(ResolverConfig::cloudflare_https(), ResolverOpts::default())
+        }
+    }
+
+    /// Create a resolver using the DNS-over-HTTPS server at the URL, with the given options, e.g. which IP versions are looked up.
+    /// The server's own address is looked up with the system resolver.
+    pub fn with_server(server: &Url, options: ResolverOpts) -> io::Result<DohResolver> {
+        let Some(server_host) = server.host_str() else {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "DNS-over-HTTPS server URL has no host"));
+        };
+        let server_host = server_host.trim_start_matches('[').trim_end_matches(']');
+        let server_port = server.port_or_known_default().unwrap_or(443);
+
+        let mut config = ResolverConfig::new();
+        for socket_addr in (server_host, server_port).to_socket_addrs()? {
+            let mut name_server = NameServerConfig::new(socket_addr, Protocol::Https);
+            name_server.tls_dns_name = Some(server_host.to_owned());
+            config.add_name_server(name_server);
+        }
+
+        Ok(DohResolver {
+            resolver: TokioAsyncResolver::tokio(config, options)
+        })
+    }
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.resolver.clone();
+
+        Box::pin(async move {
+            let lookup = resolver.lookup_ip(name.as_str()).await?;
+            let addrs: Addrs = Box::new(
+                lookup
+                    .into_iter()
+                    .map(|ip| SocketAddr::new(ip, 0))
+                    .collect::<Vec<SocketAddr>>()
+                    .into_iter()
+            );
+
+            Ok(addrs)
+        })
+    }
+}
@@ -1,6 +1,8 @@
 use std::fmt::{self, Display};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DomainName {
     tld: String,
     domain: String,
@@ -15,21 +17,21 @@ impl DomainName {
         let domain_levels_len = domain_levels.len();
 
         if domain_levels_len < 2 {
-            return Err(DomainNameParseError);
+            Err(DomainNameParseError)
         }
         else if domain_levels_len == 2 {
-            return Ok(Self {
+            Ok(Self {
                 tld: domain_levels.last().unwrap().to_owned().to_string(),
                 domain: domain_levels.first().unwrap().to_owned().to_string(),
                 subdomains: None
-            });
+            })
         }
         else {
-            return Ok(Self {
+            Ok(Self {
                 tld: domain_levels.last().unwrap().to_owned().to_string(),
                 domain: domain_levels[domain_levels_len - 2].to_string(),
                 subdomains: Some(domain_levels[0..domain_levels.len() - 2].iter().map(|v| v.to_string()).collect())
-            });
+            })
         }
     }
 
@@ -64,7 +66,7 @@ impl DomainName {
 
     /// Returns whether the domain name is superdomain of another domain name
     pub fn is_superdomain_of(&self, other: &DomainName) -> bool {
-        other.is_subdomain_of(&self)
+        other.is_subdomain_of(self)
     }
 
     /// Returns the top-level domain of the domain name.
@@ -1 +1,5 @@
-pub mod domain_name;
\ No newline at end of file
+pub mod dns_cache;
+pub mod domain_name;
+pub mod public_resolver;
+#[cfg(feature = "dns-over-https")]
+pub mod doh_resolver;
\ No newline at end of file
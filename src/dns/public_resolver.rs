@@ -0,0 +1,39 @@
+use std::{error::Error, net::SocketAddr, sync::Arc};
+
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+
+use crate::web::host::{is_private_address, PrivateAddressError};
+
+/// A DNS resolver for the web client which fails to resolve host names with a private address, so that neither redirects
+/// nor changed DNS records lead the crawler to hosts it refused to request
+pub struct PublicAddressResolver {
+    resolver: Option<Arc<dyn Resolve>> // Resolves the host names, or the system resolver if None
+}
+
+impl PublicAddressResolver {
+    pub fn new(resolver: Option<Arc<dyn Resolve>>) -> PublicAddressResolver {
+        PublicAddressResolver { resolver }
+    }
+}
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.resolver.clone();
+
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = match resolver {
+                Some(resolver) => resolver.resolve(name.clone()).await?.collect(),
+                None => tokio::net::lookup_host((name.as_str(), 0)).await?.collect()
+            };
+
+            if let Some(address) = addresses.iter().map(SocketAddr::ip).find(is_private_address) {
+                let error: Box<dyn Error + Send + Sync> = Box::new(PrivateAddressError { host: name.as_str().to_owned(), address });
+                return Err(error);
+            }
+
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
@@ -0,0 +1,5 @@
+pub mod cli;
+pub mod crawler;
+pub mod dns;
+pub mod util;
+pub mod web;
@@ -1,69 +1,143 @@
-mod cli;
-mod crawler;
-mod dns;
-mod util;
-mod web;
-
-use cli::{args::Args, Cli};
-use crawl_target::*;
-use crawler::{crawler_config::CrawlerConfig, *};
-use dns::domain_name::DomainName;
-use rusqlite::{params, Connection};
-use web::host::Host;
+use cherna_vdovitsa::{
+    cli::args::Args,
+    web::http::RetryPolicy,
+    crawler::{crawl_target::CrawlTarget, crawler_config::{CrawlerConfig, DEFAULT_EVENT_CHANNEL_CAPACITY}, error_handler::ErrorHandler, filter::UrlFilter, http_cache::HttpCacheConfig, proxy::ProxyConfig, scope::ScopePolicy, Crawler},
+};
 
 use clap::Parser;
 use std::{
-    collections::HashSet, fs::{self, File}, io::{BufRead, BufReader}, path::PathBuf, process
+    collections::{HashMap, HashSet}, time::{Duration, Instant}
 };
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
-    let targets_file = File::open(&args.targets)?;
-
-    let targets_reader = BufReader::new(targets_file);
-    let mut initial_targets: HashSet<CrawlTarget> = HashSet::new();
+    let initial_targets: HashSet<CrawlTarget> = CrawlTarget::from_domain_file(&args.targets)?.into_iter().collect();
 
     console_subscriber::init();
 
-    // Process target hosts from file
-    for line in targets_reader.lines() {
-        match line {
-            Ok(line) => {
-                if let Ok(domain_name) = DomainName::parse(&line) {
-                    initial_targets.insert(CrawlTarget::new(Host::Domain(domain_name)));
-                } else {
-                    eprintln!("Failed to parse target URL: {}", line);
-                }
-            }
-            Err(error) => eprintln!("Failed to read targets from file: {}", error.to_string()),
-        };
-    }
-
+    // The crawler sets up the output database's tables itself
     let db_path = path_clean::clean(std::env::current_dir()?.join(&args.output_file));
 
-    // Set up the output database
-    let db = Connection::open(&args.output_file)?;
-    
-    // Create initial targets table
-    db.execute("CREATE TABLE IF NOT EXISTS targets (
-        id INTEGER PRIMARY KEY,
-        host TEXT)", ())?;
-
-    db.close().unwrap();
-
-    let crawler_config = CrawlerConfig {
+    let mut crawler_config = CrawlerConfig {
         initial_targets,
-        crawl_subdomains: args.crawl_subdomains,
+        seed_urls: Vec::new(),
+        scope_policy: match (args.crawl_subdomains, args.crawl_any_host) {
+            (_, true) => ScopePolicy::AnyHost,
+            (true, false) => ScopePolicy::RelatedHosts,
+            (false, false) => ScopePolicy::SameHostOnly
+        },
+        user_agent: args.user_agent,
+        default_headers: args.headers.into_iter().collect(),
+        request_timeout: Some(Duration::from_secs(args.request_timeout)),
+        connect_timeout: Some(Duration::from_secs(args.connect_timeout)),
+        crawl_deadline: args.time_limit.map(|time_limit| Instant::now() + Duration::from_secs(time_limit)),
+        follow_redirects: !args.no_follow_redirects,
+        max_redirects: args.max_redirects,
+        ipv4_only: args.ipv4_only,
+        ipv6_only: args.ipv6_only,
+        proxy: args.proxy.map(|url| ProxyConfig { no_proxy: args.no_proxy, ..ProxyConfig::new(url) }),
+        http_cache: args.http_cache_dir.map(HttpCacheConfig::filesystem),
+        max_concurrent_tasks: args.max_concurrent_tasks,
+        max_connections_per_host: args.max_connections_per_host,
+        tcp_keepalive: args.tcp_keepalive.map(Duration::from_secs),
+        pool_idle_timeout: Some(Duration::from_secs(args.pool_idle_timeout)),
+        fail_fast_on_error: args.fail_fast_on_error,
         db_path,
+        max_depth: args.max_depth,
+        max_total_bytes: args.max_total_bytes,
+        max_query_params: args.max_query_params,
+        excluded_query_params: args.excluded_query_params,
+        max_response_time: args.max_response_time.map(Duration::from_secs),
+        path_blocklist: args.path_blocklist,
+        url_filter: UrlFilter::new(args.allow_patterns, args.deny_patterns),
+        observer: None,
+        cross_domain_redirect_policy: args.cross_domain_redirect_policy,
+        page_rank_threshold: args.page_rank_threshold,
+        retry_policy: RetryPolicy { max_attempts: args.max_attempts, ..Default::default() },
+        error_handler: ErrorHandler::default(),
+        tls_min_version: args.tls_min_version,
+        fail_on_old_tls: args.fail_on_old_tls,
+        tls_verify: !args.insecure,
+        root_certificates: args.root_certificates,
+        content_type_map: HashMap::new(),
+        max_response_header_size: args.max_response_header_size,
+        max_body_bytes: args.max_body_bytes,
+        middleware: Vec::new(),
+        extract_resource_hints: args.extract_resource_hints,
+        url_transforms: Vec::new(),
+        query_deduplication: args.query_deduplication,
+        link_selectors: args.link_selectors,
+        url_batch_size: args.url_batch_size,
+        link_channel_buffer: args.link_channel_buffer,
+        target_channel_buffer: args.target_channel_buffer,
+        politeness_delay: args.politeness_delay.map(Duration::from_millis),
+        respect_robots_txt: !args.ignore_robots_txt,
+        allow_private_hosts: args.allow_private_hosts,
+        respect_nofollow: !args.ignore_nofollow,
+        cookie_store: args.cookie_store,
+        record_cookies: args.record_cookies,
+        timing_enabled: args.timing_enabled,
+        rotate_targets: args.rotate_targets,
+        parse_csp_headers: args.parse_csp_headers,
+        use_sitemap: !args.no_sitemap,
+        crawl_mode: args.crawl_mode,
+        robots_cache_ttl: Duration::from_secs(args.robots_cache_ttl),
+        dns_cache_ttl: Duration::from_secs(args.dns_cache_ttl),
+        pre_crawl_hook: None,
+        post_crawl_hook: None,
+        priority_fn: None,
+        event_channel_capacity: DEFAULT_EVENT_CHANNEL_CAPACITY,
+        #[cfg(feature = "dns-over-https")]
+        dns_over_https: args.dns_over_https,
     };
 
-    let mut crawler = Crawler::new(crawler_config)?;
+    if let Some(seed_csv) = &args.seed_csv {
+        crawler_config.seed_urls_from_csv(seed_csv, &args.seed_csv_column)?;
+    }
+
+    if args.strip_utm_params {
+        crawler_config.strip_utm_params();
+    }
+
+    if args.strip_tracking_params {
+        crawler_config.strip_common_tracking_params();
+    }
+
+    let crawler = Crawler::new(crawler_config)?;
+
+    // Stop the crawl on Ctrl+C, still reporting what was found
+    let cancel_token = crawler.cancel_token();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            cancel_token.cancel();
+        }
+    });
+
     let crawler_task = tokio::spawn(async move {
-        crawler.crawl().await;
+        crawler.crawl().await
     });
 
-    crawler_task.await.unwrap();
+    let report = crawler_task.await.unwrap()?;
+
+    println!(
+        "Crawled {} URLs across {} targets in {:.1}s, downloading {} bytes and failing on {}",
+        report.discovered_urls.values().map(|urls| urls.len()).sum::<usize>(),
+        report.discovered_urls.len(),
+        report.crawl_duration.as_secs_f64(),
+        report.bytes_downloaded,
+        report.errors.len()
+    );
+    println!(
+        "Sent {} requests, answered in {:.0}ms on average and {:.0}ms at the 95th percentile",
+        report.stats.total_requests,
+        report.stats.mean_latency.as_secs_f64() * 1000.0,
+        report.stats.p95_latency.as_secs_f64() * 1000.0
+    );
+
+    if report.cancelled {
+        println!("The crawl was stopped before every page was crawled.");
+    }
 
     Ok(())
 }
@@ -0,0 +1,63 @@
+use std::io::{self, BufRead, Lines};
+
+/// Returns a CSV record of the fields, without a line break. Fields containing commas, quotes or line breaks are quoted.
+pub fn csv_record(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) { format!("\"{}\"", field.replace('"', "\"\"")) } else { (*field).to_owned() }
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Reads the records of a CSV file one at a time, supporting quoted fields containing commas, escaped quotes and line breaks
+pub struct CsvReader<R> {
+    lines: Lines<R>
+}
+
+impl<R: BufRead> CsvReader<R> {
+    pub fn new(reader: R) -> CsvReader<R> {
+        CsvReader { lines: reader.lines() }
+    }
+}
+
+impl<R: BufRead> Iterator for CsvReader<R> {
+    type Item = io::Result<Vec<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = match self.lines.next()? {
+            Ok(line) => line,
+            Err(error) => return Some(Err(error))
+        };
+
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+
+        loop {
+            let mut chars = line.chars().peekable();
+            while let Some(char) = chars.next() {
+                match char {
+                    '"' if in_quotes && chars.peek() == Some(&'"') => { field.push('"'); chars.next(); }
+                    '"' => in_quotes = !in_quotes,
+                    ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+                    char => field.push(char)
+                }
+            }
+
+            if !in_quotes { break; }
+
+            // The quoted field continues on the next line
+            field.push('\n');
+            line = match self.lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(error)) => return Some(Err(error)),
+                None => break
+            };
+        }
+
+        fields.push(field);
+        Some(Ok(fields))
+    }
+}
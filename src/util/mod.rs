@@ -1,7 +1,88 @@
-use tokio::sync::mpsc;
+pub mod csv;
+
+use tokio::sync::mpsc::{self, error::SendError};
 
 #[derive(Debug)]
 pub struct ChannelPacket<T> {
-    pub sender: mpsc::Sender<ChannelPacket<T>>, // I don't know why this works
+    pub sender: PacketSender<T>, // I don't know why this works
     pub data: T
-}
\ No newline at end of file
+}
+
+/// Create a channel of packets buffering up to `buffer` of them, or any number of them if `buffer` is zero
+pub fn packet_channel<T>(buffer: usize) -> (PacketSender<T>, PacketReceiver<T>) {
+    if buffer == 0 {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (PacketSender::Unbounded(tx), PacketReceiver::Unbounded(rx))
+    } else {
+        let (tx, rx) = mpsc::channel(buffer);
+        (PacketSender::Bounded(tx), PacketReceiver::Bounded(rx))
+    }
+}
+
+/// Sends packets over a bounded or an unbounded channel
+#[derive(Debug)]
+pub enum PacketSender<T> {
+    Bounded(mpsc::Sender<ChannelPacket<T>>),
+    Unbounded(mpsc::UnboundedSender<ChannelPacket<T>>)
+}
+
+impl<T> PacketSender<T> {
+    /// Send a packet, waiting for room in the channel if it's bounded
+    pub async fn send(&self, packet: ChannelPacket<T>) -> Result<(), SendError<ChannelPacket<T>>> {
+        match self {
+            Self::Bounded(sender) => sender.send(packet).await,
+            Self::Unbounded(sender) => sender.send(packet)
+        }
+    }
+
+    /// Returns a sender which doesn't keep the channel open
+    pub fn downgrade(&self) -> WeakPacketSender<T> {
+        match self {
+            Self::Bounded(sender) => WeakPacketSender::Bounded(sender.downgrade()),
+            Self::Unbounded(sender) => WeakPacketSender::Unbounded(sender.downgrade())
+        }
+    }
+}
+
+impl<T> Clone for PacketSender<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Bounded(sender) => Self::Bounded(sender.clone()),
+            Self::Unbounded(sender) => Self::Unbounded(sender.clone())
+        }
+    }
+}
+
+/// A packet sender which doesn't keep the channel open, and has to be upgraded to send
+#[derive(Debug)]
+pub enum WeakPacketSender<T> {
+    Bounded(mpsc::WeakSender<ChannelPacket<T>>),
+    Unbounded(mpsc::WeakUnboundedSender<ChannelPacket<T>>)
+}
+
+impl<T> WeakPacketSender<T> {
+    /// Returns a sender, unless the channel has already closed
+    pub fn upgrade(&self) -> Option<PacketSender<T>> {
+        match self {
+            Self::Bounded(sender) => sender.upgrade().map(PacketSender::Bounded),
+            Self::Unbounded(sender) => sender.upgrade().map(PacketSender::Unbounded)
+        }
+    }
+}
+
+/// Receives packets over a bounded or an unbounded channel
+#[derive(Debug)]
+pub enum PacketReceiver<T> {
+    Bounded(mpsc::Receiver<ChannelPacket<T>>),
+    Unbounded(mpsc::UnboundedReceiver<ChannelPacket<T>>)
+}
+
+impl<T> PacketReceiver<T> {
+    /// Receive the next packet, or None once every sender has been dropped
+    pub async fn recv(&mut self) -> Option<ChannelPacket<T>> {
+        match self {
+            Self::Bounded(receiver) => receiver.recv().await,
+            Self::Unbounded(receiver) => receiver.recv().await
+        }
+    }
+}
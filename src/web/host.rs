@@ -1,9 +1,12 @@
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::error::Error;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::fmt;
 
-use crate::dns::domain_name::{DomainName, DomainNameParseError};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+use crate::dns::{dns_cache::DnsCache, domain_name::{DomainName, DomainNameParseError}};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Host {
     Domain(DomainName),
     Ipv4(Ipv4Addr),
@@ -11,6 +14,28 @@ pub enum Host {
 }
 
 impl Host {
+    /// Returns how the hosts are related without resolving any domain names.
+    /// Domain names are related if they share their registrable domain, and IP addresses are the same host if they're equal,
+    /// comparing IPv4-mapped IPv6 addresses as IPv4 addresses. A domain name and an IP address are always unrelated.
+    ///
+    /// ```
+    /// use cherna_vdovitsa::{dns::domain_name::DomainName, web::host::{Host, HostRelationship}};
+    ///
+    /// let www = Host::Domain(DomainName::parse("www.example.com").unwrap());
+    /// let blog = Host::Domain(DomainName::parse("blog.example.com").unwrap());
+    /// assert_eq!(Host::host_relationship(&www, &blog), HostRelationship::Related);
+    ///
+    /// let other = Host::Domain(DomainName::parse("www.example.org").unwrap());
+    /// assert_eq!(Host::host_relationship(&www, &other), HostRelationship::Unrelated);
+    ///
+    /// let ipv4 = Host::Ipv4("192.168.1.1".parse().unwrap());
+    /// let ipv6 = Host::Ipv6("::ffff:192.168.1.1".parse().unwrap());
+    /// assert_eq!(Host::host_relationship(&ipv4, &ipv6), HostRelationship::Same);
+    /// assert_eq!(Host::host_relationship(&ipv6, &Host::Ipv6("2001:db8::1".parse().unwrap())), HostRelationship::Unrelated);
+    ///
+    /// // Unless resolved with resolved_host_relationship
+    /// assert_eq!(Host::host_relationship(&www, &ipv4), HostRelationship::Unrelated);
+    /// ```
     pub fn host_relationship(host1: &Self, host2: &Self) -> HostRelationship {
         match (host1, host2) {
             (Host::Domain(domain_name1), Host::Domain(domain_name2)) => {
@@ -43,10 +68,117 @@ impl Host {
                 }
             },
 
-            // TODO: implement domain name resolution for the cases where one host is a domain and the other is an IP
+            // An IPv4 address and an IPv6 address, which are the same host if the IPv6 address is IPv4-mapped, e.g. ::ffff:192.168.1.1
+            (Host::Ipv4(ipv4), Host::Ipv6(ipv6)) | (Host::Ipv6(ipv6), Host::Ipv4(ipv4)) => {
+                if ipv6.to_ipv4_mapped().is_some_and(|mapped_ip| mapped_ip.eq(ipv4)) {
+                    HostRelationship::Same
+                } else {
+                    HostRelationship::Unrelated
+                }
+            },
+
+            // A domain name and an IP address, which can only be compared by resolving the domain name with resolved_host_relationship
             _ => HostRelationship::Unrelated,
         }
     }
+
+    /// Returns the relationship of the hosts like host_relationship, except that a domain name and an IP address
+    /// are the same host if the domain name resolves to the address, looking it up through the DNS cache
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use cherna_vdovitsa::{dns::dns_cache::DnsCache, web::host::{Host, HostRelationship}};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let dns_cache = DnsCache::new(Duration::from_secs(300));
+    ///
+    /// // Hosts which aren't a domain name and an IP address are compared without resolving anything
+    /// let ipv4 = Host::Ipv4("10.0.0.1".parse().unwrap());
+    /// let other_ipv4 = Host::Ipv4("10.0.0.2".parse().unwrap());
+    /// assert_eq!(Host::resolved_host_relationship(&ipv4, &other_ipv4, &dns_cache).await, HostRelationship::Unrelated);
+    /// # }
+    /// ```
+    ///
+    /// A domain name is the same host as the addresses it resolves to, and as their IPv4-mapped forms:
+    ///
+    /// ```no_run
+    /// use std::{net::{IpAddr, Ipv6Addr}, time::Duration};
+    /// use cherna_vdovitsa::{dns::{dns_cache::DnsCache, domain_name::DomainName}, web::host::{Host, HostRelationship}};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let dns_cache = DnsCache::new(Duration::from_secs(300));
+    /// let domain_name = DomainName::parse("example.com").unwrap();
+    /// let Some(IpAddr::V4(address)) = dns_cache.resolve(&domain_name).await.iter().copied().find(IpAddr::is_ipv4) else { return; };
+    ///
+    /// let example = Host::Domain(domain_name);
+    /// assert_eq!(Host::resolved_host_relationship(&example, &Host::Ipv4(address), &dns_cache).await, HostRelationship::Same);
+    /// assert_eq!(Host::resolved_host_relationship(&Host::Ipv6(address.to_ipv6_mapped()), &example, &dns_cache).await, HostRelationship::Same);
+    /// assert_eq!(Host::resolved_host_relationship(&example, &Host::Ipv6(Ipv6Addr::LOCALHOST), &dns_cache).await, HostRelationship::Unrelated);
+    /// # }
+    /// ```
+    pub async fn resolved_host_relationship(host1: &Self, host2: &Self, dns_cache: &DnsCache) -> HostRelationship {
+        let (domain_name, ip) = match (host1, host2) {
+            (Host::Domain(domain_name), Host::Ipv4(ip)) | (Host::Ipv4(ip), Host::Domain(domain_name)) => (domain_name, IpAddr::V4(*ip)),
+            (Host::Domain(domain_name), Host::Ipv6(ip)) | (Host::Ipv6(ip), Host::Domain(domain_name)) => (domain_name, IpAddr::V6(*ip)),
+            _ => return Self::host_relationship(host1, host2)
+        };
+
+        // IPv4-mapped IPv6 addresses are compared as the IPv4 addresses they map
+        if dns_cache.resolve(domain_name).await.iter().any(|address| address.to_canonical() == ip.to_canonical()) {
+            HostRelationship::Same
+        } else {
+            HostRelationship::Unrelated
+        }
+    }
+}
+
+impl Host {
+    /// Returns a loopback, link-local, private, multicast or unspecified address of the host, resolving domain names
+    /// through the DNS cache, or None if all its addresses are public
+    pub async fn private_address(&self, dns_cache: &DnsCache) -> Option<IpAddr> {
+        match self {
+            Host::Domain(domain_name) => dns_cache.resolve(domain_name).await.iter().copied().find(is_private_address),
+            Host::Ipv4(ip) => Some(IpAddr::V4(*ip)).filter(is_private_address),
+            Host::Ipv6(ip) => Some(IpAddr::V6(*ip)).filter(is_private_address)
+        }
+    }
+}
+
+/// Returns whether the address is loopback, link-local, private, multicast or unspecified, rather than a public one.
+/// IPv4-mapped IPv6 addresses are checked as the IPv4 addresses they map.
+pub fn is_private_address(ip: &IpAddr) -> bool {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_link_local() || ip.is_private() || ip.is_multicast() || ip.is_broadcast() || ip.is_unspecified(),
+        IpAddr::V6(ip) => ip.is_loopback() || ip.is_unicast_link_local() || ip.is_unique_local() || ip.is_multicast() || ip.is_unspecified()
+    }
+}
+
+/// The error of a request to a host with a private address, which isn't allowed to be requested
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateAddressError {
+    pub host: String,
+    pub address: IpAddr
+}
+
+impl fmt::Display for PrivateAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} has the private address {}, which isn't allowed to be requested", self.host, self.address)
+    }
+}
+
+impl Error for PrivateAddressError {}
+
+/// Returns whether the error, or any error it was caused by, is a request to a host with a private address
+pub fn is_private_address_error(error: &(dyn Error + 'static)) -> bool {
+    let mut error = Some(error);
+    while let Some(current) = error {
+        if current.is::<PrivateAddressError>() { return true; }
+        error = current.source();
+    }
+
+    false
 }
 
 impl TryFrom<url::Host<&str>> for Host {
@@ -78,9 +210,107 @@ impl fmt::Display for Host {
     }
 }
 
+/// How two hosts are related, as returned by `Host::host_relationship` and `Host::resolved_host_relationship`
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HostRelationship {
     Same,      // The hosts are the same host
     Related,   // The hosts are related
     Unrelated, // The hosts are unrelated
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn domain(domain_name: &str) -> Host {
+        Host::Domain(DomainName::parse(domain_name).unwrap())
+    }
+
+    fn ipv4(ip: &str) -> Host {
+        Host::Ipv4(ip.parse().unwrap())
+    }
+
+    fn ipv6(ip: &str) -> Host {
+        Host::Ipv6(ip.parse().unwrap())
+    }
+
+    /// A DNS cache resolving www.example.com to 93.184.215.14 and 2606:2800:21f:cb07:6820:80da:af6b:8b2c, without any lookups
+    fn dns_cache() -> DnsCache {
+        let dns_cache = DnsCache::new(Duration::from_secs(300));
+        dns_cache.insert(DomainName::parse("www.example.com").unwrap(), vec![
+            "93.184.215.14".parse().unwrap(),
+            "2606:2800:21f:cb07:6820:80da:af6b:8b2c".parse().unwrap()
+        ]);
+
+        dns_cache
+    }
+
+    #[test]
+    fn domain_and_domain() {
+        assert_eq!(Host::host_relationship(&domain("www.example.com"), &domain("www.example.com")), HostRelationship::Same);
+        assert_eq!(Host::host_relationship(&domain("www.example.com"), &domain("blog.example.com")), HostRelationship::Related);
+        assert_eq!(Host::host_relationship(&domain("example.com"), &domain("blog.example.com")), HostRelationship::Related);
+        assert_eq!(Host::host_relationship(&domain("www.example.com"), &domain("www.example.org")), HostRelationship::Unrelated);
+    }
+
+    #[test]
+    fn ipv4_and_ipv4() {
+        assert_eq!(Host::host_relationship(&ipv4("10.0.0.1"), &ipv4("10.0.0.1")), HostRelationship::Same);
+        assert_eq!(Host::host_relationship(&ipv4("10.0.0.1"), &ipv4("10.0.0.2")), HostRelationship::Unrelated);
+    }
+
+    #[test]
+    fn ipv6_and_ipv6() {
+        assert_eq!(Host::host_relationship(&ipv6("2001:db8::1"), &ipv6("2001:db8:0:0::1")), HostRelationship::Same);
+        assert_eq!(Host::host_relationship(&ipv6("2001:db8::1"), &ipv6("2001:db8::2")), HostRelationship::Unrelated);
+    }
+
+    #[test]
+    fn ipv4_and_ipv6() {
+        // Only IPv4-mapped IPv6 addresses are the same host as an IPv4 address, in either order
+        assert_eq!(Host::host_relationship(&ipv4("192.168.1.1"), &ipv6("::ffff:192.168.1.1")), HostRelationship::Same);
+        assert_eq!(Host::host_relationship(&ipv6("::ffff:192.168.1.1"), &ipv4("192.168.1.1")), HostRelationship::Same);
+        assert_eq!(Host::host_relationship(&ipv4("192.168.1.1"), &ipv6("::ffff:192.168.1.2")), HostRelationship::Unrelated);
+        assert_eq!(Host::host_relationship(&ipv4("192.168.1.1"), &ipv6("2001:db8::1")), HostRelationship::Unrelated);
+    }
+
+    #[test]
+    fn domain_and_ip_address_are_unrelated_unless_resolved() {
+        assert_eq!(Host::host_relationship(&domain("www.example.com"), &ipv4("93.184.215.14")), HostRelationship::Unrelated);
+        assert_eq!(Host::host_relationship(&ipv4("93.184.215.14"), &domain("www.example.com")), HostRelationship::Unrelated);
+        assert_eq!(Host::host_relationship(&domain("www.example.com"), &ipv6("2606:2800:21f:cb07:6820:80da:af6b:8b2c")), HostRelationship::Unrelated);
+        assert_eq!(Host::host_relationship(&ipv6("2606:2800:21f:cb07:6820:80da:af6b:8b2c"), &domain("www.example.com")), HostRelationship::Unrelated);
+    }
+
+    #[tokio::test]
+    async fn resolved_domain_and_ipv4() {
+        let dns_cache = dns_cache();
+
+        assert_eq!(Host::resolved_host_relationship(&domain("www.example.com"), &ipv4("93.184.215.14"), &dns_cache).await, HostRelationship::Same);
+        assert_eq!(Host::resolved_host_relationship(&ipv4("93.184.215.14"), &domain("www.example.com"), &dns_cache).await, HostRelationship::Same);
+        assert_eq!(Host::resolved_host_relationship(&domain("www.example.com"), &ipv4("93.184.215.15"), &dns_cache).await, HostRelationship::Unrelated);
+    }
+
+    #[tokio::test]
+    async fn resolved_domain_and_ipv6() {
+        let dns_cache = dns_cache();
+        let resolved = ipv6("2606:2800:21f:cb07:6820:80da:af6b:8b2c");
+
+        assert_eq!(Host::resolved_host_relationship(&domain("www.example.com"), &resolved, &dns_cache).await, HostRelationship::Same);
+        assert_eq!(Host::resolved_host_relationship(&resolved, &domain("www.example.com"), &dns_cache).await, HostRelationship::Same);
+        assert_eq!(Host::resolved_host_relationship(&domain("www.example.com"), &ipv6("2001:db8::1"), &dns_cache).await, HostRelationship::Unrelated);
+
+        // The IPv4-mapped form of a resolved IPv4 address is the same host too
+        assert_eq!(Host::resolved_host_relationship(&domain("www.example.com"), &ipv6("::ffff:93.184.215.14"), &dns_cache).await, HostRelationship::Same);
+    }
+
+    #[tokio::test]
+    async fn resolved_hosts_other_than_a_domain_and_an_ip_address_are_compared_without_resolving() {
+        let dns_cache = dns_cache();
+
+        assert_eq!(Host::resolved_host_relationship(&domain("www.example.com"), &domain("blog.example.com"), &dns_cache).await, HostRelationship::Related);
+        assert_eq!(Host::resolved_host_relationship(&ipv4("192.168.1.1"), &ipv6("::ffff:192.168.1.1"), &dns_cache).await, HostRelationship::Same);
+        assert_eq!(Host::resolved_host_relationship(&ipv6("2001:db8::1"), &ipv6("2001:db8::2"), &dns_cache).await, HostRelationship::Unrelated);
+    }
+}
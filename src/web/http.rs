@@ -1,4 +1,8 @@
-use reqwest::{header::{HeaderMap, HeaderValue}, Client, Response};
+use std::{error::Error, fmt, str::FromStr, time::{Duration, SystemTime}};
+
+use clap::ValueEnum;
+use encoding_rs::{Encoding, UTF_8};
+use reqwest::{header::{HeaderMap, HeaderValue}, tls, Client, Response, StatusCode, Version};
 use url::Url;
 
 
@@ -7,17 +11,217 @@ pub async fn head_url(client: &Client, url: Url) -> Result<Response, reqwest::Er
     client.head(url).send().await
 }
 
-/// Perform an asynchrnonous GET request to the specified URL
-pub async fn get_url(client: &Client, url: Url) -> Result<Response, reqwest::Error >{
-    client.get(url).send().await
+/// Perform an asynchrnonous GET request to the specified URL, with the given headers in addition to the client's default ones
+pub async fn get_url(client: &Client, url: Url, headers: Option<&HeaderMap>) -> Result<Response, reqwest::Error >{
+    match headers {
+        Some(headers) => client.get(url).headers(headers.clone()).send().await,
+        None => client.get(url).send().await
+    }
+}
+
+/// Decode a response body as text, using the charset of its content type or UTF-8
+pub fn decode_body(content_type: &str, body: &[u8]) -> String {
+    let encoding = content_type
+        .split(';')
+        .filter_map(|param| param.trim().split_once('='))
+        .find(|(name, _)| name.eq_ignore_ascii_case("charset"))
+        .and_then(|(_, charset)| Encoding::for_label(charset.trim_matches('"').as_bytes()))
+        .unwrap_or(UTF_8);
+
+    encoding.decode(body).0.into_owned()
+}
+
+/// Returns the size of the headers in bytes, as they would be sent over HTTP/1.1
+pub fn response_header_size(headers: &HeaderMap<HeaderValue>) -> usize {
+    // Each header is sent as "name: value\r\n"
+    headers.iter().map(|(name, value)| name.as_str().len() + value.len() + 4).sum()
+}
+
+/// The version of HTTP used for a response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum HttpVersion {
+    Http09,
+    Http10,
+    #[default]
+    Http11,
+    Http2,
+    Http3
+}
+
+impl HttpVersion {
+    /// Returns whether the version predates HTTP/2 and should be upgraded
+    pub fn needs_upgrade(&self) -> bool {
+        *self < HttpVersion::Http2
+    }
+}
+
+impl From<Version> for HttpVersion {
+    fn from(version: Version) -> Self {
+        match version {
+            Version::HTTP_09 => HttpVersion::Http09,
+            Version::HTTP_10 => HttpVersion::Http10,
+            Version::HTTP_2 => HttpVersion::Http2,
+            Version::HTTP_3 => HttpVersion::Http3,
+            _ => HttpVersion::Http11
+        }
+    }
+}
+
+impl fmt::Display for HttpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http09 => write!(f, "HTTP/0.9"),
+            Self::Http10 => write!(f, "HTTP/1.0"),
+            Self::Http11 => write!(f, "HTTP/1.1"),
+            Self::Http2 => write!(f, "HTTP/2"),
+            Self::Http3 => write!(f, "HTTP/3")
+        }
+    }
+}
+
+impl FromStr for HttpVersion {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "HTTP/0.9" => Ok(Self::Http09),
+            "HTTP/1.0" => Ok(Self::Http10),
+            "HTTP/1.1" => Ok(Self::Http11),
+            "HTTP/2" => Ok(Self::Http2),
+            "HTTP/3" => Ok(Self::Http3),
+            _ => Err(())
+        }
+    }
+}
+
+/// The version of TLS used for a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, ValueEnum)]
+pub enum TlsVersion {
+    Tls10,
+    Tls11,
+    Tls12,
+    Tls13 // Not supported as a minimum version by the native TLS backend
+}
+
+impl From<TlsVersion> for tls::Version {
+    fn from(version: TlsVersion) -> Self {
+        match version {
+            TlsVersion::Tls10 => tls::Version::TLS_1_0,
+            TlsVersion::Tls11 => tls::Version::TLS_1_1,
+            TlsVersion::Tls12 => tls::Version::TLS_1_2,
+            TlsVersion::Tls13 => tls::Version::TLS_1_3
+        }
+    }
+}
+
+impl fmt::Display for TlsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tls10 => write!(f, "TLS 1.0"),
+            Self::Tls11 => write!(f, "TLS 1.1"),
+            Self::Tls12 => write!(f, "TLS 1.2"),
+            Self::Tls13 => write!(f, "TLS 1.3")
+        }
+    }
+}
+
+impl FromStr for TlsVersion {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "TLS 1.0" => Ok(Self::Tls10),
+            "TLS 1.1" => Ok(Self::Tls11),
+            "TLS 1.2" => Ok(Self::Tls12),
+            "TLS 1.3" => Ok(Self::Tls13),
+            _ => Err(())
+        }
+    }
+}
+
+/// Returns whether a request failed because the server only supports an older TLS version than allowed
+pub fn is_tls_version_error(error: &reqwest::Error) -> bool {
+    if !error.is_connect() { return false; }
+
+    let mut source: Option<&dyn Error> = error.source();
+    while let Some(error) = source {
+        let message = error.to_string().to_lowercase();
+        if message.contains("protocol version") || message.contains("unsupported protocol") {
+            return true;
+        }
+
+        source = error.source();
+    }
+
+    false
+}
+
+/// How long to wait between the attempts of a failed request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryBackoff {
+    Fixed(Duration),                               // The same delay before every retry
+    Exponential { base: Duration, max: Duration }  // A delay doubling with each retry from the base, up to the maximum
 }
 
-/// Obtain the headers of the response to a GET request
-pub async fn get_url_response_headers(client: &Client, url: Url) -> Result<HeaderMap<HeaderValue>, reqwest::Error> {
-    match (client.head(url).send().await) {
-        Ok(response) => {
-            Ok(response.headers().to_owned())
-        },
-        Err(error) => Err(error)
+impl RetryBackoff {
+    /// Returns the delay before the retry following the given number of failed attempts
+    pub fn delay(&self, failed_attempts: usize) -> Duration {
+        match *self {
+            Self::Fixed(delay) => delay,
+            Self::Exponential { base, max } => {
+                let factor = 2u32.saturating_pow(u32::try_from(failed_attempts.saturating_sub(1)).unwrap_or(u32::MAX));
+                base.saturating_mul(factor).min(max)
+            }
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Which failed requests are retried, how often and after how long.
+/// Network errors and responses with the listed status codes are retried, waiting as long as a Retry-After header asks if there is one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: usize,        // The number of times a request is sent at most, including the first time
+    pub retry_on: Vec<StatusCode>,  // The status codes of responses retried
+    pub backoff: RetryBackoff
+}
+
+impl RetryPolicy {
+    /// Returns how long to wait before retrying a request which has failed the given number of times, if it should be retried.
+    /// A request without a status code failed to be sent or answered, while the Retry-After header is used if the response had one.
+    pub fn retry_delay(&self, failed_attempts: usize, status_code: Option<StatusCode>, retry_after: Option<&HeaderValue>) -> Option<Duration> {
+        if failed_attempts >= self.max_attempts { return None; }
+        if status_code.is_some_and(|status_code| !self.retry_on.contains(&status_code)) { return None; }
+
+        Some(retry_after.and_then(parse_retry_after).unwrap_or_else(|| self.backoff.delay(failed_attempts)))
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Sends requests up to 4 times, retrying 429, 500, 502, 503 and 504 responses 1, 2 and then 4 seconds later
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            retry_on: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT
+            ],
+            backoff: RetryBackoff::Exponential { base: Duration::from_secs(1), max: Duration::from_secs(60) }
+        }
+    }
+}
+
+/// Parse a Retry-After header, given as a number of seconds or the HTTP date to retry at
+pub fn parse_retry_after(retry_after: &HeaderValue) -> Option<Duration> {
+    let retry_after = retry_after.to_str().ok()?.trim();
+
+    match retry_after.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => {
+            let retry_at = httpdate::parse_http_date(retry_after).ok()?;
+            Some(retry_at.duration_since(SystemTime::now()).unwrap_or_default())
+        }
+    }
+}
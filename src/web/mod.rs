@@ -1,2 +1,4 @@
 pub mod http;
-pub mod host;
\ No newline at end of file
+pub mod host;
+pub mod url_relation;
+pub mod user_agent;
\ No newline at end of file
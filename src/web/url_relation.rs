@@ -0,0 +1,41 @@
+use url::Url;
+
+use super::host::{Host, HostRelationship};
+
+/// The relationship between two URLs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UrlRelation {
+    SamePage,              // The URLs differ at most in their fragment
+    SameHost,              // The URLs are different pages on the same host
+    Subdomain,             // The host of one URL is a subdomain of the other's
+    SameRegistrableDomain, // The hosts are different subdomains of the same domain
+    Unrelated,             // The URLs are unrelated
+}
+
+/// Returns the relationship between two URLs
+pub fn compare_urls(url1: &Url, url2: &Url) -> UrlRelation {
+    let (Some(host1), Some(host2)) = (url1.host(), url2.host()) else { return UrlRelation::Unrelated; };
+    let (Ok(host1), Ok(host2)) = (Host::try_from(host1), Host::try_from(host2)) else { return UrlRelation::Unrelated; };
+
+    // IPv4-mapped IPv6 addresses are the same host as their IPv4 addresses
+    if Host::host_relationship(&host1, &host2) == HostRelationship::Same {
+        if url1[..url::Position::AfterQuery] == url2[..url::Position::AfterQuery] {
+            return UrlRelation::SamePage;
+        }
+
+        return UrlRelation::SameHost;
+    }
+
+    match (&host1, &host2) {
+        (Host::Domain(domain_name1), Host::Domain(domain_name2)) => {
+            if domain_name1.is_subdomain_of(domain_name2) || domain_name1.is_superdomain_of(domain_name2) {
+                UrlRelation::Subdomain
+            } else if domain_name1.domain().eq(domain_name2.domain()) && domain_name1.tld().eq(domain_name2.tld()) {
+                UrlRelation::SameRegistrableDomain
+            } else {
+                UrlRelation::Unrelated
+            }
+        },
+        _ => UrlRelation::Unrelated
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// The user agent of a web browser, for crawling sites which block other user agents
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BrowserUA {
+    Chrome { version: String },  // Chrome on Windows, e.g. version 120.0
+    Firefox { version: String }, // Firefox on Windows, e.g. version 121.0
+    Safari { version: String },  // Safari on macOS, e.g. version 17.2
+    Edge { version: String },    // Edge on Windows, e.g. version 120.0
+}
+
+impl fmt::Display for BrowserUA {
+    /// Formats the full user agent string the browser sends, including its platform and engine
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Chrome { version } => write!(
+                f,
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{}.0.0 Safari/537.36",
+                major_version(version)
+            ),
+            Self::Firefox { version } => write!(
+                f,
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:{}) Gecko/20100101 Firefox/{}",
+                version, version
+            ),
+            Self::Safari { version } => write!(
+                f,
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/{} Safari/605.1.15",
+                version
+            ),
+            Self::Edge { version } => write!(
+                f,
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{}.0.0 Safari/537.36 Edg/{}.0.0",
+                major_version(version), major_version(version)
+            )
+        }
+    }
+}
+
+/// Returns the major part of a browser version, since Chromium-based browsers report the rest as zeros
+fn major_version(version: &str) -> &str {
+    version.split('.').next().unwrap_or(version)
+}
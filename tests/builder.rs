@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use cherna_vdovitsa::crawler::{crawl_target::CrawlTarget, crawler_builder::CrawlerBuilder, CrawlerError};
+
+#[test]
+fn builder_options_override_the_defaults() {
+    let builder = CrawlerBuilder::new()
+        .add_target("example.com".parse::<CrawlTarget>().unwrap())
+        .max_concurrent_tasks(8)
+        .request_timeout(Duration::from_secs(5))
+        .user_agent("test-agent/1.0")
+        .follow_redirects(false);
+
+    let config = builder.config();
+    assert_eq!(config.initial_targets.len(), 1);
+    assert_eq!(config.max_concurrent_tasks, 8);
+    assert_eq!(config.request_timeout, Some(Duration::from_secs(5)));
+    assert_eq!(config.user_agent, "test-agent/1.0");
+    assert!(!config.follow_redirects);
+
+    assert!(builder.build().is_ok());
+}
+
+#[test]
+fn build_fails_on_invalid_configurations() {
+    let error = CrawlerBuilder::new().max_concurrent_tasks(0).build().err().unwrap();
+    assert!(matches!(error, CrawlerError::InvalidConfig(_)), "{}", error);
+
+    let error = CrawlerBuilder::new()
+        .link_selectors(vec![(String::from("a[[href]]"), String::from("href"))])
+        .build()
+        .err()
+        .unwrap();
+    assert!(matches!(error, CrawlerError::InvalidSelector { .. }), "{}", error);
+}
+
+#[test]
+fn channel_buffers_grow_with_the_concurrent_tasks_unless_configured() {
+    let builder = CrawlerBuilder::new().add_target("example.com".parse::<CrawlTarget>().unwrap()).max_concurrent_tasks(256);
+    assert_eq!(builder.config().link_channel_buffer_size(), 256);
+    assert_eq!(builder.config().target_channel_buffer_size(), 256);
+    assert!(builder.build().is_ok());
+
+    let builder = CrawlerBuilder::new().max_concurrent_tasks(8);
+    assert_eq!(builder.config().link_channel_buffer_size(), 64);
+    assert_eq!(builder.config().target_channel_buffer_size(), 64);
+
+    // Buffers configured smaller than the concurrent tasks keep them waiting on each other
+    let error = CrawlerBuilder::new()
+        .add_target("example.com".parse::<CrawlTarget>().unwrap())
+        .max_concurrent_tasks(256)
+        .configure(|config| config.target_channel_buffer = Some(64))
+        .build()
+        .err()
+        .unwrap();
+    assert!(matches!(error, CrawlerError::InvalidConfig(_)), "{}", error);
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use cherna_vdovitsa::crawler::{crawl_event::CrawlEvent, crawler_builder::CrawlerBuilder, url_transform::{self, QueryDeduplication}};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn cancelling_mid_crawl_returns_the_partial_report() {
+    let slow = MockResponse::html("<p>Slow</p>").with_delay(Duration::from_secs(30));
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/fast">Fast</a> <a href="/slow-1">Slow</a> <a href="/slow-2">Slow</a>"#)),
+        ("/fast", MockResponse::html("<p>Fast</p>")),
+        ("/slow-1", slow.clone()),
+        ("/slow-2", slow)
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = Arc::new(CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap());
+    let mut events = crawler.subscribe();
+    let cancel_token = crawler.cancel_token();
+
+    let crawl = tokio::spawn({
+        let crawler = Arc::clone(&crawler);
+        async move { crawler.crawl().await }
+    });
+
+    // Cancel once the fast pages are done, while the slow ones are still being answered
+    let mut crawled = Vec::new();
+    while crawled.len() < 2 {
+        if let Ok(CrawlEvent::UrlCrawled(url)) = events.recv().await { crawled.push(url); }
+    }
+    cancel_token.cancel();
+
+    let report = tokio::time::timeout(Duration::from_secs(5), crawl)
+        .await
+        .expect("the cancelled crawl didn't finish")
+        .unwrap()
+        .unwrap();
+
+    assert!(report.cancelled);
+    let discovered_urls: Vec<&String> = report.discovered_urls.values().flatten().collect();
+    for url in crawled {
+        let dedup_key = url_transform::dedup_key(QueryDeduplication::default(), &[], url.as_str());
+        assert!(discovered_urls.contains(&&dedup_key), "{} isn't in the report: {:?}", url, discovered_urls);
+    }
+}
+
+#[tokio::test]
+async fn cancelling_before_the_crawl_starts_does_not_deadlock() {
+    let server = MockServer::start(vec![("/", MockResponse::html("<p>Never crawled</p>"))]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap();
+    crawler.cancel_token().cancel();
+
+    let report = tokio::time::timeout(Duration::from_secs(5), crawler.crawl())
+        .await
+        .expect("the cancelled crawl didn't finish")
+        .unwrap();
+    assert!(report.cancelled);
+}
@@ -0,0 +1,330 @@
+//! A minimal HTTP/1.1 server for the integration tests, serving fixed responses and recording the requests it receives
+#![allow(dead_code)]
+
+use std::{collections::HashMap, net::SocketAddr, sync::{atomic::{AtomicUsize, Ordering}, Arc, Mutex}, time::Duration};
+
+use cherna_vdovitsa::crawler::{
+    crawl_result::CrawlResult,
+    crawl_target::CrawlTarget,
+    crawler_config::CrawlerConfig,
+    observer::CrawlObserver,
+    skip_reason::SkipReason,
+    CrawlerError,
+};
+use tempfile::TempDir;
+use tokio::{io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt}, net::TcpListener};
+use url::Url;
+
+/// A response the server sends for a path
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub delay: Duration // How long the server waits before responding
+}
+
+impl MockResponse {
+    /// A 200 response with an HTML body
+    pub fn html(body: &str) -> MockResponse {
+        MockResponse::new(200, body.as_bytes()).with_header("Content-Type", "text/html; charset=utf-8")
+    }
+
+    /// An empty response with the status code
+    pub fn status(status: u16) -> MockResponse {
+        MockResponse::new(status, b"")
+    }
+
+    /// A 301 response redirecting to the location
+    pub fn redirect(location: &str) -> MockResponse {
+        MockResponse::status(301).with_header("Location", location)
+    }
+
+    pub fn new(status: u16, body: &[u8]) -> MockResponse {
+        MockResponse { status, headers: Vec::new(), body: body.to_vec(), delay: Duration::ZERO }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> MockResponse {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> MockResponse {
+        self.delay = delay;
+        self
+    }
+}
+
+/// A request the server received
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String, // With the query, e.g. "/search?q=1"
+    pub headers: Vec<(String, String)>
+}
+
+impl MockRequest {
+    /// Returns the value of a header, comparing names case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(header, _)| header.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+}
+
+/// A server on a local port answering each path with its response, and any other path with a 404
+pub struct MockServer {
+    address: SocketAddr,
+    scheme: &'static str, // https if the server only accepts TLS connections
+    routes: Arc<Mutex<HashMap<String, MockResponse>>>,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+    concurrency: Arc<Concurrency>
+}
+
+/// The number of requests being answered at once, and the most there have been
+#[derive(Debug, Default)]
+struct Concurrency {
+    current: AtomicUsize,
+    max: AtomicUsize
+}
+
+impl MockServer {
+    pub async fn start(routes: Vec<(&str, MockResponse)>) -> MockServer {
+        Self::start_with(routes, None).await
+    }
+
+    /// Start a server only accepting TLS connections, with a self-signed certificate for 127.0.0.1 which no client trusts
+    pub async fn start_tls(routes: Vec<(&str, MockResponse)>) -> MockServer {
+        Self::start_with(routes, Some(self_signed_acceptor())).await
+    }
+
+    async fn start_with(routes: Vec<(&str, MockResponse)>, tls_acceptor: Option<tokio_native_tls::TlsAcceptor>) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let routes: HashMap<String, MockResponse> = routes.into_iter().map(|(path, response)| (path.to_owned(), response)).collect();
+        let routes = Arc::new(Mutex::new(routes));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let concurrency = Arc::new(Concurrency::default());
+
+        let scheme = if tls_acceptor.is_some() { "https" } else { "http" };
+        let (server_routes, server_requests, server_concurrency) = (Arc::clone(&routes), Arc::clone(&requests), Arc::clone(&concurrency));
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (routes, requests, concurrency) = (Arc::clone(&server_routes), Arc::clone(&server_requests), Arc::clone(&server_concurrency));
+
+                match tls_acceptor.clone() {
+                    // Connections whose client rejects the certificate are closed during the handshake
+                    Some(tls_acceptor) => {
+                        tokio::spawn(async move {
+                            if let Ok(stream) = tls_acceptor.accept(stream).await {
+                                Self::serve(stream, routes, requests, concurrency).await;
+                            }
+                        });
+                    }
+                    None => { tokio::spawn(Self::serve(stream, routes, requests, concurrency)); }
+                }
+            }
+        });
+
+        MockServer { address, scheme, routes, requests, concurrency }
+    }
+
+    /// Answer the path with the response from now on, e.g. with a page linking to the server's own URL
+    pub fn set_route(&self, path: &str, response: MockResponse) {
+        self.routes.lock().unwrap().insert(path.to_owned(), response);
+    }
+
+    /// Returns the URL of a path on the server, e.g. http://127.0.0.1:8080/page
+    pub fn url(&self, path: &str) -> Url {
+        Url::parse(&format!("{}://{}{}", self.scheme, self.address, path)).unwrap()
+    }
+
+    /// Returns the requests received so far, in order
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Returns the most requests the server has answered at once
+    pub fn max_concurrent_requests(&self) -> usize {
+        self.concurrency.max.load(Ordering::SeqCst)
+    }
+
+    /// Returns the requests received so far as "METHOD /path", in order
+    pub fn request_lines(&self) -> Vec<String> {
+        self.requests().iter().map(|request| format!("{} {}", request.method, request.path)).collect()
+    }
+
+    /// Answer the requests of a connection until it's closed
+    async fn serve(mut stream: impl AsyncRead + AsyncWrite + Unpin, routes: Arc<Mutex<HashMap<String, MockResponse>>>, requests: Arc<Mutex<Vec<MockRequest>>>, concurrency: Arc<Concurrency>) {
+        let mut buffer: Vec<u8> = Vec::new();
+
+        loop {
+            // Read until the end of the request's headers
+            let header_end = loop {
+                if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") { break position + 4; }
+
+                let mut chunk = [0; 4096];
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(read) => buffer.extend_from_slice(&chunk[..read])
+                }
+            };
+
+            let head = String::from_utf8_lossy(&buffer[..header_end]).into_owned();
+            let mut lines = head.split("\r\n");
+            let mut request_line = lines.next().unwrap_or_default().split(' ');
+            let method = request_line.next().unwrap_or_default().to_owned();
+            let path = request_line.next().unwrap_or_default().to_owned();
+            let headers: Vec<(String, String)> = lines
+                .filter_map(|line| line.split_once(':'))
+                .map(|(name, value)| (name.trim().to_owned(), value.trim().to_owned()))
+                .collect();
+
+            // Skip the request's body
+            let content_length: usize = headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, value)| value.parse().ok())
+                .unwrap_or_default();
+            while buffer.len() < header_end + content_length {
+                let mut chunk = [0; 4096];
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(read) => buffer.extend_from_slice(&chunk[..read])
+                }
+            }
+            buffer.drain(..header_end + content_length);
+
+            let response = routes.lock().unwrap().get(&path).cloned().unwrap_or_else(|| MockResponse::status(404));
+            requests.lock().unwrap().push(MockRequest { method: method.clone(), path, headers });
+
+            let current = concurrency.current.fetch_add(1, Ordering::SeqCst) + 1;
+            concurrency.max.fetch_max(current, Ordering::SeqCst);
+            tokio::time::sleep(response.delay).await;
+
+            let mut message = format!("HTTP/1.1 {} Mock\r\nContent-Length: {}\r\n", response.status, response.body.len());
+            for (name, value) in &response.headers {
+                message.push_str(&format!("{}: {}\r\n", name, value));
+            }
+            message.push_str("\r\n");
+
+            let mut message = message.into_bytes();
+            if method != "HEAD" { message.extend_from_slice(&response.body); }
+            let written = stream.write_all(&message).await;
+            concurrency.current.fetch_sub(1, Ordering::SeqCst);
+            if written.is_err() { return; }
+        }
+    }
+}
+
+/// Build a TLS acceptor with a freshly generated self-signed certificate for 127.0.0.1
+fn self_signed_acceptor() -> tokio_native_tls::TlsAcceptor {
+    use openssl::{asn1::Asn1Time, bn::BigNum, hash::MessageDigest, pkey::PKey, rsa::Rsa, x509::{extension::SubjectAlternativeName, X509NameBuilder, X509}};
+
+    let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_text("CN", "127.0.0.1").unwrap();
+    let name = name.build();
+
+    let mut certificate = X509::builder().unwrap();
+    certificate.set_version(2).unwrap();
+    certificate.set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap()).unwrap();
+    certificate.set_subject_name(&name).unwrap();
+    certificate.set_issuer_name(&name).unwrap();
+    certificate.set_pubkey(&key).unwrap();
+    certificate.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+    certificate.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+    let alternative_names = SubjectAlternativeName::new().ip("127.0.0.1").build(&certificate.x509v3_context(None, None)).unwrap();
+    certificate.append_extension(alternative_names).unwrap();
+    certificate.sign(&key, MessageDigest::sha256()).unwrap();
+    let certificate = certificate.build();
+
+    let identity = native_tls::Identity::from_pkcs8(&certificate.to_pem().unwrap(), &key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+    tokio_native_tls::TlsAcceptor::from(native_tls::TlsAcceptor::new(identity).unwrap())
+}
+
+/// A configuration for crawling mock servers, which are on the loopback address, saving to a database in the directory
+pub fn test_config(db_dir: &TempDir) -> CrawlerConfig {
+    CrawlerConfig {
+        db_path: db_dir.path().join("crawl.db"),
+        allow_private_hosts: true,
+        ..Default::default()
+    }
+}
+
+/// A crawl observer which records every request, for asserting on what the crawler did
+#[derive(Debug, Default)]
+pub struct RecordingObserver {
+    requested_urls: Mutex<Vec<Url>>,
+    results: Mutex<Vec<CrawlResult>>,
+    skipped_urls: Mutex<Vec<(Url, SkipReason)>>,
+    skipped_targets: Mutex<Vec<CrawlTarget>>
+}
+
+impl RecordingObserver {
+    pub fn new() -> RecordingObserver {
+        RecordingObserver::default()
+    }
+
+    /// Returns the URLs that were requested, in order
+    pub fn requested_urls(&self) -> Vec<Url> {
+        match self.requested_urls.lock() {
+            Ok(requested_urls) => requested_urls.clone(),
+            Err(error) => error.into_inner().clone()
+        }
+    }
+
+    /// Returns the results of the processed pages, in order
+    pub fn results(&self) -> Vec<CrawlResult> {
+        match self.results.lock() {
+            Ok(results) => results.clone(),
+            Err(error) => error.into_inner().clone()
+        }
+    }
+
+    /// Returns the URLs that were skipped and why, in order
+    pub fn skipped_urls(&self) -> Vec<(Url, SkipReason)> {
+        match self.skipped_urls.lock() {
+            Ok(skipped_urls) => skipped_urls.clone(),
+            Err(error) => error.into_inner().clone()
+        }
+    }
+
+    /// Returns the targets that were skipped, in order
+    pub fn skipped_targets(&self) -> Vec<CrawlTarget> {
+        match self.skipped_targets.lock() {
+            Ok(skipped_targets) => skipped_targets.clone(),
+            Err(error) => error.into_inner().clone()
+        }
+    }
+}
+
+impl CrawlObserver for RecordingObserver {
+    fn on_before_request(&self, url: &Url) {
+        match self.requested_urls.lock() {
+            Ok(mut requested_urls) => requested_urls.push(url.clone()),
+            Err(error) => error.into_inner().push(url.clone())
+        }
+    }
+
+    fn on_after_request(&self, result: &CrawlResult) {
+        match self.results.lock() {
+            Ok(mut results) => results.push(result.clone()),
+            Err(error) => error.into_inner().push(result.clone())
+        }
+    }
+
+    fn on_page_skipped(&self, url: &Url, reason: &SkipReason) {
+        match self.skipped_urls.lock() {
+            Ok(mut skipped_urls) => skipped_urls.push((url.clone(), reason.clone())),
+            Err(error) => error.into_inner().push((url.clone(), reason.clone()))
+        }
+    }
+
+    fn on_target_skipped(&self, target: &CrawlTarget, _error: &CrawlerError) {
+        match self.skipped_targets.lock() {
+            Ok(mut skipped_targets) => skipped_targets.push(target.clone()),
+            Err(error) => error.into_inner().push(target.clone())
+        }
+    }
+}
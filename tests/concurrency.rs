@@ -0,0 +1,63 @@
+mod common;
+
+use std::time::Duration;
+
+use cherna_vdovitsa::crawler::crawler_builder::CrawlerBuilder;
+use cherna_vdovitsa::crawler::crawler_config::CrawlerConfig;
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+async fn linking_server() -> MockServer {
+    let delay = Duration::from_millis(100);
+
+    MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/a">A</a> <a href="/b">B</a> <a href="/c">C</a>"#)),
+        ("/a", MockResponse::html("<p>A</p>").with_delay(delay)),
+        ("/b", MockResponse::html("<p>B</p>").with_delay(delay)),
+        ("/c", MockResponse::html("<p>C</p>").with_delay(delay))
+    ]).await
+}
+
+#[tokio::test]
+async fn pages_are_requested_one_at_a_time_with_max_concurrent_tasks_1() {
+    let server = linking_server().await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .seed_url(server.url("/"))
+        .max_concurrent_tasks(1)
+        .build()
+        .unwrap();
+    crawler.crawl().await.unwrap();
+
+    // The seed page is requested first, then each of its links after the previous one was answered
+    let pages: Vec<String> = server.request_lines().into_iter().filter(|line| line != "GET /robots.txt").collect();
+    assert_eq!(pages.first().map(String::as_str), Some("GET /"));
+    assert_eq!(pages.len(), 4, "{:?}", pages);
+    assert_eq!(server.max_concurrent_requests(), 1);
+}
+
+#[tokio::test]
+async fn pages_are_requested_concurrently_by_default() {
+    let server = linking_server().await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap();
+    crawler.crawl().await.unwrap();
+
+    assert!(server.max_concurrent_requests() > 1);
+}
+
+#[tokio::test]
+async fn pages_of_a_host_are_requested_one_at_a_time_with_max_connections_per_host_1() {
+    let server = linking_server().await;
+
+    let db_dir = TempDir::new().unwrap();
+    let config = CrawlerConfig { max_connections_per_host: Some(1), ..test_config(&db_dir) };
+    let crawler = CrawlerBuilder::from_config(config).seed_url(server.url("/")).build().unwrap();
+    crawler.crawl().await.unwrap();
+
+    let pages: Vec<String> = server.request_lines().into_iter().filter(|line| line != "GET /robots.txt").collect();
+    assert_eq!(pages.len(), 4, "{:?}", pages);
+    assert_eq!(server.max_concurrent_requests(), 1);
+}
@@ -0,0 +1,73 @@
+use std::fs;
+
+use cherna_vdovitsa::crawler::{crawl_target::{CrawlTarget, TargetConfig}, Crawler, CrawlerError};
+use tempfile::TempDir;
+
+// The example of `Crawler::from_config_file`'s documentation
+const TARGETS: &str = r#"[
+    { "host": "example.com" },
+    {
+        "host": "docs.example.org",
+        "scheme": "Https",
+        "path_prefix": "/api/",
+        "auth": { "basic": { "username": "crawler", "password": "secret" } },
+        "max_depth": 5,
+        "max_pages": 1000,
+        "priority": 10
+    },
+    { "host": "192.168.1.1", "auth": { "bearer": "token" } }
+]"#;
+
+#[test]
+fn target_configs_round_trip_through_json_and_crawl_targets() {
+    let target_configs: Vec<TargetConfig> = serde_json::from_str(TARGETS).unwrap();
+    assert_eq!(target_configs.len(), 3);
+
+    for target_config in target_configs {
+        let json = serde_json::to_string(&target_config).unwrap();
+        assert_eq!(serde_json::from_str::<TargetConfig>(&json).unwrap(), target_config);
+
+        let target = CrawlTarget::try_from(target_config.clone()).unwrap();
+        assert_eq!(TargetConfig::from(&target), target_config);
+    }
+}
+
+#[tokio::test]
+async fn a_config_file_creates_a_crawler_of_its_targets() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("targets.json");
+    fs::write(&path, TARGETS).unwrap();
+
+    let crawler = Crawler::from_config_file(&path).unwrap();
+
+    // The checkpoint has the crawler's targets
+    let checkpoint_path = dir.path().join("checkpoint.json");
+    crawler.save_checkpoint(&checkpoint_path).await.unwrap();
+    let checkpoint: serde_json::Value = serde_json::from_slice(&fs::read(&checkpoint_path).unwrap()).unwrap();
+    let targets: Vec<CrawlTarget> = serde_json::from_value(checkpoint["targets"].clone()).unwrap();
+
+    let docs = targets.iter().find(|target| target.host().to_string() == "docs.example.org").expect("the docs target");
+    assert_eq!(docs.path_prefix(), Some("/api/"));
+    assert_eq!((docs.max_depth(), docs.max_pages(), docs.priority()), (Some(5), Some(1000), 10));
+    assert_eq!(targets.len(), 3);
+}
+
+#[test]
+fn unknown_fields_fail_to_parse() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("targets.json");
+    fs::write(&path, r#"[{ "host": "example.com", "max_dpeth": 5 }]"#).unwrap();
+
+    let Err(error) = Crawler::from_config_file(&path) else { panic!("the unknown field was accepted"); };
+    assert!(matches!(&error, CrawlerError::ConfigParse { path: error_path, .. } if *error_path == path), "{:?}", error);
+    assert!(error.to_string().contains("max_dpeth"), "{}", error);
+}
+
+#[test]
+fn invalid_json_fails_to_parse() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("targets.json");
+    fs::write(&path, r#"[{ "host": "example.com" "#).unwrap();
+
+    assert!(matches!(Crawler::from_config_file(&path), Err(CrawlerError::ConfigParse { .. })));
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use cherna_vdovitsa::crawler::{crawl_error::CrawlErrorKind, crawler_builder::CrawlerBuilder};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn failed_pages_are_in_the_report_errors() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"
+            <a href="/broken">Broken</a>
+            <a href="/logo.png">Logo</a>
+            <a href="/working">Working</a>
+        "#)),
+        ("/broken", MockResponse::status(404)),
+        ("/logo.png", MockResponse::new(200, b"\x89PNG").with_header("Content-Type", "image/png")),
+        ("/working", MockResponse::html("<p>Working</p>"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap();
+    let report = crawler.crawl().await.unwrap();
+
+    let kind_of = |path: &str| report.errors.iter().find(|error| error.url == server.url(path)).map(|error| error.kind);
+    assert_eq!(kind_of("/broken"), Some(CrawlErrorKind::HttpError), "{:?}", report.errors);
+    assert_eq!(kind_of("/logo.png"), Some(CrawlErrorKind::NonHtmlContent), "{:?}", report.errors);
+    assert_eq!(kind_of("/working"), None, "{:?}", report.errors);
+}
+
+#[tokio::test]
+async fn a_crawl_without_failures_has_no_errors() {
+    let server = MockServer::start(vec![("/", MockResponse::html("<p>Home</p>"))]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap();
+    let report = crawler.crawl().await.unwrap();
+
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+}
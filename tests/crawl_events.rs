@@ -0,0 +1,43 @@
+mod common;
+
+use cherna_vdovitsa::{
+    crawler::{crawl_event::CrawlEvent, crawler_builder::CrawlerBuilder},
+    web::host::Host,
+};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn subscribers_receive_a_url_crawled_event_per_seed() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/linked">Linked</a>"#)),
+        ("/other", MockResponse::html("<p>Other</p>")),
+        ("/linked", MockResponse::html("<p>Linked</p>"))
+    ]).await;
+    let seed_urls = [server.url("/"), server.url("/other")];
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .seed_url(seed_urls[0].clone())
+        .seed_url(seed_urls[1].clone())
+        .configure(|config| config.event_channel_capacity = 1024)
+        .build()
+        .unwrap();
+
+    let mut events = crawler.subscribe();
+    crawler.crawl().await.unwrap();
+
+    let mut received = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        received.push(event);
+    }
+
+    for seed_url in &seed_urls {
+        assert!(received.contains(&CrawlEvent::UrlCrawled(seed_url.clone())), "{} wasn't crawled: {:?}", seed_url, received);
+    }
+    assert!(received.contains(&CrawlEvent::UrlCrawled(server.url("/linked"))));
+
+    let host = Host::Ipv4("127.0.0.1".parse().unwrap());
+    assert!(received.contains(&CrawlEvent::TargetStarted(host.clone())));
+    assert_eq!(received.last(), Some(&CrawlEvent::TargetFinished(host)));
+}
@@ -0,0 +1,39 @@
+use std::str::FromStr;
+
+use cherna_vdovitsa::{crawler::crawl_target::CrawlTarget, dns::domain_name::DomainName, web::host::Host};
+use url::Url;
+
+#[test]
+fn urls_and_bare_host_names_make_equivalent_targets() {
+    let from_url = CrawlTarget::from_str("https://example.com/path").unwrap();
+    let from_host_name = CrawlTarget::from_str("example.com").unwrap();
+    let parsed: CrawlTarget = "example.com".parse().unwrap();
+
+    assert_eq!(from_url, from_host_name);
+    assert_eq!(from_host_name, parsed);
+    assert_eq!(from_url.host(), &Host::Domain(DomainName::parse("example.com").unwrap()));
+}
+
+#[test]
+fn from_url_takes_the_host_of_the_url() {
+    let target = CrawlTarget::from_url(&Url::parse("http://192.168.1.1:8080/admin").unwrap()).unwrap();
+    assert_eq!(target.host(), &Host::Ipv4("192.168.1.1".parse().unwrap()));
+
+    let target = CrawlTarget::from_url(&Url::parse("https://[2001:db8::1]/").unwrap()).unwrap();
+    assert_eq!(target.host(), &Host::Ipv6("2001:db8::1".parse().unwrap()));
+
+    assert!(CrawlTarget::from_url(&Url::parse("mailto:someone@example.com").unwrap()).is_err());
+}
+
+#[test]
+fn invalid_targets_fail_to_parse() {
+    assert!(CrawlTarget::from_str("").is_err());
+    assert!(CrawlTarget::from_str("not a domain").is_err());
+    assert!(CrawlTarget::from_str("localhost").is_err());
+}
+
+#[test]
+fn targets_display_as_their_host() {
+    assert_eq!(CrawlTarget::from_str("https://www.example.com/path").unwrap().to_string(), "www.example.com");
+    assert_eq!(CrawlTarget::from_str("10.0.0.1").unwrap().to_string(), "10.0.0.1");
+}
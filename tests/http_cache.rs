@@ -0,0 +1,109 @@
+mod common;
+
+use std::sync::Arc;
+
+use cherna_vdovitsa::crawler::{crawl_result::CrawlResult, crawler_builder::CrawlerBuilder, http_cache::HttpCacheConfig};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+const PAGE: &str = "<p>Cached</p>";
+
+/// Crawl the server's main page through the cache, returning its result
+async fn crawl_main_page(server: &MockServer, http_cache: HttpCacheConfig) -> CrawlResult {
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .seed_url(server.url("/"))
+        .http_cache(http_cache)
+        .build()
+        .unwrap();
+
+    let mut results = Arc::new(crawler).crawl_incremental_stream(Vec::new(), false);
+    let mut main_page = None;
+    while let Some(result) = results.recv().await {
+        if result.url == server.url("/") { main_page = Some(result); }
+    }
+    main_page.unwrap()
+}
+
+/// Returns the number of requests for the server's main page
+fn main_page_requests(server: &MockServer) -> usize {
+    server.request_lines().iter().filter(|request| *request == "GET /").count()
+}
+
+#[tokio::test]
+async fn a_fresh_page_is_not_requested_again() {
+    let server = MockServer::start(vec![("/", MockResponse::html(PAGE).with_header("Cache-Control", "max-age=60"))]).await;
+    let http_cache = HttpCacheConfig::in_memory();
+
+    let first = crawl_main_page(&server, http_cache.clone()).await;
+    assert!(!first.from_http_cache);
+
+    let second = crawl_main_page(&server, http_cache).await;
+    assert!(second.from_http_cache);
+    assert_eq!(second.response_body, PAGE);
+    assert_eq!(main_page_requests(&server), 1);
+}
+
+#[tokio::test]
+async fn a_stale_page_is_revalidated_with_its_etag() {
+    let server = MockServer::start(vec![("/", MockResponse::html(PAGE).with_header("Cache-Control", "no-cache").with_header("ETag", "\"v1\""))]).await;
+    let http_cache = HttpCacheConfig::in_memory();
+
+    crawl_main_page(&server, http_cache.clone()).await;
+    server.set_route("/", MockResponse::status(304).with_header("ETag", "\"v1\""));
+
+    let second = crawl_main_page(&server, http_cache).await;
+    assert!(second.from_http_cache);
+    assert_eq!(second.status_code, 200);
+    assert_eq!(second.response_body, PAGE);
+
+    let revalidation = server.requests().into_iter().rfind(|request| request.path == "/").unwrap();
+    assert_eq!(revalidation.header("If-None-Match"), Some("\"v1\""));
+}
+
+#[tokio::test]
+async fn a_changed_page_replaces_the_cached_one() {
+    let server = MockServer::start(vec![("/", MockResponse::html(PAGE).with_header("ETag", "\"v1\""))]).await;
+    let http_cache = HttpCacheConfig::in_memory();
+
+    crawl_main_page(&server, http_cache.clone()).await;
+    server.set_route("/", MockResponse::html("<p>Changed</p>").with_header("ETag", "\"v2\""));
+
+    let second = crawl_main_page(&server, http_cache.clone()).await;
+    assert!(!second.from_http_cache);
+    assert_eq!(second.response_body, "<p>Changed</p>");
+
+    crawl_main_page(&server, http_cache).await;
+    let revalidation = server.requests().into_iter().rfind(|request| request.path == "/").unwrap();
+    assert_eq!(revalidation.header("If-None-Match"), Some("\"v2\""));
+}
+
+#[tokio::test]
+async fn the_filesystem_cache_is_kept_between_crawls() {
+    let last_modified = "Wed, 21 Oct 2015 07:28:00 GMT";
+    let server = MockServer::start(vec![("/", MockResponse::html(PAGE).with_header("Last-Modified", last_modified))]).await;
+    let cache_dir = TempDir::new().unwrap();
+
+    crawl_main_page(&server, HttpCacheConfig::filesystem(cache_dir.path())).await;
+    server.set_route("/", MockResponse::status(304));
+
+    let second = crawl_main_page(&server, HttpCacheConfig::filesystem(cache_dir.path())).await;
+    assert!(second.from_http_cache);
+    assert_eq!(second.response_body, PAGE);
+
+    let revalidation = server.requests().into_iter().rfind(|request| request.path == "/").unwrap();
+    assert_eq!(revalidation.header("If-Modified-Since"), Some(last_modified));
+}
+
+#[tokio::test]
+async fn a_no_store_page_is_not_cached() {
+    let server = MockServer::start(vec![("/", MockResponse::html(PAGE).with_header("Cache-Control", "no-store, max-age=60").with_header("ETag", "\"v1\""))]).await;
+    let http_cache = HttpCacheConfig::in_memory();
+
+    crawl_main_page(&server, http_cache.clone()).await;
+    let second = crawl_main_page(&server, http_cache).await;
+
+    assert!(!second.from_http_cache);
+    assert_eq!(main_page_requests(&server), 2);
+    assert!(server.requests().iter().all(|request| request.header("If-None-Match").is_none()));
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use cherna_vdovitsa::crawler::{crawler_config::CrawlerConfig, Crawler};
+use cherna_vdovitsa::web::host::Host;
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn library_crawl_records_the_pages_of_its_targets() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/first">First</a> <a href="/second">Second</a>"#)),
+        ("/first", MockResponse::html("<p>First</p>")),
+        ("/second", MockResponse::html("<p>Second</p>"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let config = CrawlerConfig { seed_urls: vec![server.url("/")], ..test_config(&db_dir) };
+    let report = Crawler::new(config).unwrap().crawl().await.unwrap();
+
+    let discovered_urls = &report.discovered_urls[&Host::Ipv4("127.0.0.1".parse().unwrap())];
+    assert_eq!(discovered_urls.len(), 3);
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert!(report.stats.total_requests >= 3);
+
+    let db = rusqlite::Connection::open(db_dir.path().join("crawl.db")).unwrap();
+    let targets: i64 = db.query_row("SELECT COUNT(*) FROM targets", (), |row| row.get(0)).unwrap();
+    assert_eq!(targets, 1);
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use cherna_vdovitsa::crawler::crawler_builder::CrawlerBuilder;
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn a_custom_selector_extracts_links_from_its_attribute() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"
+            <div data-href="/from-div">From a div</div>
+            <div>No link</div>
+            <a href="/from-anchor">From an anchor</a>
+        "#)),
+        ("/from-div", MockResponse::html("<p>From a div</p>")),
+        ("/from-anchor", MockResponse::html("<p>From an anchor</p>"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .seed_url(server.url("/"))
+        .link_selectors(vec![(String::from("div[data-href]"), String::from("data-href"))])
+        .build()
+        .unwrap();
+    crawler.crawl().await.unwrap();
+
+    // Only the custom selector is used, replacing the default one of anchors
+    let requests = server.request_lines();
+    assert!(requests.contains(&String::from("GET /from-div")), "{:?}", requests);
+    assert!(!requests.contains(&String::from("GET /from-anchor")), "{:?}", requests);
+}
+
+#[tokio::test]
+async fn anchors_are_followed_by_default() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"<div data-href="/from-div">From a div</div> <a href="/from-anchor">From an anchor</a>"#)),
+        ("/from-anchor", MockResponse::html("<p>From an anchor</p>"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap();
+    crawler.crawl().await.unwrap();
+
+    let requests = server.request_lines();
+    assert!(requests.contains(&String::from("GET /from-anchor")), "{:?}", requests);
+    assert!(!requests.contains(&String::from("GET /from-div")), "{:?}", requests);
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use cherna_vdovitsa::crawler::crawler_builder::CrawlerBuilder;
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn pages_two_hops_away_are_not_requested_with_max_depth_1() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/one-hop">One hop</a>"#)),
+        ("/one-hop", MockResponse::html(r#"<a href="/two-hops">Two hops</a>"#)),
+        ("/two-hops", MockResponse::html(r#"<a href="/three-hops">Three hops</a>"#))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .seed_url(server.url("/"))
+        .max_depth(1)
+        .build()
+        .unwrap();
+    crawler.crawl().await.unwrap();
+
+    let requests = server.request_lines();
+    assert!(requests.contains(&String::from("GET /")), "{:?}", requests);
+    assert!(requests.contains(&String::from("GET /one-hop")), "{:?}", requests);
+    assert!(!requests.contains(&String::from("GET /two-hops")), "{:?}", requests);
+    assert!(!requests.contains(&String::from("GET /three-hops")), "{:?}", requests);
+}
+
+#[tokio::test]
+async fn every_reachable_page_is_requested_without_max_depth() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/one-hop">One hop</a>"#)),
+        ("/one-hop", MockResponse::html(r#"<a href="/two-hops">Two hops</a>"#)),
+        ("/two-hops", MockResponse::html("<p>Two hops</p>"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap();
+    crawler.crawl().await.unwrap();
+
+    assert!(server.request_lines().contains(&String::from("GET /two-hops")));
+}
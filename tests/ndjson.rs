@@ -0,0 +1,35 @@
+mod common;
+
+use std::collections::HashSet;
+
+use cherna_vdovitsa::crawler::crawler_builder::CrawlerBuilder;
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn a_json_line_is_written_for_every_crawled_page() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/one">One</a> <a href="/two">Two</a>"#)),
+        ("/one", MockResponse::html("<p>One</p>")),
+        ("/two", MockResponse::html(r#"<a href="/">Home</a>"#))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap();
+    let mut output: Vec<u8> = Vec::new();
+    let report = crawler.crawl_to_writer(&mut output).await.unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.ends_with('\n'));
+
+    let records: Vec<serde_json::Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    for record in &records {
+        assert_eq!(record["host"], "127.0.0.1");
+        assert!(record["timestamp"].as_str().is_some_and(|timestamp| humantime::parse_rfc3339(timestamp).is_ok()), "{}", record);
+    }
+
+    let written_urls: HashSet<&str> = records.iter().map(|record| record["url"].as_str().unwrap()).collect();
+    let crawled_urls: HashSet<&str> = report.link_graph.keys().map(|url| url.as_str()).collect();
+    assert_eq!(records.len(), 3);
+    assert_eq!(written_urls, crawled_urls);
+}
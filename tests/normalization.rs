@@ -0,0 +1,30 @@
+mod common;
+
+use cherna_vdovitsa::crawler::crawler_builder::CrawlerBuilder;
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn variants_of_a_page_are_crawled_once() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html("")),
+        ("/page", MockResponse::html("<p>Page</p>")),
+        ("/page/", MockResponse::html("<p>Page</p>")),
+        ("/Page", MockResponse::html("<p>Page</p>"))
+    ]).await;
+    let root = MockResponse::html(&format!(
+        r#"<a href="/page">1</a> <a href="https://{}/page">2</a> <a href="/page/">3</a> <a href="/Page">4</a>"#,
+        server.url("/").authority()
+    ));
+    server.set_route("/", root);
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap();
+    let report = crawler.crawl().await.unwrap();
+
+    let page_requests = server.request_lines().into_iter().filter(|line| line.to_lowercase().trim_end_matches('/') == "get /page").count();
+    assert_eq!(page_requests, 1, "{:?}", server.request_lines());
+
+    let discovered_urls: Vec<&String> = report.discovered_urls.values().flatten().collect();
+    assert_eq!(discovered_urls.len(), 2, "{:?}", discovered_urls);
+}
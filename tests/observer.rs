@@ -0,0 +1,41 @@
+mod common;
+
+use std::sync::Arc;
+
+use cherna_vdovitsa::crawler::{crawler_builder::CrawlerBuilder, crawler_config::CrawlerConfig, skip_reason::SkipReason};
+use common::{test_config, MockResponse, MockServer, RecordingObserver};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn the_observer_is_notified_of_every_request_and_skipped_page() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/about">About</a> <a href="/private/admin">Admin</a>"#)),
+        ("/about", MockResponse::html("<p>About</p>")),
+        ("/private/admin", MockResponse::html("<p>Admin</p>"))
+    ]).await;
+
+    let observer = Arc::new(RecordingObserver::new());
+    let db_dir = TempDir::new().unwrap();
+    let config = CrawlerConfig {
+        observer: Some(observer.clone()),
+        path_blocklist: vec![String::from("/private")],
+        ..test_config(&db_dir)
+    };
+    let crawler = CrawlerBuilder::from_config(config).seed_url(server.url("/")).build().unwrap();
+    crawler.crawl().await.unwrap();
+
+    // The seed page is requested before the page it links to, and each requested page has its result
+    let requested_urls = observer.requested_urls();
+    assert_eq!(requested_urls.first(), Some(&server.url("/")));
+    assert_eq!(requested_urls.len(), 2, "{:?}", requested_urls);
+    assert!(requested_urls.contains(&server.url("/about")));
+
+    let mut result_urls: Vec<_> = observer.results().into_iter().map(|result| result.url).collect();
+    result_urls.sort();
+    let mut expected_urls = requested_urls.clone();
+    expected_urls.sort();
+    assert_eq!(result_urls, expected_urls);
+
+    assert_eq!(observer.skipped_urls(), vec![(server.url("/private/admin"), SkipReason::BlockedPath)]);
+    assert!(observer.skipped_targets().is_empty());
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use std::sync::Arc;
+
+use cherna_vdovitsa::{
+    crawler::{crawl_error::CrawlErrorKind, crawler_config::CrawlerConfig, redirect::{self, CrossDomainRedirectPolicy, RedirectLog}, Crawler},
+    dns::public_resolver::PublicAddressResolver,
+    web::host::is_private_address_error,
+};
+use common::{MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn private_targets_are_refused_before_any_request() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/secret">Secret</a>"#)),
+        ("/robots.txt", MockResponse::new(200, b"User-agent: *\nAllow: /"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let config = CrawlerConfig {
+        db_path: db_dir.path().join("crawl.db"),
+        seed_urls: vec![server.url("/")],
+        ..Default::default()
+    };
+    let report = Crawler::new(config).unwrap().crawl().await.unwrap();
+
+    assert!(server.requests().is_empty(), "{:?}", server.request_lines());
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].kind, CrawlErrorKind::BlockedByPolicy);
+}
+
+#[tokio::test]
+async fn redirects_to_private_addresses_are_not_followed() {
+    let server = MockServer::start(vec![("/secret", MockResponse::html("<p>Secret</p>"))]).await;
+    let redirect_server = MockServer::start(vec![("/", MockResponse::redirect(server.url("/secret").as_str()))]).await;
+
+    let policy = redirect::redirect_policy(CrossDomainRedirectPolicy::Follow, 10, false, Arc::new(RedirectLog::default()));
+    let client = reqwest::Client::builder().redirect(policy).build().unwrap();
+
+    let error = client.get(redirect_server.url("/")).send().await.unwrap_err();
+    assert!(is_private_address_error(&error));
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn host_names_resolving_to_private_addresses_are_not_connected_to() {
+    let server = MockServer::start(vec![("/", MockResponse::html("<p>Local</p>"))]).await;
+    let client = reqwest::Client::builder()
+        .dns_resolver(Arc::new(PublicAddressResolver::new(None)))
+        .build()
+        .unwrap();
+
+    let mut url = server.url("/");
+    url.set_host(Some("localhost")).unwrap();
+
+    let error = client.get(url).send().await.unwrap_err();
+    assert!(is_private_address_error(&error));
+    assert!(server.requests().is_empty());
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use cherna_vdovitsa::crawler::{crawler_builder::CrawlerBuilder, proxy::ProxyConfig, CrawlerError};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+use url::Url;
+
+#[tokio::test]
+async fn requests_are_sent_through_the_proxy_with_its_credentials() {
+    let proxy = MockServer::start(vec![("http://example.com/", MockResponse::html("<p>Home</p>"))]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .seed_url(Url::parse("http://example.com/").unwrap())
+        .proxy(ProxyConfig { credentials: Some((String::from("user"), String::from("secret"))), ..ProxyConfig::new(proxy.url("/")) })
+        .build()
+        .unwrap();
+    crawler.crawl().await.unwrap();
+
+    let requests = proxy.requests();
+    let page_request = requests.iter().find(|request| request.path == "http://example.com/").expect("the page was requested through the proxy");
+    // "user:secret" in base64
+    assert_eq!(page_request.header("Proxy-Authorization"), Some("Basic dXNlcjpzZWNyZXQ="));
+}
+
+#[tokio::test]
+async fn exempted_hosts_are_requested_directly() {
+    let proxy = MockServer::start(Vec::new()).await;
+    let server = MockServer::start(vec![("/", MockResponse::html("<p>Home</p>"))]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .seed_url(server.url("/"))
+        .proxy(ProxyConfig { no_proxy: vec![String::from("127.0.0.1")], ..ProxyConfig::new(proxy.url("/")) })
+        .build()
+        .unwrap();
+    crawler.crawl().await.unwrap();
+
+    assert!(server.request_lines().contains(&String::from("GET /")));
+    assert!(proxy.requests().is_empty(), "{:?}", proxy.request_lines());
+}
+
+#[test]
+fn an_unusable_proxy_url_fails_the_build() {
+    let proxy = ProxyConfig::new(Url::parse("ftp://proxy.example.com").unwrap());
+
+    assert!(matches!(proxy.to_proxy(), Err(CrawlerError::InvalidConfig(_))));
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(Url::parse("http://example.com/").unwrap()).proxy(proxy).build();
+    assert!(matches!(crawler, Err(CrawlerError::InvalidConfig(message)) if message.contains("proxy")));
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use cherna_vdovitsa::crawler::{crawl_error::CrawlErrorKind, crawler_builder::CrawlerBuilder, redirect::CrossDomainRedirectPolicy};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn a_301_chain_is_recorded_in_the_report() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/old">Old</a>"#)),
+        ("/old", MockResponse::redirect("/older")),
+        ("/older", MockResponse::redirect("/new")),
+        ("/new", MockResponse::html(r#"<a href="/after">After</a>"#)),
+        ("/after", MockResponse::html(r#"<a href="/new">New</a>"#))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap();
+    let report = crawler.crawl().await.unwrap();
+
+    assert_eq!(report.redirects.get(&server.url("/old")), Some(&server.url("/new")), "{:?}", report.redirects);
+    assert_eq!(report.redirect_chains.get(&server.url("/old")), Some(&vec![server.url("/older"), server.url("/new")]), "{:?}", report.redirect_chains);
+    assert!(!report.redirects.contains_key(&server.url("/")));
+
+    // The page redirected to isn't crawled again when it's linked to, as it was crawled as the redirected page
+    let requests = server.request_lines();
+    assert!(requests.contains(&String::from("GET /after")), "{:?}", requests);
+    assert_eq!(requests.iter().filter(|request| *request == "GET /new").count(), 1, "{:?}", requests);
+}
+
+#[tokio::test]
+async fn same_domain_redirects_are_still_handled_when_blocking_cross_domain_ones() {
+    let server = MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/old">Old</a><a href="/nowhere">Nowhere</a>"#)),
+        ("/old", MockResponse::redirect("/new")),
+        ("/nowhere", MockResponse::status(302)),
+        ("/new", MockResponse::html("<p>New</p>"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .seed_url(server.url("/"))
+        .follow_redirects(false)
+        .configure(|config| config.cross_domain_redirect_policy = CrossDomainRedirectPolicy::Block)
+        .build()
+        .unwrap();
+    let report = crawler.crawl().await.unwrap();
+
+    // The unfollowed redirect is crawled as a link, and the one leading nowhere is an error rather than dropped
+    let requests = server.request_lines();
+    assert!(requests.contains(&String::from("GET /new")), "{:?}", requests);
+
+    let nowhere = report.errors.iter().find(|error| error.url == server.url("/nowhere"));
+    assert_eq!(nowhere.map(|error| error.kind), Some(CrawlErrorKind::TooManyRedirects), "{:?}", report.errors);
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use cherna_vdovitsa::crawler::{crawler_builder::CrawlerBuilder, crawler_config::CrawlerConfig};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn every_page_of_a_crawl_is_requested_once() {
+    const PAGES: usize = 5;
+
+    // The main page links to every other page, each of which links back to it
+    let links: String = (1..PAGES).map(|page| format!("<a href=\"/{}\">{}</a>", page, page)).collect();
+    let mut routes = vec![(String::from("/"), MockResponse::html(&links))];
+    routes.extend((1..PAGES).map(|page| (format!("/{}", page), MockResponse::html("<a href=\"/\">Home</a>"))));
+    let server = MockServer::start(routes.iter().map(|(path, response)| (path.as_str(), response.clone())).collect()).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let config = CrawlerConfig { use_sitemap: false, ..test_config(&db_dir) };
+    let crawler = CrawlerBuilder::from_config(config).seed_url(server.url("/")).build().unwrap();
+
+    crawler.crawl().await.unwrap();
+
+    // Besides the host's robots.txt, the server is sent exactly one GET request per page, where a HEAD request before each made it two
+    let requests = server.request_lines();
+    assert_eq!(requests.len(), PAGES + 1, "{:?}", requests);
+    assert_eq!(requests.iter().filter(|request| request.starts_with("GET /") && request.as_str() != "GET /robots.txt").count(), PAGES, "{:?}", requests);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use cherna_vdovitsa::crawler::crawler_builder::CrawlerBuilder;
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+const ROBOTS_TXT: &str = "User-agent: *\nDisallow: /private/\nAllow: /private/open\n";
+
+async fn start_server() -> MockServer {
+    let server = MockServer::start(vec![
+        ("/robots.txt", MockResponse::new(200, ROBOTS_TXT.as_bytes()).with_header("Content-Type", "text/plain")),
+        ("/public", MockResponse::html("<p>Public</p>")),
+        ("/private/listed", MockResponse::html("<p>Listed</p>"))
+    ]).await;
+
+    let sitemap = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"><url><loc>{}</loc></url><url><loc>{}</loc></url></urlset>"#,
+        server.url("/public"),
+        server.url("/private/listed")
+    );
+    server.set_route("/sitemap.xml", MockResponse::new(200, sitemap.as_bytes()).with_header("Content-Type", "application/xml"));
+    server
+}
+
+#[tokio::test]
+async fn urls_skipped_because_of_robots_txt_are_reported() {
+    let server = start_server().await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).respect_robots_txt(true).build().unwrap();
+    let results = crawler.crawl_sitemap_only(server.url("/sitemap.xml")).await;
+    let report = crawler.export_robots_compliance_report(&results);
+
+    assert_eq!(report.targets.len(), 1);
+    assert_eq!(report.targets[0].robots_txt, ROBOTS_TXT);
+    assert_eq!(report.targets[0].applied_rules, vec![String::from("Disallow: /private/"), String::from("Allow: /private/open")]);
+
+    let listed = server.url("/private/listed").to_string();
+    assert_eq!(report.skipped_urls.iter().map(|url| url.url.as_str()).collect::<Vec<&str>>(), vec![listed.as_str()]);
+    assert_eq!(report.skipped_urls[0].rule.as_deref(), Some("Disallow: /private/"));
+    assert_eq!(report.conflicts, report.skipped_urls);
+    assert!(report.disallowed_crawled.is_empty());
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    assert_eq!(json["conflicts"][0]["url"], listed.as_str());
+
+    let csv = report.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("kind,host,url,rule"));
+    assert!(csv.contains("rule,127.0.0.1,,Disallow: /private/\n"), "{}", csv);
+    assert!(csv.contains(&format!("skipped,127.0.0.1,{},Disallow: /private/\n", listed)), "{}", csv);
+    assert!(csv.contains(&format!("conflict,127.0.0.1,{},Disallow: /private/\n", listed)), "{}", csv);
+}
+
+#[tokio::test]
+async fn robots_txt_is_not_fetched_or_reported_when_it_is_not_respected() {
+    let server = start_server().await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).respect_robots_txt(false).build().unwrap();
+    let results = crawler.crawl_sitemap_only(server.url("/sitemap.xml")).await;
+    let report = crawler.export_robots_compliance_report(&results);
+
+    // The disallowed page is crawled, but without robots.txt fetched it isn't known to be disallowed
+    assert_eq!(results.len(), 2);
+    assert!(!server.request_lines().contains(&String::from("GET /robots.txt")), "{:?}", server.request_lines());
+    assert!(report.targets.is_empty());
+    assert!(report.skipped_urls.is_empty());
+    assert!(report.disallowed_crawled.is_empty());
+    assert!(report.conflicts.is_empty());
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use cherna_vdovitsa::crawler::{
+    crawl_target::{CrawlTarget, PreferredScheme, TargetConfig},
+    crawler_builder::CrawlerBuilder,
+    proxy::ProxyConfig,
+};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn the_main_page_choosing_the_scheme_is_requested_once() {
+    // The mock server is the proxy, refusing to tunnel HTTPS to the target, which is then crawled over HTTP
+    let proxy = MockServer::start(vec![
+        ("http://example.com/", MockResponse::html("<a href=\"/about\">About</a>")),
+        ("http://example.com/about", MockResponse::html("<p>About</p>"))
+    ]).await;
+
+    let target = CrawlTarget::try_from(TargetConfig {
+        host: String::from("example.com"),
+        scheme: PreferredScheme::Auto,
+        path_prefix: None,
+        auth: None,
+        max_depth: None,
+        max_pages: None,
+        priority: 0
+    }).unwrap();
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .add_target(target)
+        .proxy(ProxyConfig::new(proxy.url("/")))
+        .build()
+        .unwrap();
+
+    crawler.crawl().await.unwrap();
+
+    // Probing the scheme with a HEAD request took two requests of the main page, which its GET request now does alone
+    let requests = proxy.request_lines();
+    let main_page_requests = requests.iter().filter(|request| request.ends_with(" http://example.com/")).count();
+    assert_eq!(main_page_requests, 1, "{:?}", requests);
+    assert!(requests.iter().all(|request| !request.starts_with("HEAD")), "{:?}", requests);
+
+    // The links of the main page are followed from that response
+    assert!(requests.contains(&String::from("GET http://example.com/about")), "{:?}", requests);
+}
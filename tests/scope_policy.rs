@@ -0,0 +1,47 @@
+mod common;
+
+use cherna_vdovitsa::crawler::{crawler_builder::CrawlerBuilder, proxy::ProxyConfig, scope::ScopePolicy};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+use url::Url;
+
+// The mock server is the crawler's proxy, so it receives the requests for every domain name, with the absolute URL as the path
+async fn crawl_example_com(scope_policy: ScopePolicy) -> MockServer {
+    let server = MockServer::start(vec![
+        ("http://example.com/", MockResponse::html(r#"
+            <a href="/about">About</a>
+            <a href="http://blog.example.com/post">Blog post</a>
+        "#)),
+        ("http://example.com/about", MockResponse::html("<p>About</p>")),
+        ("http://blog.example.com/post", MockResponse::html("<p>Blog post</p>"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let proxy = ProxyConfig::new(server.url("/"));
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .seed_url(Url::parse("http://example.com/").unwrap())
+        .scope_policy(scope_policy)
+        .configure(|config| config.proxy = Some(proxy))
+        .build()
+        .unwrap();
+    crawler.crawl().await.unwrap();
+
+    server
+}
+
+#[tokio::test]
+async fn same_host_only_does_not_crawl_subdomains() {
+    let server = crawl_example_com(ScopePolicy::SameHostOnly).await;
+
+    let requests = server.request_lines();
+    assert!(requests.contains(&String::from("GET http://example.com/about")), "{:?}", requests);
+    assert!(!requests.iter().any(|request| request.contains("blog.example.com")), "{:?}", requests);
+}
+
+#[tokio::test]
+async fn related_hosts_crawls_subdomains() {
+    let server = crawl_example_com(ScopePolicy::RelatedHosts).await;
+
+    let requests = server.request_lines();
+    assert!(requests.contains(&String::from("GET http://blog.example.com/post")), "{:?}", requests);
+}
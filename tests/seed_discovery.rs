@@ -0,0 +1,44 @@
+mod common;
+
+use cherna_vdovitsa::crawler::{crawler_config::CrawlerConfig, Crawler};
+use common::{MockResponse, MockServer};
+use tempfile::TempDir;
+
+/// The default seed discovery configuration, for crawling the mock server
+fn seed_discovery_config(db_dir: &TempDir) -> CrawlerConfig {
+    CrawlerConfig { db_path: db_dir.path().join("crawl.db"), allow_private_hosts: true, ..CrawlerConfig::seed_discovery() }
+}
+
+#[tokio::test]
+async fn pages_more_than_3_links_away_from_the_seeds_are_not_crawled() {
+    // The root page's links are the seeds, so /d4 is 3 links away from /d1 and /d5 is 4
+    let mut routes = vec![("/", MockResponse::html(r#"<a href="/d1">D1</a>"#))];
+    let pages: Vec<String> = (1..=4).map(|page| format!(r#"<a href="/d{}">D{}</a>"#, page + 1, page + 1)).collect();
+    for (page, html) in ["/d1", "/d2", "/d3", "/d4"].into_iter().zip(&pages) {
+        routes.push((page, MockResponse::html(html)));
+    }
+    routes.push(("/d5", MockResponse::html("<p>D5</p>")));
+    let server = MockServer::start(routes).await;
+
+    let db_dir = TempDir::new().unwrap();
+    Crawler::crawl_with_seed_discovery(server.url("/"), Some(seed_discovery_config(&db_dir))).await;
+
+    let requests = server.request_lines();
+    assert!(requests.contains(&String::from("GET /d4")), "{:?}", requests);
+    assert!(!requests.contains(&String::from("GET /d5")), "{:?}", requests);
+}
+
+#[tokio::test]
+async fn at_most_100_pages_of_a_discovered_target_are_crawled() {
+    let links: String = (0..150).map(|page| format!(r#"<a href="/page{}">Page {}</a>"#, page, page)).collect();
+    let mut routes = vec![(String::from("/"), MockResponse::html(&links))];
+    routes.extend((0..150).map(|page| (format!("/page{}", page), MockResponse::html("<p>Page</p>"))));
+    let server = MockServer::start(routes.iter().map(|(path, response)| (path.as_str(), response.clone())).collect()).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let results = Crawler::crawl_with_seed_discovery(server.url("/"), Some(seed_discovery_config(&db_dir))).await;
+
+    assert_eq!(results.len(), 100);
+    let page_requests = server.request_lines().iter().filter(|request| request.starts_with("GET /page")).count();
+    assert!(page_requests <= 100, "{}", page_requests);
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use std::{collections::HashSet, str::FromStr, time::Duration};
+
+use cherna_vdovitsa::crawler::{
+    crawl_target::{CrawlTarget, PreferredScheme},
+    crawler_builder::CrawlerBuilder,
+    proxy::ProxyConfig,
+    Crawler,
+};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+use url::Url;
+
+#[test]
+fn subdomain_variants_are_distinct_targets_covered_by_each_other() {
+    let example = CrawlTarget::from_str("example.com").unwrap();
+    let www_example = CrawlTarget::from_str("www.example.com").unwrap();
+    let blog_example = CrawlTarget::from_str("blog.example.com").unwrap();
+    let example_org = CrawlTarget::from_str("example.org").unwrap();
+
+    let targets: HashSet<CrawlTarget> = [example.clone(), www_example.clone(), blog_example.clone(), example.clone()].into_iter().collect();
+    assert_eq!(targets.len(), 3);
+
+    assert!(example.covers(&www_example));
+    assert!(www_example.covers(&blog_example));
+    assert!(!example.covers(&example_org));
+}
+
+#[test]
+fn a_targets_scheme_is_part_of_its_identity() {
+    let host = CrawlTarget::from_str("example.com").unwrap().host().clone();
+    let over_https = CrawlTarget::with_scheme(host.clone(), PreferredScheme::Https);
+    let over_http = CrawlTarget::with_scheme(host, PreferredScheme::Http);
+
+    assert_ne!(over_https, over_http);
+    assert!(!over_https.covers(&over_http));
+}
+
+#[tokio::test]
+async fn url_list_seeds_on_different_subdomains_stay_separate_targets() {
+    let crawler = Crawler::from_url_list([
+        Url::parse("https://docs.example.com/api/index.html").unwrap(),
+        Url::parse("https://blog.example.com/posts/first.html").unwrap()
+    ]).unwrap();
+
+    let checkpoint_dir = TempDir::new().unwrap();
+    let checkpoint_path = checkpoint_dir.path().join("checkpoint.json");
+    crawler.save_checkpoint(&checkpoint_path).await.unwrap();
+
+    let checkpoint: serde_json::Value = serde_json::from_slice(&std::fs::read(&checkpoint_path).unwrap()).unwrap();
+    let mut targets: Vec<(String, String)> = checkpoint["targets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|target| (target["host"].to_string(), target["path_prefix"].as_str().unwrap().to_owned()))
+        .collect();
+    targets.sort();
+
+    assert_eq!(targets.len(), 2, "{:?}", targets);
+    assert!(targets[0].0.contains("blog") && targets[0].1 == "/posts/", "{:?}", targets);
+    assert!(targets[1].0.contains("docs") && targets[1].1 == "/api/", "{:?}", targets);
+}
+
+#[tokio::test]
+async fn a_target_on_the_registered_domain_of_one_being_crawled_is_covered_by_it() {
+    // The mock server is the proxy, so it serves both domain names. The slow page keeps the crawl running.
+    let proxy = MockServer::start(vec![
+        ("http://example.com/", MockResponse::html("<p>Slow</p>").with_delay(Duration::from_millis(500))),
+        ("http://other.com/", MockResponse::html("<p>Other</p>"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .seed_url(Url::parse("http://example.com/").unwrap())
+        .proxy(ProxyConfig::new(proxy.url("/")))
+        .build()
+        .unwrap();
+
+    let add_targets = async {
+        let sender = loop {
+            if let Some(sender) = crawler.target_sender() { break sender; }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+
+        let www_example = CrawlTarget::from_str("www.example.com").unwrap().host().clone();
+        sender.send(CrawlTarget::new(www_example)).await.unwrap();
+        let other = CrawlTarget::from_str("other.com").unwrap().host().clone();
+        sender.send(CrawlTarget::with_scheme(other, PreferredScheme::Http)).await.unwrap();
+    };
+
+    let (report, _) = tokio::join!(crawler.crawl(), add_targets);
+    report.unwrap();
+
+    let requests = proxy.request_lines();
+    assert!(requests.contains(&String::from("GET http://other.com/")), "{:?}", requests);
+    assert!(!requests.iter().any(|request| request.contains("www.example.com")), "{:?}", requests);
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use cherna_vdovitsa::crawler::{
+    crawl_event::CrawlEvent,
+    crawl_target::{CrawlTarget, PreferredScheme, TargetConfig},
+    crawler_builder::CrawlerBuilder,
+    proxy::ProxyConfig,
+};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+// The mock server is the proxy, so it serves the targets' domain names
+async fn start_proxy() -> MockServer {
+    MockServer::start(vec![
+        ("http://blog.com/", MockResponse::html(r#"<a href="/one-hop">One hop</a>"#)),
+        ("http://blog.com/one-hop", MockResponse::html(r#"<a href="/two-hops">Two hops</a>"#)),
+        ("http://blog.com/two-hops", MockResponse::html("<p>Two hops</p>")),
+        ("http://shop.com/", MockResponse::html(r#"<a href="/a">A</a> <a href="/b">B</a> <a href="/c">C</a> <a href="/d">D</a>"#)),
+        ("http://shop.com/a", MockResponse::html("<p>A</p>")),
+        ("http://shop.com/b", MockResponse::html("<p>B</p>")),
+        ("http://shop.com/c", MockResponse::html("<p>C</p>")),
+        ("http://shop.com/d", MockResponse::html("<p>D</p>"))
+    ]).await
+}
+
+fn target(host: &str, max_depth: Option<usize>, max_pages: Option<usize>) -> CrawlTarget {
+    CrawlTarget::try_from(TargetConfig {
+        host: host.to_owned(),
+        scheme: PreferredScheme::Http,
+        path_prefix: None,
+        auth: None,
+        max_depth,
+        max_pages,
+        priority: 0
+    }).unwrap()
+}
+
+// The pages requested on the host, without its robots.txt and sitemap
+fn page_requests(proxy: &MockServer, host: &str) -> Vec<String> {
+    proxy.request_lines()
+        .into_iter()
+        .filter(|request| request.starts_with(&format!("GET http://{}/", host)))
+        .filter(|request| !request.ends_with("/robots.txt") && !request.ends_with("/sitemap.xml"))
+        .collect()
+}
+
+#[tokio::test]
+async fn a_targets_max_depth_limits_only_that_target() {
+    let proxy = start_proxy().await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .add_target(target("blog.com", Some(1), None))
+        .add_target(target("shop.com", None, None))
+        .proxy(ProxyConfig::new(proxy.url("/")))
+        .build()
+        .unwrap();
+    crawler.crawl().await.unwrap();
+
+    let blog_requests = page_requests(&proxy, "blog.com");
+    assert!(blog_requests.contains(&String::from("GET http://blog.com/one-hop")), "{:?}", blog_requests);
+    assert!(!blog_requests.contains(&String::from("GET http://blog.com/two-hops")), "{:?}", blog_requests);
+    assert_eq!(page_requests(&proxy, "shop.com").len(), 5);
+}
+
+#[tokio::test]
+async fn a_more_restrictive_target_max_depth_overrides_the_crawlers() {
+    let proxy = start_proxy().await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .add_target(target("blog.com", Some(0), None))
+        .max_depth(5)
+        .proxy(ProxyConfig::new(proxy.url("/")))
+        .build()
+        .unwrap();
+    crawler.crawl().await.unwrap();
+
+    assert_eq!(page_requests(&proxy, "blog.com"), vec![String::from("GET http://blog.com/")]);
+}
+
+#[tokio::test]
+async fn a_targets_max_pages_limits_only_that_target() {
+    let proxy = start_proxy().await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .add_target(target("shop.com", None, Some(2)))
+        .add_target(target("blog.com", None, None))
+        .proxy(ProxyConfig::new(proxy.url("/")))
+        .configure(|config| config.event_channel_capacity = 1024)
+        .build()
+        .unwrap();
+
+    let mut events = crawler.subscribe();
+    crawler.crawl().await.unwrap();
+
+    assert_eq!(page_requests(&proxy, "shop.com").len(), 2, "{:?}", page_requests(&proxy, "shop.com"));
+    assert_eq!(page_requests(&proxy, "blog.com").len(), 3);
+
+    let mut limits_reached = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let CrawlEvent::TargetLimitReached(host) = event { limits_reached.push(host.to_string()); }
+    }
+    assert_eq!(limits_reached, vec![String::from("shop.com")]);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use cherna_vdovitsa::crawler::{
+    crawl_event::CrawlEvent,
+    crawl_target::{CrawlTarget, PreferredScheme, TargetConfig},
+    crawler_builder::CrawlerBuilder,
+    proxy::ProxyConfig,
+};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+fn target(host: &str, priority: i32) -> CrawlTarget {
+    CrawlTarget::try_from(TargetConfig {
+        host: host.to_owned(),
+        scheme: PreferredScheme::Http,
+        path_prefix: None,
+        auth: None,
+        max_depth: None,
+        max_pages: None,
+        priority
+    }).unwrap()
+}
+
+#[tokio::test]
+async fn a_higher_priority_target_starts_first_with_one_task() {
+    // The mock server is the proxy, so it serves the targets' domain names
+    let proxy = MockServer::start(vec![
+        ("http://low.com/", MockResponse::html("<p>Low</p>")),
+        ("http://high.com/", MockResponse::html("<p>High</p>"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .add_target(target("low.com", 0))
+        .add_target(target("high.com", 10))
+        .max_concurrent_tasks(1)
+        .proxy(ProxyConfig::new(proxy.url("/")))
+        .configure(|config| config.event_channel_capacity = 1024)
+        .build()
+        .unwrap();
+
+    let mut events = crawler.subscribe();
+    crawler.crawl().await.unwrap();
+
+    let mut started = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let CrawlEvent::TargetStarted(host) = event { started.push(host.to_string()); }
+    }
+    assert_eq!(started, vec![String::from("high.com"), String::from("low.com")]);
+
+    let page_requests: Vec<String> = proxy.request_lines().into_iter().filter(|request| request.ends_with(".com/")).collect();
+    assert_eq!(page_requests, vec![String::from("GET http://high.com/"), String::from("GET http://low.com/")]);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use std::time::Duration;
+
+use cherna_vdovitsa::crawler::{
+    crawl_target::{CrawlTarget, PreferredScheme, TargetConfig},
+    crawler_builder::CrawlerBuilder,
+    proxy::ProxyConfig,
+};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+fn target(host: &str) -> CrawlTarget {
+    CrawlTarget::try_from(TargetConfig {
+        host: host.to_owned(),
+        scheme: PreferredScheme::Http,
+        path_prefix: None,
+        auth: None,
+        max_depth: None,
+        max_pages: None,
+        priority: 0
+    }).unwrap()
+}
+
+/// Crawl a.com, with many pages, and b.com, whose slow main page links one more, two pages at a time, returning the pages requested in order
+async fn crawl_pages(rotate_targets: bool) -> Vec<String> {
+    let a_links: String = (1..=8).map(|page| format!("<a href=\"/a{}\">A{}</a>", page, page)).collect();
+    let mut routes = vec![
+        ("http://a.com/".to_owned(), MockResponse::html(&a_links)),
+        ("http://b.com/".to_owned(), MockResponse::html("<a href=\"/b1\">B1</a>").with_delay(Duration::from_millis(50))),
+        ("http://b.com/b1".to_owned(), MockResponse::html("<p>B1</p>"))
+    ];
+    for page in 1..=8 {
+        routes.push((format!("http://a.com/a{}", page), MockResponse::html("<p>A</p>").with_delay(Duration::from_millis(20))));
+    }
+
+    // The mock server is the proxy, so it serves the targets' domain names
+    let proxy = MockServer::start(routes.iter().map(|(path, response)| (path.as_str(), response.clone())).collect()).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .add_target(target("a.com"))
+        .add_target(target("b.com"))
+        .max_concurrent_tasks(2)
+        .rotate_targets(rotate_targets)
+        .proxy(ProxyConfig::new(proxy.url("/")))
+        .build()
+        .unwrap();
+
+    crawler.crawl().await.unwrap();
+
+    proxy.request_lines().into_iter().filter(|request| !request.ends_with("robots.txt") && !request.contains("sitemap")).collect()
+}
+
+#[tokio::test]
+async fn rotating_targets_lets_a_target_with_few_pages_take_turns() {
+    let requests = crawl_pages(true).await;
+
+    // b.com's page is requested as soon as a page of a.com is done, rather than after all of a.com's queued pages
+    assert_ne!(requests.last().map(String::as_str), Some("GET http://b.com/b1"), "{:?}", requests);
+}
+
+#[tokio::test]
+async fn without_rotating_targets_pages_are_requested_in_the_order_they_are_queued() {
+    let requests = crawl_pages(false).await;
+
+    assert_eq!(requests.last().map(String::as_str), Some("GET http://b.com/b1"), "{:?}", requests);
+}
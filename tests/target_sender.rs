@@ -0,0 +1,44 @@
+mod common;
+
+use std::time::Duration;
+
+use cherna_vdovitsa::{
+    crawler::{crawl_target::{CrawlTarget, PreferredScheme}, crawler_builder::CrawlerBuilder, proxy::ProxyConfig},
+    web::host::Host,
+};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+use url::Url;
+
+#[tokio::test]
+async fn a_target_sent_during_the_crawl_is_crawled() {
+    // The mock server is the proxy, so both domain names are served by it. The slow page keeps the crawl running.
+    let proxy = MockServer::start(vec![
+        ("http://example.com/", MockResponse::html("<p>Slow</p>").with_delay(Duration::from_millis(500))),
+        ("http://added.com/", MockResponse::html("<p>Added</p>"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir))
+        .seed_url(Url::parse("http://example.com/").unwrap())
+        .proxy(ProxyConfig::new(proxy.url("/")))
+        .build()
+        .unwrap();
+    assert!(crawler.target_sender().is_none());
+
+    let add_target = async {
+        let sender = loop {
+            if let Some(sender) = crawler.target_sender() { break sender; }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+
+        let host = Host::try_from(url::Host::Domain("added.com")).unwrap();
+        sender.send(CrawlTarget::with_scheme(host, PreferredScheme::Http)).await.unwrap();
+    };
+
+    let (report, _) = tokio::join!(crawler.crawl(), add_target);
+    report.unwrap();
+
+    assert!(proxy.request_lines().contains(&String::from("GET http://added.com/")), "{:?}", proxy.request_lines());
+    assert!(crawler.target_sender().is_none());
+}
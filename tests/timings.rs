@@ -0,0 +1,39 @@
+mod common;
+
+use std::collections::HashMap;
+
+use cherna_vdovitsa::crawler::crawler_builder::CrawlerBuilder;
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+async fn start_server() -> MockServer {
+    MockServer::start(vec![
+        ("/", MockResponse::html(r#"<a href="/one">One</a> <a href="/two">Two</a>"#)),
+        ("/one", MockResponse::html(r#"<a href="/">Home</a>"#)),
+        ("/two", MockResponse::html("<p>Two</p>"))
+    ]).await
+}
+
+#[tokio::test]
+async fn every_crawled_page_is_timed_when_timing_is_enabled() {
+    let server = start_server().await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).timing_enabled(true).build().unwrap();
+    let report = crawler.crawl().await.unwrap();
+
+    let link_counts: HashMap<String, usize> = report.timings.iter().map(|timing| (timing.url.path().to_owned(), timing.link_count)).collect();
+    assert_eq!(link_counts, HashMap::from([(String::from("/"), 2), (String::from("/one"), 1), (String::from("/two"), 0)]));
+    assert!(report.timings.iter().all(|timing| timing.status == 200), "{:?}", report.timings);
+}
+
+#[tokio::test]
+async fn pages_are_not_timed_by_default() {
+    let server = start_server().await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap();
+    let report = crawler.crawl().await.unwrap();
+
+    assert!(report.timings.is_empty());
+}
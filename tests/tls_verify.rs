@@ -0,0 +1,32 @@
+mod common;
+
+use cherna_vdovitsa::{crawler::{crawler_builder::CrawlerBuilder, crawler_config::CrawlerConfig}, web::http::RetryPolicy};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn a_self_signed_server_is_not_crawled_when_verifying_certificates() {
+    let server = MockServer::start_tls(vec![("/", MockResponse::html("<p>Self-signed</p>"))]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    // Without retrying, as the handshake fails the same way every time
+    let config = CrawlerConfig { retry_policy: RetryPolicy { max_attempts: 1, ..RetryPolicy::default() }, ..test_config(&db_dir) };
+    let crawler = CrawlerBuilder::from_config(config).seed_url(server.url("/")).tls_verify(true).build().unwrap();
+    let report = crawler.crawl().await.unwrap();
+
+    // The handshake fails, so the server never receives the request
+    assert!(report.errors.iter().any(|error| error.url == server.url("/")), "{:?}", report.errors);
+    assert!(server.requests().is_empty(), "{:?}", server.request_lines());
+}
+
+#[tokio::test]
+async fn a_self_signed_server_is_crawled_without_verifying_certificates() {
+    let server = MockServer::start_tls(vec![("/", MockResponse::html("<p>Self-signed</p>"))]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).tls_verify(false).build().unwrap();
+    let report = crawler.crawl().await.unwrap();
+
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert!(server.request_lines().contains(&String::from("GET /")), "{:?}", server.request_lines());
+}
@@ -0,0 +1,40 @@
+//! Compile-time checks that the public types can be shared across threads, so that adding a field which can't be fails the build
+
+use static_assertions::assert_impl_all;
+
+use cherna_vdovitsa::{
+    crawler::{
+        crawl_error::CrawlError,
+        crawl_event::CrawlEvent,
+        crawl_report::CrawlReport,
+        crawl_result::CrawlResult,
+        crawl_target::{CrawlTarget, TargetConfig},
+        crawler_builder::{CrawlerBuilder, VdovitsaBuilder},
+        crawler_config::CrawlerConfig,
+        crawler_pool::{CrawlerPool, JobHandle},
+        link::{Link, LinkSelectors},
+        Crawler,
+        CrawlerError,
+    },
+    dns::{dns_cache::DnsCache, domain_name::DomainName},
+    web::host::Host,
+};
+
+assert_impl_all!(Crawler: Send, Sync);
+assert_impl_all!(CrawlerBuilder: Send, Sync);
+assert_impl_all!(VdovitsaBuilder: Send, Sync);
+assert_impl_all!(CrawlerConfig: Send, Sync);
+assert_impl_all!(CrawlerPool: Send, Sync);
+assert_impl_all!(JobHandle: Send, Sync);
+assert_impl_all!(CrawlTarget: Send, Sync);
+assert_impl_all!(TargetConfig: Send, Sync);
+assert_impl_all!(CrawlerError: Send, Sync);
+assert_impl_all!(CrawlResult: Send, Sync);
+assert_impl_all!(CrawlReport: Send, Sync);
+assert_impl_all!(CrawlError: Send, Sync);
+assert_impl_all!(CrawlEvent: Send, Sync);
+assert_impl_all!(Link: Send, Sync);
+assert_impl_all!(LinkSelectors: Send, Sync);
+assert_impl_all!(Host: Send, Sync);
+assert_impl_all!(DomainName: Send, Sync);
+assert_impl_all!(DnsCache: Send, Sync);
@@ -0,0 +1,39 @@
+mod common;
+
+use cherna_vdovitsa::crawler::{crawler_config::CrawlerConfig, proxy::ProxyConfig, Crawler};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+use url::Url;
+
+#[tokio::test]
+async fn seeds_on_different_hosts_are_crawled_independently() {
+    // The mock server is the proxy, so it serves both domain names
+    let proxy = MockServer::start(vec![
+        ("http://first.com/docs/index.html", MockResponse::html(r#"
+            <a href="/docs/guide.html">Guide</a>
+            <a href="/pricing.html">Pricing</a>
+            <a href="http://second.com/unlisted.html">Unlisted</a>
+        "#)),
+        ("http://first.com/docs/guide.html", MockResponse::html("<p>Guide</p>")),
+        ("http://second.com/blog/post.html", MockResponse::html(r#"<a href="http://third.com/">Third</a>"#))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let config = CrawlerConfig { proxy: Some(ProxyConfig::new(proxy.url("/"))), ..test_config(&db_dir) };
+    let crawler = Crawler::from_url_list_with_config([
+        Url::parse("http://first.com/docs/index.html").unwrap(),
+        Url::parse("http://second.com/blog/post.html").unwrap()
+    ], config).unwrap();
+    crawler.crawl().await.unwrap();
+
+    let requests = proxy.request_lines();
+    assert!(requests.contains(&String::from("GET http://first.com/docs/index.html")), "{:?}", requests);
+    assert!(requests.contains(&String::from("GET http://first.com/docs/guide.html")), "{:?}", requests);
+    assert!(requests.contains(&String::from("GET http://second.com/blog/post.html")), "{:?}", requests);
+
+    // Neither the links to other hosts nor those outside the seeds' directories are followed, and the main pages aren't crawled
+    assert!(!requests.contains(&String::from("GET http://second.com/unlisted.html")), "{:?}", requests);
+    assert!(!requests.iter().any(|request| request.contains("third.com")), "{:?}", requests);
+    assert!(!requests.contains(&String::from("GET http://first.com/pricing.html")), "{:?}", requests);
+    assert!(!requests.contains(&String::from("GET http://first.com/")), "{:?}", requests);
+}
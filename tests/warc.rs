@@ -0,0 +1,45 @@
+#![cfg(feature = "warc")]
+
+mod common;
+
+use std::{sync::Arc, time::{Duration, SystemTime}};
+
+use cherna_vdovitsa::crawler::{crawler_builder::CrawlerBuilder, Crawler};
+use common::{test_config, MockResponse, MockServer};
+use tempfile::TempDir;
+
+/// Returns whether the bytes contain the other bytes
+fn contains(bytes: &[u8], other: &[u8]) -> bool {
+    bytes.windows(other.len()).any(|window| window == other)
+}
+
+#[tokio::test]
+async fn records_hold_the_fetch_time_and_the_body_as_received() {
+    // The body is Latin-1, which is decoded to different bytes as text
+    let body = b"<p>Caf\xe9</p>";
+    let server = MockServer::start(vec![
+        ("/", MockResponse::new(200, body).with_header("Content-Type", "text/html; charset=iso-8859-1"))
+    ]).await;
+
+    let db_dir = TempDir::new().unwrap();
+    let crawler = CrawlerBuilder::from_config(test_config(&db_dir)).seed_url(server.url("/")).build().unwrap();
+
+    let mut results = Vec::new();
+    let mut stream = Arc::new(crawler).crawl_incremental_stream(Vec::new(), false);
+    while let Some(result) = stream.recv().await {
+        results.push(result);
+    }
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].body_bytes, body);
+    assert_eq!(results[0].response_body, "<p>Café</p>");
+
+    // Date the page as if it had been fetched long before the export
+    results[0].fetched_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_445_412_480);
+
+    let mut warc = Vec::new();
+    Crawler::export_warc(&results, &mut warc).unwrap();
+
+    assert!(contains(&warc, b"WARC-Date: 2015-10-21T07:28:00Z\r\n"), "{}", String::from_utf8_lossy(&warc));
+    assert!(contains(&warc, format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes()), "{}", String::from_utf8_lossy(&warc));
+    assert!(contains(&warc, body), "{}", String::from_utf8_lossy(&warc));
+}